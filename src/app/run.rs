@@ -1,25 +1,38 @@
 use super::super::{
     args::{Args, Command},
     cmd::{
-        build, copyright, deref_db, init_aur_builder, outdated, patch_makepkg, print_config, sort,
-        sync_srcinfo,
+        build, copyright, deref_db, init_aur_builder, list, outdated, patch_makepkg, print_config,
+        prune, remove_orphans, sort, sync_srcinfo, verify_repo,
     },
+    manifest::ManifestOptions,
     status::Status,
 };
 use super::App;
 
 impl App {
     pub fn run(self) -> Status {
-        let Args { command } = self.args;
+        let Args {
+            manifest,
+            overrides,
+            command,
+        } = self.args;
+        let manifest = ManifestOptions {
+            file: manifest.as_deref(),
+            overrides: &overrides,
+        };
         match command {
             Command::PrintConfig(args) => print_config(args),
-            Command::InitAurBuilder(args) => init_aur_builder(args),
-            Command::Sort(args) => sort(args),
-            Command::Outdated(args) => outdated(args),
-            Command::SyncSrcInfo(args) => sync_srcinfo(args),
+            Command::InitAurBuilder(args) => init_aur_builder(args, manifest),
+            Command::Sort(args) => sort(args, manifest),
+            Command::Outdated(args) => outdated(args, manifest),
+            Command::List(args) => list(args, manifest),
+            Command::SyncSrcInfo(args) => sync_srcinfo(args, manifest),
             Command::PatchMakepkg(args) => patch_makepkg(args),
-            Command::DerefDb(args) => deref_db(args),
-            Command::Build(args) => build(args),
+            Command::DerefDb(args) => deref_db(args, manifest),
+            Command::Prune(args) => prune(args, manifest),
+            Command::RemoveOrphans(args) => remove_orphans(args, manifest),
+            Command::VerifyRepo(args) => verify_repo(args, manifest),
+            Command::Build(args) => build(args, manifest),
             Command::Copyright(args) => copyright(args),
         }
     }
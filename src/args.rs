@@ -2,31 +2,56 @@ mod build;
 mod copyright;
 mod deref_db;
 mod init_aur_builder;
+mod list;
 mod outdated;
 mod patch_makepkg;
 mod print_config;
+mod prune;
+mod remove_orphans;
 mod sort;
 mod sync_srcinfo;
+mod verify_repo;
 
-pub use build::BuildArgs;
+pub use build::{BuildArgs, PlanFormat, ReportFormat};
 pub use copyright::CopyrightArgs;
 pub use deref_db::DerefDbArgs;
 pub use init_aur_builder::InitAurBuilderArgs;
+pub use list::{ListArgs, ListFormat};
 pub use outdated::{OutdatedArgs, OutdatedDetails};
 pub use patch_makepkg::PatchMakepkgArgs;
 pub use print_config::PrintConfigArgs;
+pub use prune::PruneArgs;
+pub use remove_orphans::RemoveOrphansArgs;
 pub use sort::SortArgs;
 pub use sync_srcinfo::SyncSrcInfoArgs;
+pub use verify_repo::VerifyRepoArgs;
 
+use super::manifest::SettingOverride;
 use argh::*;
+use std::path::PathBuf;
 
 #[derive(Debug, FromArgs)]
 #[argh(description = "Build a custom pacman repository from a collection of PKGBUILD directories")]
 pub struct Args {
+    #[argh(
+        option,
+        description = "path to the manifest file, instead of searching for build-pacman-repo.yaml in the current directory and its parents"
+    )]
+    pub manifest: Option<PathBuf>,
+
+    #[argh(
+        option,
+        long = "set",
+        description = "override a global setting of the manifest, in the form of key=value where value is YAML"
+    )]
+    pub overrides: Vec<SettingOverride>,
+
     #[argh(subcommand)]
     pub command: Command,
 }
 
+// NOTE: argh cannot parse boxed subcommands, and only one Command is ever created
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, FromArgs)]
 #[argh(subcommand)]
 pub enum Command {
@@ -34,9 +59,13 @@ pub enum Command {
     PrintConfig(PrintConfigArgs),
     InitAurBuilder(InitAurBuilderArgs),
     Outdated(OutdatedArgs),
+    List(ListArgs),
     SyncSrcInfo(SyncSrcInfoArgs),
     PatchMakepkg(PatchMakepkgArgs),
     DerefDb(DerefDbArgs),
+    Prune(PruneArgs),
+    RemoveOrphans(RemoveOrphansArgs),
+    VerifyRepo(VerifyRepoArgs),
     Build(BuildArgs),
     Copyright(CopyrightArgs),
 }
@@ -1,5 +1,89 @@
 use argh::*;
+use std::{num::NonZeroUsize, path::PathBuf, str::FromStr};
 
 #[derive(Debug, FromArgs)]
 #[argh(subcommand, name = "build", description = "Build a pacman repository")]
-pub struct BuildArgs {}
+pub struct BuildArgs {
+    #[argh(
+        option,
+        short = 'j',
+        default = "NonZeroUsize::new(1).unwrap()",
+        description = "number of packages to build at the same time, packages that require removing conflicts fail unless it is 1"
+    )]
+    pub jobs: NonZeroUsize,
+
+    #[argh(
+        option,
+        description = "name of a profile of the manifest to apply on top of the global settings"
+    )]
+    pub profile: Option<String>,
+
+    #[argh(
+        switch,
+        description = "print what would be done for each package without executing anything"
+    )]
+    pub dry_run: bool,
+
+    #[argh(
+        option,
+        description = "format of the plan printed by --dry-run (text|yaml)"
+    )]
+    pub plan_format: Option<PlanFormat>,
+
+    #[argh(
+        option,
+        default = "20",
+        description = "number of lines at the end of the log of each failed build to show in the summary"
+    )]
+    pub log_tail: usize,
+
+    #[argh(
+        option,
+        description = "write the outcome of every package to this file"
+    )]
+    pub report: Option<PathBuf>,
+
+    #[argh(
+        option,
+        description = "format of the file written by --report (json|junit)"
+    )]
+    pub report_format: Option<ReportFormat>,
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub enum PlanFormat {
+    #[default]
+    Text,
+    Yaml,
+}
+
+impl FromStr for PlanFormat {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Ok(match text {
+            "text" => PlanFormat::Text,
+            "yaml" => PlanFormat::Yaml,
+            _ => return Err(format!("invalid choice: {}", text)),
+        })
+    }
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Junit,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Ok(match text {
+            "json" => ReportFormat::Json,
+            "junit" => ReportFormat::Junit,
+            _ => return Err(format!("invalid choice: {}", text)),
+        })
+    }
+}
@@ -0,0 +1,40 @@
+use argh::*;
+use std::{path::PathBuf, str::FromStr};
+
+#[derive(Debug, FromArgs)]
+#[argh(
+    subcommand,
+    name = "list",
+    description = "List the packages in the repository database"
+)]
+pub struct ListArgs {
+    #[argh(option, description = "output format (text|yaml|json)")]
+    pub format: Option<ListFormat>,
+
+    #[argh(
+        option,
+        description = "database to read instead of the repository in the manifest"
+    )]
+    pub repository: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub enum ListFormat {
+    #[default]
+    Text,
+    Yaml,
+    Json,
+}
+
+impl FromStr for ListFormat {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Ok(match text {
+            "text" => ListFormat::Text,
+            "yaml" => ListFormat::Yaml,
+            "json" => ListFormat::Json,
+            _ => return Err(format!("invalid choice: {}", text)),
+        })
+    }
+}
@@ -9,6 +9,12 @@ pub struct OutdatedArgs {
         description = "level of details of information (pkgname|pkg-file-path|lossy-yaml|strict-yaml)"
     )]
     pub details: Option<OutdatedDetails>,
+
+    #[argh(
+        option,
+        description = "name of a profile of the manifest to apply on top of the global settings"
+    )]
+    pub profile: Option<String>,
 }
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
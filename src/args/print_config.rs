@@ -1,6 +1,6 @@
 use super::super::manifest::TriState;
 use argh::*;
-use std::path::PathBuf;
+use std::{num::NonZeroUsize, path::PathBuf};
 
 #[derive(Debug, FromArgs)]
 #[argh(
@@ -55,4 +55,18 @@ pub struct PrintConfigArgs {
     pub with_allow_failure: Option<bool>,
     #[argh(option, description = "set dereference-database-symlinks")]
     pub with_dereference_database_symlinks: Option<bool>,
+    #[argh(option, description = "set log-directory")]
+    pub with_log_directory: Option<PathBuf>,
+    #[argh(option, description = "set sign-packages")]
+    pub with_sign_packages: Option<bool>,
+    #[argh(option, description = "set sign-database")]
+    pub with_sign_database: Option<bool>,
+    #[argh(option, description = "set signing-key")]
+    pub with_signing_key: Option<String>,
+    #[argh(option, description = "set keep-versions")]
+    pub with_keep_versions: Option<NonZeroUsize>,
+    #[argh(option, description = "add to protected-packages")]
+    pub with_protected_packages: Vec<String>,
+    #[argh(option, description = "set package-extension")]
+    pub with_package_extension: Option<String>,
 }
@@ -0,0 +1,19 @@
+use argh::*;
+use std::num::NonZeroUsize;
+
+#[derive(Debug, FromArgs)]
+#[argh(
+    subcommand,
+    name = "prune",
+    description = "Delete package files that newer versions in the repository have superseded"
+)]
+pub struct PruneArgs {
+    #[argh(
+        option,
+        description = "number of versions of each package to keep, including the current one (default: keep-versions or 1)"
+    )]
+    pub keep_versions: Option<NonZeroUsize>,
+
+    #[argh(switch, description = "only list the files that would be deleted")]
+    pub dry_run: bool,
+}
@@ -0,0 +1,12 @@
+use argh::*;
+
+#[derive(Debug, FromArgs)]
+#[argh(
+    subcommand,
+    name = "remove-orphans",
+    description = "Remove packages that no member of the manifest produces from the repository"
+)]
+pub struct RemoveOrphansArgs {
+    #[argh(switch, description = "only list the packages that would be removed")]
+    pub dry_run: bool,
+}
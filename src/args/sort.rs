@@ -6,4 +6,16 @@ use argh::*;
     name = "sort",
     description = "List packages in build order"
 )]
-pub struct SortArgs {}
+pub struct SortArgs {
+    #[argh(
+        option,
+        description = "only consider architecture-specific dependencies of this architecture"
+    )]
+    pub arch: Option<String>,
+
+    #[argh(
+        option,
+        description = "name of a profile of the manifest to apply on top of the global settings"
+    )]
+    pub profile: Option<String>,
+}
@@ -0,0 +1,9 @@
+use argh::*;
+
+#[derive(Debug, FromArgs)]
+#[argh(
+    subcommand,
+    name = "verify-repo",
+    description = "Check that the repository database agrees with the files in the repository"
+)]
+pub struct VerifyRepoArgs {}
@@ -2,18 +2,26 @@ mod build;
 mod copyright;
 mod deref_db;
 mod init_aur_builder;
+mod list;
 mod outdated;
 mod patch_makepkg;
 mod print_config;
+mod prune;
+mod remove_orphans;
 mod sort;
 mod sync_srcinfo;
+mod verify_repo;
 
 pub use build::build;
 pub use copyright::copyright;
 pub use deref_db::deref_db;
 pub use init_aur_builder::init_aur_builder;
+pub use list::list;
 pub use outdated::outdated;
 pub use patch_makepkg::patch_makepkg;
 pub use print_config::print_config;
+pub use prune::prune;
+pub use remove_orphans::remove_orphans;
 pub use sort::sort;
 pub use sync_srcinfo::sync_srcinfo;
+pub use verify_repo::verify_repo;
@@ -1,29 +1,62 @@
 use super::super::{
     args::BuildArgs,
-    manifest::{GlobalSettings, Member, TriState},
-    srcinfo::database::DatabaseValue,
+    manifest::{GlobalSettings, ManifestOptions, Member, OwnedArchFilter, OwnedMember, TriState},
+    repository_database::{
+        journal::Journal, package_entry::PackageEntry, Error as DatabaseError, RepositoryDatabase,
+    },
+    srcinfo::{
+        database::{text_wrapper::PkgBase, DatabaseValue, SimpleDatabase, SimpleDatabaseValue},
+        parsed::ParsedSrcInfo,
+    },
     status::{status_of_code, Code, Failure, Status},
     utils::{
-        create_makepkg_command, load_failed_build_record, run_deref_db, CommandUtils, DbInit,
-        DbInitValue, ExternalPackageList, InstallationPlan, PackageFileName,
+        create_makepkg_command, load_failed_build_record, log_file_path, prune_repository,
+        run_deref_db, run_in_dependency_order, signing_key_args, tail_lines, BuildLog,
+        CommandUtils, DbInit, DbInitValue, ExternalPackageList, FailedBuildRecordEntry,
+        InstallationPlan, PackageFileName, DEFAULT_PACKAGE_EXTENSION,
     },
 };
 use command_extra::CommandExtra;
+use indexmap::IndexMap;
 use itertools::Itertools;
 use pipe_trait::*;
 use reflink::reflink_or_copy;
 use std::{
+    env,
     ffi::OsString,
-    fs::{remove_file, write},
+    fs::{create_dir_all, remove_file, write},
     os::unix::prelude::{OsStrExt, OsStringExt},
-    path::Path,
+    path::{Path, PathBuf},
     process::{Command, Stdio},
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime},
 };
 
-pub fn build(args: BuildArgs) -> Status {
-    let BuildArgs {} = args;
+mod plan;
+mod report;
+
+use report::{write_report, ArchitectureReport, Outcome, PackageReport};
 
-    let mut db_init = DbInit::default();
+macro_rules! log {
+    ($log:expr, $($arguments:tt)*) => {
+        $log.message(format_args!($($arguments)*))
+    };
+}
+
+pub fn build(args: BuildArgs, manifest: ManifestOptions) -> Status {
+    let BuildArgs {
+        jobs,
+        profile,
+        dry_run,
+        plan_format,
+        log_tail,
+        report: report_path,
+        report_format,
+    } = args;
+
+    let mut db_init = DbInit::default()
+        .with_manifest(manifest)
+        .with_profile(profile.as_deref());
     let DbInitValue {
         database,
         error_count,
@@ -35,12 +68,25 @@ pub fn build(args: BuildArgs) -> Status {
         packager,
         dereference_database_symlinks,
         arch_filter,
+        log_directory,
+        sign_packages,
+        sign_database,
+        signing_key,
+        keep_versions,
+        package_extension,
         ..
     } = &manifest.global_settings;
     let packager: Option<&str> = packager.as_ref().map(AsRef::as_ref);
     let dereference_database_symlinks = dereference_database_symlinks.unwrap_or(false);
     let default_arch_filter = Default::default();
     let arch_filter = arch_filter.as_ref().unwrap_or(&default_arch_filter);
+    let log_directory: Option<&Path> = log_directory.as_ref().map(AsRef::as_ref);
+    let sign_packages = sign_packages.unwrap_or(false);
+    let sign_database = sign_database.unwrap_or(false);
+    let signing_key: Option<&str> = signing_key.as_ref().map(AsRef::as_ref);
+    let package_extension: &str = package_extension
+        .as_ref()
+        .map_or(DEFAULT_PACKAGE_EXTENSION, AsRef::as_ref);
 
     let failed_build_record = load_failed_build_record(record_failed_builds).map_err(|error| {
         eprintln!("⮾ {}", error);
@@ -52,8 +98,8 @@ pub fn build(args: BuildArgs) -> Status {
         return Code::GenericFailure.into();
     }
 
-    let build_order = match database.build_order() {
-        Ok(build_order) => build_order,
+    let build_order: Vec<_> = match database.build_order() {
+        Ok(build_order) => build_order.collect(),
         Err(error) => {
             eprintln!("⮾ {}", error);
             return error.code().into();
@@ -63,35 +109,402 @@ pub fn build(args: BuildArgs) -> Status {
     let repository = manifest.global_settings.repository.as_ref();
     let repository_directory = repository.parent().expect("get repository directory");
     let members: Vec<_> = manifest.resolve_members().collect();
+
+    if let (Some(log_directory), false) = (log_directory, dry_run) {
+        create_dir_all(log_directory).map_err(|error| {
+            eprintln!("⮾ Cannot create {:?}: {}", log_directory, error);
+            Failure::from(error)
+        })?;
+    }
+
+    let context = BuildContext {
+        database: &database,
+        members: &members,
+        repository,
+        repository_directory,
+        arch_filter,
+        packager,
+        log_directory,
+        sign_packages,
+        signing_key,
+        package_extension,
+        failed_build_record: &failed_build_record,
+        parallel: jobs.get() > 1,
+        built_package_files: Mutex::new(Vec::new()),
+        blocked: Mutex::new(IndexMap::new()),
+        pacman_lock: Mutex::new(()),
+        new_entries: Mutex::new(Vec::new()),
+        journal: Journal::new(repository),
+    };
+
+    if dry_run {
+        return plan::print_plan(&context, &build_order, plan_format.unwrap_or_default());
+    }
+
+    let (mut repository_database, mut database_changed) =
+        load_database(repository, repository_directory, &context.journal).map_err(|error| {
+            eprintln!("⮾ Cannot load {:?}: {}", repository, error);
+            Failure::from(error)
+        })?;
+
     let mut failed_builds = Vec::new();
-    let mut built_packages = ExternalPackageList::from_env();
+    let mut build_logs = Vec::new();
+    let mut reports = Vec::new();
+    let mut failure = None;
+    run_in_dependency_order(
+        &build_order,
+        |pkgbase| database.pkgbase()[pkgbase].dependencies.keys().collect(),
+        jobs,
+        |pkgbase| {
+            let mut log = BuildLog::new(context.parallel);
+            let mut report = PackageReport::new(pkgbase.0);
+            let start = Instant::now();
+            let result = context.build_package(pkgbase, &mut log, &mut report);
+            report.duration = start.elapsed();
+            (log, report, result)
+        },
+        |pkgbase, (mut log, report, result)| {
+            log.flush();
+            reports.push(report);
+            for file in log.files() {
+                build_logs.push((pkgbase, file.clone()));
+            }
+
+            // NOTE: the database is only written once every package is done,
+            //       the journal keeps the copied package files for the next build if this one is interrupted
+            let mut new_entries = context
+                .new_entries
+                .lock()
+                .expect("lock new database entries");
+            if !new_entries.is_empty() {
+                eprintln!("🛈 Adding package files to {}", repository.to_string_lossy());
+                for entry in new_entries.drain(..) {
+                    eprintln!("  → {}", entry.file_name);
+                    repository_database.add(&entry);
+                }
+                database_changed = true;
+            }
+
+            match result {
+                Ok(None) => true,
+                Ok(Some(failed_build)) => {
+                    if failed_build.blocked_by.is_none() {
+                        context.block_dependants(pkgbase);
+                    }
+                    failed_builds.push(failed_build);
+                    true
+                }
+                Err(error) => {
+                    failure = Some(error);
+                    false
+                }
+            }
+        },
+    );
+
+    if database_changed {
+        eprintln!("🛈 Writing {}", repository.to_string_lossy());
+        let written = repository_database
+            .write(repository, sign_database, signing_key)
+            .and_then(|()| context.journal.remove().map_err(DatabaseError::from));
+        if let Err(error) = written {
+            eprintln!("⮾ Cannot update {:?}: {}", repository, error);
+            failure.get_or_insert(Failure::from(error));
+        }
+    }
+
+    if let Some(report_path) = &report_path {
+        let report_format = report_format.unwrap_or_default();
+        if let Err(error) = write_report(report_path, report_format, &reports) {
+            eprintln!("⮾ Cannot write report to {:?}: {}", report_path, error);
+            failure.get_or_insert(Failure::from(error));
+        }
+    }
 
-    for pkgbase in build_order {
-        let DatabaseValue {
-            directory, srcinfo, ..
-        } = database.pkgbase().get(pkgbase).unwrap_or_else(|| {
+    if let Some(failure) = failure {
+        return Err(failure);
+    }
+
+    if let Some(keep_versions) = *keep_versions {
+        eprintln!();
+        eprintln!();
+        eprintln!("🛈 Pruning superseded package files");
+        prune_repository(repository, keep_versions, false).map_err(|error| {
+            eprintln!("⮾ {}", error);
+            Failure::from(error)
+        })?;
+    }
+
+    if dereference_database_symlinks {
+        eprintln!();
+        eprintln!();
+        eprintln!("🛈 Resolving all symlinks to repository database into real files");
+        run_deref_db(repository_directory).map_err(|error| {
+            eprintln!("⮾ {}", error);
+            Failure::from(error)
+        })?;
+    }
+
+    if !build_logs.is_empty() {
+        eprintln!();
+        eprintln!();
+        eprintln!("🛈 Build logs:");
+        for (pkgbase, file) in &build_logs {
+            eprintln!("  ● {}: {}", pkgbase, file.to_string_lossy());
+        }
+    }
+
+    let has_failed_builds = !failed_builds.is_empty();
+    let (blocked_builds, failed_builds): (Vec<_>, Vec<_>) = failed_builds
+        .into_iter()
+        .partition(|failed_build| failed_build.blocked_by.is_some());
+
+    if !failed_builds.is_empty() {
+        eprintln!();
+        eprintln!();
+        eprintln!("🛈 Some builds failed:");
+        for FailedBuild {
+            pkgbase,
+            directory,
+            logs,
+            ..
+        } in &failed_builds
+        {
+            eprintln!("  ● {} ({})", pkgbase, directory.to_string_lossy());
+            for (arch, file) in logs {
+                eprintln!("    log of {}: {}", arch, file.to_string_lossy());
+                match tail_lines(file, log_tail) {
+                    Ok(lines) => {
+                        for line in lines {
+                            eprintln!("    │ {}", line);
+                        }
+                    }
+                    Err(error) => eprintln!("    ⚠ Cannot read {:?}: {}", file, error),
+                }
+            }
+        }
+    }
+
+    if !blocked_builds.is_empty() {
+        eprintln!();
+        eprintln!();
+        eprintln!("🛈 Some builds were skipped because their dependencies failed:");
+        for FailedBuild {
+            pkgbase,
+            directory,
+            blocked_by,
+            ..
+        } in &blocked_builds
+        {
+            if let Some(blocked_by) = blocked_by {
+                eprintln!(
+                    "  ● {} ({}), blocked by {}",
+                    pkgbase,
+                    directory.to_string_lossy(),
+                    blocked_by,
+                );
+            }
+        }
+    }
+
+    if has_failed_builds {
+        if let Some(record_path) = record_failed_builds {
+            let mut failed_build_record = failed_build_record.clone();
+            for FailedBuild {
+                package_file_names,
+                logs,
+                blocked_by,
+                ..
+            } in failed_builds.into_iter().chain(blocked_builds)
+            {
+                for PackageFileName {
+                    pkgname,
+                    version,
+                    arch,
+                } in package_file_names
+                {
+                    // NOTE: packages for "any" are built along with every other architecture
+                    let log = logs
+                        .iter()
+                        .find(|(log_arch, _)| *log_arch == arch)
+                        .or_else(|| logs.first())
+                        .map(|(_, file)| file.clone());
+                    failed_build_record.push(FailedBuildRecordEntry {
+                        package: PackageFileName {
+                            pkgname: pkgname.to_string(),
+                            version,
+                            arch: arch.to_string(),
+                        },
+                        log,
+                        blocked_by: blocked_by.map(|pkgbase| pkgbase.to_string()),
+                    });
+                }
+            }
+
+            let content = serde_yaml::to_string(&failed_build_record).unwrap();
+            write(record_path.as_ref(), content).map_err(|error| {
+                eprintln!("⮾ {}", error);
+                Failure::from(Code::FailedBuildRecordWritingFailure)
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Everything that the builds of different packages share.
+struct BuildContext<'a> {
+    database: &'a SimpleDatabase<'a>,
+    members: &'a [OwnedMember],
+    repository: &'a Path,
+    repository_directory: &'a Path,
+    arch_filter: &'a OwnedArchFilter,
+    packager: Option<&'a str>,
+    /// Where to write the log of each architecture of each package.
+    log_directory: Option<&'a Path>,
+    /// Whether makepkg should create a detached signature for each package file.
+    sign_packages: bool,
+    /// GPG key to sign with instead of the default one.
+    signing_key: Option<&'a str>,
+    /// Value of `PKGEXT` for makepkg, such as `.pkg.tar.zst`.
+    package_extension: &'a str,
+    failed_build_record: &'a [FailedBuildRecordEntry],
+    /// Whether more than one package may be built at the same time.
+    parallel: bool,
+    /// Package files that have been created so far.
+    built_package_files: Mutex<Vec<Vec<u8>>>,
+    /// Packages that must not be built and the failed package that they depend on.
+    blocked: Mutex<IndexMap<PkgBase<'a>, PkgBase<'a>>>,
+    /// Only one pacman transaction may run at a time.
+    pacman_lock: Mutex<()>,
+    /// Database entries of the package files that have been copied to the repository
+    /// but not yet added to the database.
+    new_entries: Mutex<Vec<PackageEntry>>,
+    /// Names of the package files that have been copied to the repository, in case the build is interrupted.
+    journal: Journal,
+}
+
+struct FailedBuild<'a> {
+    pkgbase: PkgBase<'a>,
+    directory: &'a Path,
+    package_file_names: Vec<PackageFileName<&'a str, String, &'a str>>,
+    /// Log files of the architectures that failed.
+    logs: Vec<(&'a str, PathBuf)>,
+    /// Failed package that this package depends on, if it was not built because of it.
+    blocked_by: Option<PkgBase<'a>>,
+}
+
+/// Reason not to build a package at all.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum SkipReason {
+    AlreadyBuilt,
+    FailureRecorded,
+}
+
+impl<'a> BuildContext<'a> {
+    fn lookup(&self, pkgbase: &PkgBase<'a>) -> (&'a SimpleDatabaseValue<'a>, &'a OwnedMember) {
+        let value = self.database.pkgbase().get(pkgbase).unwrap_or_else(|| {
             dbg!(pkgbase);
             panic!("cannot lookup value")
         });
 
-        let Member {
-            directory,
-            install_missing_dependencies,
-            clean_before_build,
-            clean_after_build,
-            force_rebuild,
-            check,
-            pacman,
-            allow_failure,
-            ..
-        } = members
+        let member = self
+            .members
             .iter()
-            .find(|member| member.directory.as_ref() == *directory)
+            .find(|member| member.directory.as_ref() == value.directory)
             .unwrap_or_else(|| {
-                dbg!(pkgbase, directory);
+                dbg!(pkgbase, value.directory);
                 panic!("cannot lookup member");
             });
 
+        (value, member)
+    }
+
+    fn future_package_file_base_names(
+        &self,
+        srcinfo: &'a ParsedSrcInfo<'a>,
+    ) -> Vec<PackageFileName<&'a str, String, &'a str>> {
+        srcinfo
+            .package_file_base_names(|arch| self.arch_filter.test(arch))
+            .expect("get future package file base names")
+            .collect()
+    }
+
+    fn skip_reason(
+        &self,
+        future_package_file_base_names: &[PackageFileName<&str, String, &str>],
+        force_rebuild: bool,
+    ) -> Option<SkipReason> {
+        if force_rebuild {
+            return None;
+        }
+
+        let is_built = |name: &PackageFileName<&str, String, &str>| {
+            name.find_in(self.repository_directory, self.package_extension)
+                .is_some()
+        };
+        if future_package_file_base_names.iter().all(is_built) {
+            return Some(SkipReason::AlreadyBuilt);
+        }
+
+        let is_failed = |name: &PackageFileName<&str, String, &str>| {
+            self.failed_build_record
+                .iter()
+                .any(|FailedBuildRecordEntry { package, .. }| {
+                    name.pkgname == package.pkgname
+                        && name.arch == package.arch
+                        && name.version == package.version
+                })
+        };
+        if future_package_file_base_names.iter().all(is_failed) {
+            return Some(SkipReason::FailureRecorded);
+        }
+
+        None
+    }
+
+    /// Prevent every package that depends on the failed `pkgbase` from being built.
+    fn block_dependants(&self, pkgbase: &PkgBase<'a>) {
+        let mut blocked = self.blocked.lock().expect("lock blocked packages");
+        for dependant in self.database.transitive_dependants(pkgbase) {
+            blocked.entry(*dependant).or_insert(*pkgbase);
+        }
+    }
+
+    fn build_package(
+        &self,
+        pkgbase: &'a PkgBase<'a>,
+        log: &mut BuildLog,
+        report: &mut PackageReport<'a>,
+    ) -> Result<Option<FailedBuild<'a>>, Failure> {
+        let BuildContext {
+            repository,
+            repository_directory,
+            arch_filter,
+            packager,
+            log_directory,
+            sign_packages,
+            signing_key,
+            package_extension,
+            parallel,
+            ..
+        } = *self;
+
+        let (
+            DatabaseValue { srcinfo, .. },
+            Member {
+                directory,
+                install_missing_dependencies,
+                clean_before_build,
+                clean_after_build,
+                force_rebuild,
+                check,
+                pacman,
+                allow_failure,
+                ..
+            },
+        ) = self.lookup(pkgbase);
+
         let directory: &Path = directory.as_ref();
         let force_rebuild = force_rebuild.unwrap_or(false);
         let check = check.unwrap_or(TriState::Inherit);
@@ -101,307 +514,475 @@ pub fn build(args: BuildArgs) -> Status {
         let pacman: Option<&str> = pacman.as_ref().map(AsRef::as_ref);
         let allow_failure = allow_failure.unwrap_or(false);
 
-        eprintln!();
-        eprintln!();
-        eprintln!("==== PACKAGE ====");
-        eprintln!();
-        eprintln!("🛈 pkgbase:           {}", pkgbase);
+        log!(log, "");
+        log!(log, "");
+        log!(log, "==== PACKAGE ====");
+        log!(log, "");
+        log!(log, "🛈 pkgbase:           {}", pkgbase);
         for pkgname in srcinfo.pkgname() {
-            eprintln!("🛈 pkgname:           {}", pkgname);
+            log!(log, "🛈 pkgname:           {}", pkgname);
         }
-        eprintln!("🛈 source directory:  {}", directory.to_string_lossy());
-        eprintln!("🛈 target repository: {}", repository.to_string_lossy());
-        eprintln!();
-
-        let future_package_file_base_names: Vec<_> = srcinfo
-            .package_file_base_names(|arch| arch_filter.test(arch))
-            .expect("get future package file base names")
+        log!(log, "🛈 source directory:  {}", directory.to_string_lossy());
+        log!(log, "🛈 target repository: {}", repository.to_string_lossy());
+        log!(log, "");
+
+        report.architectures = srcinfo
+            .arch()
+            .filter(|arch| arch_filter.test(arch))
+            .map(|arch| ArchitectureReport {
+                arch,
+                exit_code: None,
+                duration: Duration::ZERO,
+                log: None,
+            })
             .collect();
 
-        let future_package_file_paths: Vec<_> = future_package_file_base_names
-            .iter()
-            .map(|name| repository_directory.join(name.to_string()))
-            .collect();
+        let future_package_file_base_names = self.future_package_file_base_names(srcinfo);
+
+        match self.skip_reason(&future_package_file_base_names, force_rebuild) {
+            None => {}
+            Some(SkipReason::AlreadyBuilt) => {
+                log!(log, "🛈 All packages are already built. Skip.");
+                report.outcome = Outcome::SkippedUpToDate;
+
+                let future_package_file_paths = future_package_file_base_names
+                    .iter()
+                    .filter_map(|name| name.find_in(repository_directory, package_extension));
+                let _pacman_lock = self.pacman_lock.lock().expect("lock pacman");
+                let status = pacman
+                    .unwrap_or("pacman")
+                    .pipe(Command::new)
+                    .with_arg("--upgrade")
+                    .with_args(future_package_file_paths)
+                    .with_arg("--noconfirm")
+                    .with_arg("--asdeps")
+                    .pipe_mut(|command| log.run(command))
+                    .map_err(|error| {
+                        log!(log, "⮾ {}", error);
+                        Failure::from(error)
+                    })?
+                    .code()
+                    .unwrap_or(1);
+                if status != 0 {
+                    log!(
+                        log,
+                        "⮾ pacman -U exits with non-zero status code: {}",
+                        status
+                    );
+                    return status_of_code(status).map(|()| None);
+                }
 
-        if !force_rebuild && future_package_file_paths.iter().all(|path| path.exists()) {
-            eprintln!("🛈 All packages are already built. Skip.");
-
-            let status = pacman
-                .unwrap_or("pacman")
-                .pipe(Command::new)
-                .with_arg("--upgrade")
-                .with_args(future_package_file_paths)
-                .with_arg("--noconfirm")
-                .with_arg("--asdeps")
-                .spawn()
-                .and_then(|mut child| child.wait())
-                .map_err(|error| {
-                    eprintln!("⮾ {}", error);
-                    Failure::from(error)
-                })?
-                .code()
-                .unwrap_or(1);
-            if status != 0 {
-                eprintln!("⮾ pacman -U exits with non-zero status code: {}", status);
-                return status_of_code(status);
+                return Ok(None);
+            }
+            Some(SkipReason::FailureRecorded) => {
+                log!(log, "⚠ Failures had been recorded. Skip.");
+                report.outcome = Outcome::SkippedRecordedFailure;
+                return Ok(None);
             }
-
-            continue;
         }
 
-        let is_failed = |name: &PackageFileName<&str, String, &str>| {
-            failed_build_record.iter().any(|x| {
-                name.pkgname == x.pkgname && name.arch == x.arch && name.version == x.version
-            })
-        };
-        if !force_rebuild && future_package_file_base_names.iter().all(is_failed) {
-            eprintln!("⚠ Failures had been recorded. Skip.");
-            continue;
+        let blocked_by = self
+            .blocked
+            .lock()
+            .expect("lock blocked packages")
+            .get(pkgbase)
+            .copied();
+        if let Some(blocked_by) = blocked_by {
+            log!(log, "⚠ Dependency {} failed to build. Skip.", blocked_by);
+            report.outcome = Outcome::Blocked;
+            report.blocked_by = Some(blocked_by.0);
+            return Ok(Some(FailedBuild {
+                pkgbase: *pkgbase,
+                directory,
+                package_file_names: future_package_file_base_names,
+                logs: Vec::new(),
+                blocked_by: Some(blocked_by),
+            }));
         }
 
+        let mut built_packages = ExternalPackageList::from_env();
+        for filename in self
+            .built_package_files
+            .lock()
+            .expect("lock built package files")
+            .iter()
         {
-            eprintln!("🛈 Checking for missing dependencies...");
-            let InstallationPlan { wanted, unwanted } = built_packages.needed(
-                srcinfo.all_required_dependencies().map(|x| x.name),
-                srcinfo.conflicts().map(|x| x.name),
-            );
-            let has_wanted = !wanted.is_empty();
-            let has_unwanted = !unwanted.is_empty();
-            if has_wanted {
-                eprintln!(
-                    "🛈 Missing dependencies: {}",
-                    wanted.iter().map(|target| &target.name).join(" ")
-                );
-            }
-            if has_unwanted {
-                eprintln!("🛈 Conflicts: {}", unwanted.iter().join(" "));
+            built_packages.add_external_package(filename.clone());
+        }
+
+        let mut build_failed = false;
+        let mut failed_logs = Vec::new();
+        for arch in srcinfo.arch() {
+            if !arch_filter.test(arch) {
+                log!(log, "🛈 Skip architecture {}.", arch);
+                continue;
             }
 
-            macro_rules! spawn_and_warn {
-                ($short:literal, $command:expr) => {
-                    match $command
-                        .spawn()
-                        .and_then(|mut child| child.wait())
-                        .map(|status| status.code().unwrap_or(1))
-                    {
-                        Ok(0) => {}
-                        Ok(status) => eprintln!(
-                            "⚠ pacman {} exits with non-zero status code: {}",
-                            $short, status,
-                        ),
-                        Err(error) => eprintln!("⚠ {}", error),
+            let start = Instant::now();
+
+            let log_file = log_directory.and_then(|log_directory| {
+                let name = format!("{}-{}", pkgbase, arch);
+                let path = log_file_path(log_directory, &name, SystemTime::now());
+                match log.open_file(path.clone()) {
+                    Ok(()) => Some(path),
+                    Err(error) => {
+                        log!(log, "⚠ Cannot create {:?}: {}", path, error);
+                        None
                     }
-                };
+                }
+            });
+            if let Some(log_file) = &log_file {
+                log!(log, "🛈 Log of {}: {}", arch, log_file.to_string_lossy());
             }
 
-            if install_missing_dependencies && has_wanted {
-                macro_rules! run_pacman {
-                    ($long:literal, $short:literal, $target:expr) => {
-                        spawn_and_warn!(
-                            $short,
-                            pacman
-                                .unwrap_or("pacman")
-                                .pipe(Command::new)
-                                .with_arg($long)
-                                .with_args($target)
-                                .with_arg("--noconfirm")
-                                .with_arg("--asdeps")
-                                .with_arg("--needed")
-                        );
+            let conflicts_refused = {
+                let _pacman_lock = self.pacman_lock.lock().expect("lock pacman");
+
+                log!(log, "🛈 Checking for missing dependencies of {}...", arch);
+                let InstallationPlan {
+                    wanted,
+                    unwanted,
+                    outdated,
+                    unsatisfiable,
+                } = installation_plan(&built_packages, srcinfo, arch);
+                if !outdated.is_empty() {
+                    log!(
+                        log,
+                        "⚠ Outdated dependencies: {}",
+                        outdated.iter().join(", ")
+                    );
+                }
+                if !unsatisfiable.is_empty() {
+                    log!(
+                        log,
+                        "⚠ No package satisfies: {}",
+                        unsatisfiable.iter().join(" "),
+                    );
+                }
+                let has_wanted = !wanted.is_empty();
+                let has_unwanted = !unwanted.is_empty();
+                if has_wanted {
+                    log!(
+                        log,
+                        "🛈 Missing dependencies: {}",
+                        wanted.iter().map(|target| &target.name).join(" ")
+                    );
+                }
+                if has_unwanted {
+                    log!(log, "🛈 Conflicts: {}", unwanted.iter().join(" "));
+                }
+
+                macro_rules! spawn_and_warn {
+                    ($short:literal, $command:expr) => {
+                        match log
+                            .run(&mut $command)
+                            .map(|status| status.code().unwrap_or(1))
+                        {
+                            Ok(0) => {}
+                            Ok(status) => log!(
+                                log,
+                                "⚠ pacman {} exits with non-zero status code: {}",
+                                $short,
+                                status,
+                            ),
+                            Err(error) => log!(log, "⚠ {}", error),
+                        }
                     };
                 }
 
-                let (upgrade_targets, sync_targets): (Vec<_>, Vec<_>) = wanted
-                    .into_iter()
-                    .partition(|target| target.external.is_some());
+                if install_missing_dependencies && has_wanted {
+                    macro_rules! run_pacman {
+                        ($long:literal, $short:literal, $target:expr) => {
+                            spawn_and_warn!(
+                                $short,
+                                pacman
+                                    .unwrap_or("pacman")
+                                    .pipe(Command::new)
+                                    .with_arg($long)
+                                    .with_args($target)
+                                    .with_arg("--noconfirm")
+                                    .with_arg("--asdeps")
+                                    .with_arg("--needed")
+                            );
+                        };
+                    }
 
-                if !upgrade_targets.is_empty() {
-                    eprintln!("🛈 Installing missing dependencies from created package files...");
-                    let upgrade_targets = upgrade_targets
+                    let (upgrade_targets, sync_targets): (Vec<_>, Vec<_>) = wanted
                         .into_iter()
-                        .flat_map(|target| target.external)
-                        .map(OsString::from_vec);
-                    run_pacman!("--upgrade", "-U", upgrade_targets);
+                        .partition(|target| target.external.is_some());
+
+                    if !upgrade_targets.is_empty() {
+                        log!(
+                            log,
+                            "🛈 Installing missing dependencies from created package files..."
+                        );
+                        let upgrade_targets = upgrade_targets
+                            .into_iter()
+                            .flat_map(|target| target.external)
+                            .map(OsString::from_vec);
+                        run_pacman!("--upgrade", "-U", upgrade_targets);
+                    }
+
+                    if !sync_targets.is_empty() {
+                        log!(
+                            log,
+                            "🛈 Installing missing dependencies from sync database..."
+                        );
+                        let sync_targets = sync_targets.into_iter().map(|target| target.name);
+                        run_pacman!("--sync", "-S", sync_targets);
+                    }
                 }
 
-                if !sync_targets.is_empty() {
-                    eprintln!("🛈 Installing missing dependencies from sync database...");
-                    let sync_targets = sync_targets.into_iter().map(|target| target.name);
-                    run_pacman!("--sync", "-S", sync_targets);
+                // NOTE: pacman_lock only keeps pacman transactions apart, removing packages while
+                //       other builds are running could take away what they have just installed
+                if install_missing_dependencies && has_unwanted && parallel {
+                    log!(
+                        log,
+                        "⮾ Cannot remove conflicts while other packages are being built, build {} with --jobs 1",
+                        pkgbase,
+                    );
+                    true
+                } else if install_missing_dependencies && has_unwanted {
+                    log!(log, "🛈 Removing conflicts...");
+                    spawn_and_warn!(
+                        "-R",
+                        pacman
+                            .unwrap_or("pacman")
+                            .pipe(Command::new)
+                            .with_arg("--remove")
+                            .with_args(unwanted)
+                            .with_arg("--unneeded")
+                            .with_arg("--assumed-installed")
+                            .with_arg("--noconfirm")
+                    );
+                    false
+                } else {
+                    false
                 }
+            };
+
+            // NOTE: makepkg --install and --syncdeps would start pacman transactions that race with other builds,
+            //       so parallel builds rely on the dependencies installed above and install their packages
+            //       separately while holding pacman_lock
+            let makepkg_status = if conflicts_refused {
+                None
+            } else {
+                log!(log, "🛈 Building for architecture {}...", arch);
+                create_makepkg_command(package_extension)
+                    .arg_if("--install", !parallel)
+                    .arg_if("--sign", sign_packages)
+                    .with_args(signing_key_args(sign_packages, signing_key))
+                    .may_env(
+                        "GNUPGHOME",
+                        sign_packages.then(|| env::var_os("GNUPGHOME")).flatten(),
+                    )
+                    .with_arg("--noconfirm")
+                    .with_arg("--asdeps")
+                    .arg_if("--syncdeps", install_missing_dependencies && !parallel)
+                    .arg_if("--clean", clean_after_build)
+                    .arg_if("--cleanbuild", clean_before_build)
+                    .arg_if("--force", force_rebuild)
+                    .arg_if("--check", check == TriState::Enabled)
+                    .arg_if("--nocheck", check == TriState::Disabled)
+                    .may_env("PACMAN", pacman)
+                    .may_env("PACKAGER", packager)
+                    .with_env("CARCH", arch)
+                    .with_current_dir(directory)
+                    .with_stdin(Stdio::null())
+                    .pipe_mut(|command| log.run(command))
+                    .map_err(|error| {
+                        log!(log, "⮾ {}", error);
+                        Failure::from(error)
+                    })?
+                    .code()
+                    .unwrap_or(1)
+                    .pipe(Some)
+            };
+            let mut status = makepkg_status.unwrap_or(1);
+
+            if status == 0 && parallel {
+                let package_files: Vec<_> = srcinfo
+                    .package_file_base_names(|x| *x == arch || *x == "any")
+                    .expect("get package file base names")
+                    .map(|name| directory.join(name.file_name(package_extension)))
+                    .filter(|path| path.exists())
+                    .collect();
+                let _pacman_lock = self.pacman_lock.lock().expect("lock pacman");
+                status = pacman
+                    .unwrap_or("pacman")
+                    .pipe(Command::new)
+                    .with_arg("--upgrade")
+                    .with_args(package_files)
+                    .with_arg("--noconfirm")
+                    .with_arg("--asdeps")
+                    .with_stdin(Stdio::null())
+                    .pipe_mut(|command| log.run(command))
+                    .map_err(|error| {
+                        log!(log, "⮾ {}", error);
+                        Failure::from(error)
+                    })?
+                    .code()
+                    .unwrap_or(1);
             }
 
-            if install_missing_dependencies && has_unwanted {
-                eprintln!("🛈 Removing conflicts...");
-                spawn_and_warn!(
-                    "-R",
-                    pacman
-                        .unwrap_or("pacman")
-                        .pipe(Command::new)
-                        .with_arg("--remove")
-                        .with_args(unwanted)
-                        .with_arg("--unneeded")
-                        .with_arg("--assumed-installed")
-                        .with_arg("--noconfirm")
-                );
-            }
-        }
+            log.close_file();
 
-        let mut build_failed = false;
-        for arch in srcinfo.arch() {
-            if !arch_filter.test(arch) {
-                eprintln!("🛈 Skip architecture {}.", arch);
-                continue;
+            if let Some(architecture) = report
+                .architectures
+                .iter_mut()
+                .find(|architecture| architecture.arch == arch)
+            {
+                architecture.exit_code = makepkg_status;
+                architecture.duration = start.elapsed();
+                architecture.log = log_file.clone();
             }
 
-            eprintln!("🛈 Building for architecture {}...", arch);
-
-            let status = create_makepkg_command()
-                .with_arg("--install")
-                .with_arg("--noconfirm")
-                .with_arg("--asdeps")
-                .arg_if("--syncdeps", install_missing_dependencies)
-                .arg_if("--clean", clean_after_build)
-                .arg_if("--cleanbuild", clean_before_build)
-                .arg_if("--force", force_rebuild)
-                .arg_if("--check", check == TriState::Enabled)
-                .arg_if("--nocheck", check == TriState::Disabled)
-                .may_env("PACMAN", pacman)
-                .may_env("PACKAGER", packager)
-                .with_env("CARCH", arch)
-                .with_current_dir(directory)
-                .with_stdin(Stdio::null())
-                .with_stdout(Stdio::inherit())
-                .with_stderr(Stdio::inherit())
-                .spawn()
-                .and_then(|mut child| child.wait())
-                .map_err(|error| {
-                    eprintln!("⮾ {}", error);
-                    Failure::from(error)
-                })?
-                .code()
-                .unwrap_or(1);
-
             if status != 0 {
                 build_failed = true;
+                failed_logs.extend(log_file.map(|log_file| (arch, log_file)));
 
                 if allow_failure {
-                    eprintln!("⚠ makepkg exits with non-zero status code: {}", status);
-                    eprintln!("⚠ skip {}", pkgbase);
+                    if makepkg_status.is_some() {
+                        log!(log, "⚠ makepkg exits with non-zero status code: {}", status);
+                    }
+                    log!(log, "⚠ skip {}", pkgbase);
                     continue;
                 } else {
-                    eprintln!("⮾ makepkg exits with non-zero status code: {}", status);
-                    return status_of_code(status);
+                    if makepkg_status.is_some() {
+                        log!(log, "⮾ makepkg exits with non-zero status code: {}", status);
+                    }
+                    return status_of_code(status).map(|()| None);
                 }
             }
         }
 
-        if build_failed {
-            failed_builds.push((*pkgbase, directory, future_package_file_base_names));
-        }
-
         for pkg_file_name in srcinfo
             .package_file_base_names(|arch| arch_filter.test(arch))
             .expect("get package file base names")
         {
-            let pkg_file_name = &pkg_file_name.to_string();
+            let pkg_file_name = &pkg_file_name.file_name(package_extension);
             let pkg_src_file = directory.join(pkg_file_name);
             let pkg_dst_file = repository_directory.join(pkg_file_name);
 
             if !pkg_src_file.exists() {
-                eprintln!("⚠ File {:?} does not exist. Skip.", &pkg_src_file);
+                log!(log, "⚠ File {:?} does not exist. Skip.", &pkg_src_file);
                 continue;
             }
 
-            eprintln!("📦 made file {}", pkg_file_name);
+            log!(log, "📦 made file {}", pkg_file_name);
+            report.package_files.push(pkg_file_name.to_string());
+
+            self.built_package_files
+                .lock()
+                .expect("lock built package files")
+                .push(pkg_src_file.as_os_str().as_bytes().to_vec());
 
             {
-                built_packages.add_external_package(pkg_src_file.as_os_str().as_bytes().to_vec());
+                log!(
+                    log,
+                    "  → copy to {}/",
+                    repository_directory.to_string_lossy()
+                );
+                if let Err(error) = reflink_or_copy(&pkg_src_file, &pkg_dst_file) {
+                    log!(log, "⮾ {}", error);
+                    return Err(Failure::from(error));
+                }
             }
 
-            {
-                eprintln!("  → copy to {}/", repository_directory.to_string_lossy());
-                if let Err(error) = reflink_or_copy(&pkg_src_file, pkg_dst_file) {
-                    eprintln!("⮾ {}", error);
-                    return error.pipe(Failure::from).into();
+            let sig_src_file = directory.join(format!("{}.sig", pkg_file_name));
+            if sign_packages {
+                if sig_src_file.exists() {
+                    log!(log, "  → copy signature");
+                    let sig_dst_file = repository_directory.join(format!("{}.sig", pkg_file_name));
+                    if let Err(error) = reflink_or_copy(&sig_src_file, sig_dst_file) {
+                        log!(log, "⮾ {}", error);
+                        return Err(Failure::from(error));
+                    }
+                } else {
+                    log!(log, "⚠ Signature {:?} does not exist.", &sig_src_file);
                 }
             }
 
+            if let Err(error) = self.journal.append(pkg_file_name) {
+                log!(log, "⮾ Cannot write {:?}: {}", self.journal.path(), error);
+                return Err(Failure::from(error));
+            }
+
             if clean_after_build {
-                eprintln!("  → clean");
+                log!(log, "  → clean");
                 if let Err(error) = remove_file(pkg_src_file) {
-                    eprintln!("⚠ {}", error);
+                    log!(log, "⚠ {}", error);
+                }
+                if sig_src_file.exists() {
+                    if let Err(error) = remove_file(sig_src_file) {
+                        log!(log, "⚠ {}", error);
+                    }
                 }
             }
 
-            {
-                eprintln!("  → add to {}", repository.to_string_lossy());
-                let status = Command::new("repo-add")
-                    .with_arg("--quiet")
-                    .with_arg("--nocolor")
-                    .with_arg(repository)
-                    .with_arg(repository_directory.join(pkg_file_name))
-                    .with_stdin(Stdio::null())
-                    .with_stdout(Stdio::inherit())
-                    .with_stderr(Stdio::inherit())
-                    .spawn()
-                    .and_then(|mut child| child.wait())
-                    .map_err(|error| {
-                        eprintln!("⮾ {}", error);
-                        Failure::from(error)
-                    })?
-                    .code()
-                    .unwrap_or(1);
-                if status != 0 {
-                    eprintln!("⮾ repo-add exits with non-zero status code: {}", status);
-                    return status_of_code(status);
+            match PackageEntry::from_package_file(&pkg_dst_file) {
+                Ok(entry) => {
+                    log!(log, "  → queue for {}", repository.to_string_lossy());
+                    self.new_entries
+                        .lock()
+                        .expect("lock new database entries")
+                        .push(entry);
+                }
+                Err(error) => {
+                    log!(log, "⮾ {}", error);
+                    return Err(Failure::from(error));
                 }
             }
         }
-    }
-
-    if dereference_database_symlinks {
-        eprintln!();
-        eprintln!();
-        eprintln!("🛈 Resolving all symlinks to repository database into real files");
-        run_deref_db(repository_directory).map_err(|error| {
-            eprintln!("⮾ {}", error);
-            Failure::from(error)
-        })?;
-    }
 
-    if !failed_builds.is_empty() {
-        eprintln!();
-        eprintln!();
-        eprintln!("🛈 Some builds failed:");
-        for (pkgbase, directory, _) in &failed_builds {
-            eprintln!("  ● {} ({})", pkgbase, directory.to_string_lossy());
+        if !build_failed {
+            report.outcome = Outcome::Built;
+            return Ok(None);
         }
 
-        if let Some(record_path) = record_failed_builds {
-            let mut failed_build_record = failed_build_record;
-            for (_, _, record) in failed_builds {
-                for PackageFileName {
-                    pkgname,
-                    version,
-                    arch,
-                } in record
-                {
-                    failed_build_record.push(PackageFileName {
-                        pkgname: pkgname.to_string(),
-                        version,
-                        arch: arch.to_string(),
-                    });
-                }
-            }
+        Ok(Some(FailedBuild {
+            pkgbase: *pkgbase,
+            directory,
+            package_file_names: future_package_file_base_names,
+            logs: failed_logs,
+            blocked_by: None,
+        }))
+    }
+}
 
-            let content = serde_yaml::to_string(&failed_build_record).unwrap();
-            write(record_path.as_ref(), content).map_err(|error| {
-                eprintln!("⮾ {}", error);
-                Failure::from(Code::FailedBuildRecordWritingFailure)
-            })?;
+fn installation_plan<'a>(
+    built_packages: &ExternalPackageList,
+    srcinfo: &'a ParsedSrcInfo<'a>,
+    arch: &str,
+) -> InstallationPlan {
+    built_packages.needed(
+        srcinfo.all_required_dependencies_for_arch(arch),
+        srcinfo.conflicts_for_arch(arch),
+    )
+}
+
+/// Load the repository database along with the package files that an interrupted build has copied.
+///
+/// Return whether the database differs from the one on disk.
+fn load_database(
+    repository: &Path,
+    repository_directory: &Path,
+    journal: &Journal,
+) -> Result<(RepositoryDatabase, bool), DatabaseError> {
+    let mut database = RepositoryDatabase::load(repository)?;
+    let file_names = journal.read()?;
+    if !file_names.is_empty() {
+        eprintln!(
+            "🛈 Adding package files that an interrupted build copied to {}",
+            repository.to_string_lossy(),
+        );
+    }
+    for file_name in &file_names {
+        let package_file = repository_directory.join(file_name);
+        if package_file.exists() {
+            eprintln!("  → {}", file_name);
+            database.add(&PackageEntry::from_package_file(&package_file)?);
         }
     }
-
-    Ok(())
+    Ok((database, !file_names.is_empty()))
 }
@@ -0,0 +1,212 @@
+use super::super::super::{
+    args::PlanFormat,
+    manifest::Member,
+    srcinfo::{
+        database::text_wrapper::PkgBase, dependency::UnreasonedDependency, parsed::ParsedSrcInfo,
+    },
+    status::{Code, Failure, Status},
+    utils::{serialize_iter_yaml, ExternalPackageList, InstallationPlan},
+};
+use super::{installation_plan, BuildContext, SkipReason};
+use itertools::Itertools;
+use serde::Serialize;
+use std::{
+    fmt::{self, Display, Formatter},
+    path::Path,
+};
+
+/// What `build` would do with a single package.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackagePlan<'a> {
+    pub pkgbase: &'a str,
+    pub directory: &'a Path,
+    #[serde(flatten)]
+    pub action: PlannedAction<'a>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "action")]
+pub enum PlannedAction<'a> {
+    SkipAlreadyBuilt,
+    SkipRecordedFailure,
+    #[serde(rename_all = "kebab-case")]
+    Build {
+        install_missing_dependencies: bool,
+        architectures: Vec<ArchitecturePlan<'a>>,
+    },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ArchitecturePlan<'a> {
+    pub arch: &'a str,
+    /// Dependencies that are not installed.
+    pub dependencies: Vec<String>,
+    /// Installed packages that conflict with the package.
+    pub conflicts: Vec<String>,
+    pub outdated: Vec<String>,
+    pub unsatisfiable: Vec<String>,
+}
+
+impl<'a> BuildContext<'a> {
+    /// Decide what would be done with `pkgbase` without executing anything.
+    fn plan_package(
+        &self,
+        pkgbase: &'a PkgBase<'a>,
+        built_packages: &ExternalPackageList,
+    ) -> PackagePlan<'a> {
+        let (value, member) = self.lookup(pkgbase);
+        let Member {
+            directory,
+            install_missing_dependencies,
+            force_rebuild,
+            ..
+        } = member;
+        let srcinfo = value.srcinfo;
+        let force_rebuild = force_rebuild.unwrap_or(false);
+        let install_missing_dependencies = install_missing_dependencies.unwrap_or(false);
+
+        let future_package_file_base_names = self.future_package_file_base_names(srcinfo);
+        let action = match self.skip_reason(&future_package_file_base_names, force_rebuild) {
+            Some(SkipReason::AlreadyBuilt) => PlannedAction::SkipAlreadyBuilt,
+            Some(SkipReason::FailureRecorded) => PlannedAction::SkipRecordedFailure,
+            None => {
+                let architectures = srcinfo
+                    .arch()
+                    .filter(|arch| self.arch_filter.test(arch))
+                    .map(|arch| self.plan_architecture(srcinfo, arch, built_packages))
+                    .collect();
+                PlannedAction::Build {
+                    install_missing_dependencies,
+                    architectures,
+                }
+            }
+        };
+
+        PackagePlan {
+            pkgbase: pkgbase.0,
+            directory: directory.as_ref(),
+            action,
+        }
+    }
+
+    fn plan_architecture(
+        &self,
+        srcinfo: &'a ParsedSrcInfo<'a>,
+        arch: &'a str,
+        built_packages: &ExternalPackageList,
+    ) -> ArchitecturePlan<'a> {
+        let InstallationPlan {
+            wanted,
+            unwanted,
+            outdated,
+            unsatisfiable,
+        } = installation_plan(built_packages, srcinfo, arch);
+
+        let mut dependencies: Vec<_> = wanted.into_iter().map(|target| target.name).collect();
+
+        // NOTE: packages of other members have not been built yet,
+        //       they would have been found among the created package files
+        let (from_members, unsatisfiable): (Vec<_>, Vec<_>) =
+            unsatisfiable.into_iter().partition(|dependency| {
+                let dependency = UnreasonedDependency::new(dependency);
+                !self
+                    .database
+                    .resolve_dependency(dependency, Some(arch))
+                    .is_empty()
+            });
+        dependencies.extend(
+            from_members
+                .iter()
+                .map(|dependency| UnreasonedDependency::new(dependency).name.to_string()),
+        );
+
+        ArchitecturePlan {
+            arch,
+            dependencies,
+            conflicts: unwanted.into_iter().collect(),
+            outdated: outdated.into_iter().collect(),
+            unsatisfiable,
+        }
+    }
+}
+
+impl<'a> Display for PackagePlan<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.pkgbase)?;
+
+        let (install_missing_dependencies, architectures) = match &self.action {
+            PlannedAction::SkipAlreadyBuilt => return write!(f, "skip, already built"),
+            PlannedAction::SkipRecordedFailure => return write!(f, "skip, failure recorded"),
+            PlannedAction::Build {
+                install_missing_dependencies,
+                architectures,
+            } => (*install_missing_dependencies, architectures),
+        };
+
+        if architectures.is_empty() {
+            return write!(f, "skip, no architecture passes the filter");
+        }
+
+        let (dependencies_label, conflicts_label) = if install_missing_dependencies {
+            ("install", "remove")
+        } else {
+            ("missing", "conflicts")
+        };
+
+        write!(f, "build for ")?;
+        for (index, plan) in architectures.iter().enumerate() {
+            if index != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", plan.arch)?;
+
+            let details: Vec<_> = [
+                (dependencies_label, &plan.dependencies),
+                (conflicts_label, &plan.conflicts),
+                ("outdated", &plan.outdated),
+                ("unsatisfiable", &plan.unsatisfiable),
+            ]
+            .into_iter()
+            .filter(|(_, list)| !list.is_empty())
+            .map(|(label, list)| format!("{}: {}", label, list.iter().join(" ")))
+            .collect();
+            if !details.is_empty() {
+                write!(f, " ({})", details.join("; "))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Print what `build` would do with every package in `build_order`.
+pub fn print_plan<'a>(
+    context: &BuildContext<'a>,
+    build_order: &[&'a PkgBase<'a>],
+    format: PlanFormat,
+) -> Status {
+    let built_packages = ExternalPackageList::from_env();
+    let plans = build_order
+        .iter()
+        .map(|pkgbase| context.plan_package(pkgbase, &built_packages));
+
+    match format {
+        PlanFormat::Text => {
+            for plan in plans {
+                println!("{}", plan);
+            }
+        }
+        PlanFormat::Yaml => {
+            let plans: Vec<_> = plans.collect();
+            let yaml = serialize_iter_yaml(plans).map_err(|error| {
+                eprintln!("⮾ {}", error);
+                Failure::from(Code::GenericFailure)
+            })?;
+            print!("{}", yaml);
+        }
+    }
+
+    Ok(())
+}
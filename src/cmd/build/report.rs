@@ -0,0 +1,214 @@
+use super::super::super::args::ReportFormat;
+use serde::{Serialize, Serializer};
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// What happened to a single package during `build`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackageReport<'a> {
+    pub pkgbase: &'a str,
+    pub outcome: Outcome,
+    /// Failed package that this package depends on, if the outcome is [`Outcome::Blocked`].
+    pub blocked_by: Option<&'a str>,
+    #[serde(serialize_with = "serialize_seconds")]
+    pub duration: Duration,
+    /// Names of the package files that were added to the repository.
+    pub package_files: Vec<String>,
+    pub architectures: Vec<ArchitectureReport<'a>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ArchitectureReport<'a> {
+    pub arch: &'a str,
+    /// Exit code of makepkg, if it was run.
+    pub exit_code: Option<i32>,
+    #[serde(serialize_with = "serialize_seconds")]
+    pub duration: Duration,
+    pub log: Option<PathBuf>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Outcome {
+    Built,
+    SkippedUpToDate,
+    SkippedRecordedFailure,
+    Failed,
+    Blocked,
+}
+
+fn serialize_seconds<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+impl<'a> PackageReport<'a> {
+    /// Report of a package that has yet to be built, it is failed until proven otherwise.
+    pub fn new(pkgbase: &'a str) -> Self {
+        PackageReport {
+            pkgbase,
+            outcome: Outcome::Failed,
+            blocked_by: None,
+            duration: Duration::ZERO,
+            package_files: Vec::new(),
+            architectures: Vec::new(),
+        }
+    }
+
+    fn failure_message(&self) -> String {
+        let failed_architectures: Vec<_> = self
+            .architectures
+            .iter()
+            .filter_map(|report| match report.exit_code {
+                Some(0) | None => None,
+                Some(code) => Some(format!("{} (exit code {})", report.arch, code)),
+            })
+            .collect();
+        if failed_architectures.is_empty() {
+            "build failed".to_string()
+        } else {
+            format!("makepkg failed for {}", failed_architectures.join(", "))
+        }
+    }
+}
+
+/// Write `reports` to the file at `path`.
+pub fn write_report(
+    path: &Path,
+    format: ReportFormat,
+    reports: &[PackageReport],
+) -> io::Result<()> {
+    let content = match format {
+        ReportFormat::Json => json(reports)?,
+        ReportFormat::Junit => junit(reports),
+    };
+    let mut file = File::create(path).map(BufWriter::new)?;
+    file.write_all(content.as_bytes())?;
+    file.flush()
+}
+
+fn json(reports: &[PackageReport]) -> serde_json::Result<String> {
+    #[derive(Serialize)]
+    struct JsonReport<'a> {
+        packages: &'a [PackageReport<'a>],
+    }
+
+    let mut output = serde_json::to_string_pretty(&JsonReport { packages: reports })?;
+    output.push('\n');
+    Ok(output)
+}
+
+fn junit(reports: &[PackageReport]) -> String {
+    let count = |outcomes: &[Outcome]| {
+        reports
+            .iter()
+            .filter(|report| outcomes.contains(&report.outcome))
+            .count()
+    };
+    let failures = count(&[Outcome::Failed]);
+    let skipped = count(&[
+        Outcome::SkippedUpToDate,
+        Outcome::SkippedRecordedFailure,
+        Outcome::Blocked,
+    ]);
+    let time: f64 = reports
+        .iter()
+        .map(|report| report.duration.as_secs_f64())
+        .sum();
+
+    let mut output = String::new();
+    output.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(
+        output,
+        "<testsuites name=\"build-pacman-repo\" tests=\"{tests}\" failures=\"{failures}\" skipped=\"{skipped}\" time=\"{time:.3}\">",
+        tests = reports.len(),
+    )
+    .expect("write to a string");
+    writeln!(
+        output,
+        "  <testsuite name=\"build\" tests=\"{tests}\" failures=\"{failures}\" skipped=\"{skipped}\" time=\"{time:.3}\">",
+        tests = reports.len(),
+    )
+    .expect("write to a string");
+
+    for report in reports {
+        let architectures: Vec<_> = report.architectures.iter().map(|x| x.arch).collect();
+        writeln!(
+            output,
+            "    <testcase classname=\"build\" name=\"{}\" time=\"{:.3}\">",
+            xml_escape(report.pkgbase),
+            report.duration.as_secs_f64(),
+        )
+        .expect("write to a string");
+
+        let skipped_message = match report.outcome {
+            Outcome::Built | Outcome::Failed => None,
+            Outcome::SkippedUpToDate => Some("already built".to_string()),
+            Outcome::SkippedRecordedFailure => Some("failure recorded".to_string()),
+            Outcome::Blocked => Some(match report.blocked_by {
+                Some(blocked_by) => format!("blocked by {}", blocked_by),
+                None => "blocked".to_string(),
+            }),
+        };
+        if let Some(message) = skipped_message {
+            writeln!(
+                output,
+                "      <skipped message=\"{}\"/>",
+                xml_escape(&message),
+            )
+            .expect("write to a string");
+        }
+        if report.outcome == Outcome::Failed {
+            let logs: Vec<_> = report
+                .architectures
+                .iter()
+                .filter_map(|architecture| {
+                    let log = architecture.log.as_ref()?;
+                    Some(format!("{}: {}", architecture.arch, log.to_string_lossy()))
+                })
+                .collect();
+            writeln!(
+                output,
+                "      <failure message=\"{}\">{}</failure>",
+                xml_escape(&report.failure_message()),
+                xml_escape(&logs.join("\n")),
+            )
+            .expect("write to a string");
+        }
+        writeln!(
+            output,
+            "      <system-out>architectures: {}\npackage files: {}</system-out>",
+            xml_escape(&architectures.join(" ")),
+            xml_escape(&report.package_files.join(" ")),
+        )
+        .expect("write to a string");
+        output.push_str("    </testcase>\n");
+    }
+
+    output.push_str("  </testsuite>\n</testsuites>\n");
+    output
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for char in text.chars() {
+        match char {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&apos;"),
+            char => output.push(char),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests;
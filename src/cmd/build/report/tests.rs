@@ -0,0 +1,117 @@
+use super::{json, junit, ArchitectureReport, Outcome, PackageReport};
+use pretty_assertions::assert_eq;
+use std::{path::PathBuf, time::Duration};
+
+fn reports() -> Vec<PackageReport<'static>> {
+    vec![
+        PackageReport {
+            pkgbase: "foo",
+            outcome: Outcome::Built,
+            blocked_by: None,
+            architectures: vec![ArchitectureReport {
+                arch: "x86_64",
+                exit_code: Some(0),
+                duration: Duration::from_millis(1500),
+                log: None,
+            }],
+            package_files: vec!["foo-1-1-x86_64.pkg.tar.zst".to_string()],
+            duration: Duration::from_millis(2000),
+        },
+        PackageReport {
+            pkgbase: "bar",
+            outcome: Outcome::Failed,
+            blocked_by: None,
+            architectures: vec![ArchitectureReport {
+                arch: "x86_64",
+                exit_code: Some(4),
+                duration: Duration::from_millis(250),
+                log: Some(PathBuf::from("logs/bar \"x86_64\".log")),
+            }],
+            package_files: vec![],
+            duration: Duration::from_millis(500),
+        },
+        PackageReport {
+            pkgbase: "baz",
+            outcome: Outcome::Blocked,
+            blocked_by: Some("bar"),
+            architectures: vec![],
+            package_files: vec![],
+            duration: Duration::ZERO,
+        },
+    ]
+}
+
+#[test]
+fn json_report() {
+    let expected = r#"{
+  "packages": [
+    {
+      "pkgbase": "foo",
+      "outcome": "built",
+      "blocked-by": null,
+      "duration": 2.0,
+      "package-files": [
+        "foo-1-1-x86_64.pkg.tar.zst"
+      ],
+      "architectures": [
+        {
+          "arch": "x86_64",
+          "exit-code": 0,
+          "duration": 1.5,
+          "log": null
+        }
+      ]
+    },
+    {
+      "pkgbase": "bar",
+      "outcome": "failed",
+      "blocked-by": null,
+      "duration": 0.5,
+      "package-files": [],
+      "architectures": [
+        {
+          "arch": "x86_64",
+          "exit-code": 4,
+          "duration": 0.25,
+          "log": "logs/bar \"x86_64\".log"
+        }
+      ]
+    },
+    {
+      "pkgbase": "baz",
+      "outcome": "blocked",
+      "blocked-by": "bar",
+      "duration": 0.0,
+      "package-files": [],
+      "architectures": []
+    }
+  ]
+}
+"#;
+    assert_eq!(json(&reports()).unwrap(), expected);
+}
+
+#[test]
+fn junit_report() {
+    let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="build-pacman-repo" tests="3" failures="1" skipped="1" time="2.500">
+  <testsuite name="build" tests="3" failures="1" skipped="1" time="2.500">
+    <testcase classname="build" name="foo" time="2.000">
+      <system-out>architectures: x86_64
+package files: foo-1-1-x86_64.pkg.tar.zst</system-out>
+    </testcase>
+    <testcase classname="build" name="bar" time="0.500">
+      <failure message="makepkg failed for x86_64 (exit code 4)">x86_64: logs/bar &quot;x86_64&quot;.log</failure>
+      <system-out>architectures: x86_64
+package files: </system-out>
+    </testcase>
+    <testcase classname="build" name="baz" time="0.000">
+      <skipped message="blocked by bar"/>
+      <system-out>architectures: 
+package files: </system-out>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#;
+    assert_eq!(junit(&reports()), expected);
+}
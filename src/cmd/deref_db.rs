@@ -1,15 +1,15 @@
 use super::super::{
     args::DerefDbArgs,
-    manifest::BuildPacmanRepo,
+    manifest::{BuildPacmanRepo, ManifestOptions},
     status::{Code, Failure, Status},
     utils::run_deref_db,
 };
 use pipe_trait::*;
 
-pub fn deref_db(args: DerefDbArgs) -> Status {
+pub fn deref_db(args: DerefDbArgs, manifest: ManifestOptions) -> Status {
     let DerefDbArgs {} = args;
 
-    BuildPacmanRepo::from_env()
+    BuildPacmanRepo::from_env(manifest)
         .map_err(|error| {
             eprintln!("⮾ {}", error);
             Failure::from(Code::ManifestLoadingFailure)
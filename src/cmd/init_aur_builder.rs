@@ -1,14 +1,18 @@
 use super::super::{
     args::InitAurBuilderArgs,
     manifest::{
-        BuildPacmanRepo, GlobalSettings, InitAurBuilder, OwnedMember, Wrapper, BUILD_PACMAN_REPO,
+        GlobalSettings, InitAurBuilder, ManifestOptions, OwnedBuildPacmanRepo, OwnedMember,
+        Wrapper, BUILD_PACMAN_REPO,
     },
     status::{Code, Failure, Status},
     utils::{AlpmWrapper, CloneAur},
 };
-use std::{fs::OpenOptions, path::PathBuf};
+use std::{
+    fs::OpenOptions,
+    path::{Path, PathBuf},
+};
 
-pub fn init_aur_builder(args: InitAurBuilderArgs) -> Status {
+pub fn init_aur_builder(args: InitAurBuilderArgs, manifest: ManifestOptions) -> Status {
     let InitAurBuilderArgs {} = args;
 
     let InitAurBuilder {
@@ -29,7 +33,11 @@ pub fn init_aur_builder(args: InitAurBuilderArgs) -> Status {
         .write(true)
         .read(true)
         .create(true)
-        .open(BUILD_PACMAN_REPO)
+        .open(
+            manifest
+                .file
+                .unwrap_or_else(|| Path::new(BUILD_PACMAN_REPO)),
+        )
         .map_err(|error| {
             eprintln!("⮾ {}", error);
             Failure::from(error)
@@ -63,7 +71,8 @@ pub fn init_aur_builder(args: InitAurBuilderArgs) -> Status {
         })
         .collect();
 
-    let manifest_content = BuildPacmanRepo {
+    let manifest_content = OwnedBuildPacmanRepo {
+        include: Vec::new(),
         global_settings,
         members,
     };
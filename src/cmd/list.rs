@@ -0,0 +1,76 @@
+use super::super::{
+    args::{ListArgs, ListFormat},
+    manifest::{BuildPacmanRepo, ManifestOptions},
+    repository_database::{package_record::PackageRecord, RepositoryDatabase},
+    status::{Code, Failure, Status},
+    utils::serialize_iter_yaml,
+};
+
+pub fn list(args: ListArgs, manifest: ManifestOptions) -> Status {
+    let ListArgs { format, repository } = args;
+
+    let repository = match repository {
+        Some(repository) => repository,
+        None => BuildPacmanRepo::from_env(manifest)
+            .map_err(|error| {
+                eprintln!("⮾ {}", error);
+                Failure::from(Code::ManifestLoadingFailure)
+            })?
+            .global_settings
+            .repository
+            .as_ref()
+            .to_path_buf(),
+    };
+    if !repository.exists() {
+        eprintln!("⮾ Repository database {:?} does not exist", repository);
+        return Code::GenericFailure.into();
+    }
+
+    let database = RepositoryDatabase::load(&repository).map_err(|error| {
+        eprintln!("⮾ Cannot read {:?}: {}", repository, error);
+        Failure::from(error)
+    })?;
+
+    let mut error_count = 0;
+    let mut packages: Vec<PackageRecord> = database
+        .packages()
+        .filter_map(|(directory, record)| match record {
+            Err(error) => {
+                eprintln!("⮾ Error in {}: {}", directory, error);
+                error_count += 1;
+                None
+            }
+            Ok(record) => Some(record),
+        })
+        .collect();
+    packages.sort_by(|left, right| left.name.cmp(&right.name));
+
+    match format.unwrap_or_default() {
+        ListFormat::Text => {
+            for PackageRecord { name, version, .. } in &packages {
+                println!("{} {}", name, version);
+            }
+        }
+        ListFormat::Yaml => {
+            let yaml = serialize_iter_yaml(&packages).map_err(|error| {
+                eprintln!("⮾ {}", error);
+                Failure::from(Code::GenericFailure)
+            })?;
+            print!("{}", yaml);
+        }
+        ListFormat::Json => {
+            let json = serde_json::to_string_pretty(&packages).map_err(|error| {
+                eprintln!("⮾ {}", error);
+                Failure::from(Code::GenericFailure)
+            })?;
+            println!("{}", json);
+        }
+    }
+
+    if error_count == 0 {
+        Ok(())
+    } else {
+        eprintln!("{} errors occurred", error_count);
+        Code::GenericFailure.into()
+    }
+}
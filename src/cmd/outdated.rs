@@ -1,16 +1,23 @@
 use super::super::{
     args::{OutdatedArgs, OutdatedDetails},
+    manifest::ManifestOptions,
+    repository_database::RepositoryDatabase,
     status::{Code, Failure, Status},
-    utils::{load_failed_build_record, outdated_packages, DbInit, DbInitValue, PackageFileName},
+    utils::{
+        load_failed_build_record, outdated_packages, read_dir_or_current, DbInit, DbInitValue,
+        OutdatedPackage, PackageFileName, DEFAULT_PACKAGE_EXTENSION,
+    },
 };
+use indexmap::IndexMap;
 use pipe_trait::*;
-use std::{fs::read_dir, path::PathBuf};
 
-pub fn outdated(args: OutdatedArgs) -> Status {
-    let OutdatedArgs { details } = args;
+pub fn outdated(args: OutdatedArgs, manifest: ManifestOptions) -> Status {
+    let OutdatedArgs { details, profile } = args;
     let details = details.unwrap_or_default();
 
-    let mut db_init = DbInit::default();
+    let mut db_init = DbInit::default()
+        .with_manifest(manifest)
+        .with_profile(profile.as_deref());
     let DbInitValue {
         manifest,
         database,
@@ -18,6 +25,11 @@ pub fn outdated(args: OutdatedArgs) -> Status {
     } = db_init.init()?;
 
     let arch_filter = manifest.global_settings.arch_filter.unwrap_or_default();
+    let package_extension = manifest
+        .global_settings
+        .package_extension
+        .as_ref()
+        .map_or(DEFAULT_PACKAGE_EXTENSION, AsRef::as_ref);
 
     let latest_packages: Vec<_> = database
         .package_file_base_names(|arch| arch_filter.test(arch))
@@ -37,16 +49,7 @@ pub fn outdated(args: OutdatedArgs) -> Status {
         return Code::GenericFailure.into();
     };
 
-    // PROBLEM: read_dir cannot read "" as a directory
-    // WORKAROUND: replace it with "."
-    let valid_current_directory = PathBuf::from(".");
-    let directory = if directory.as_os_str().is_empty() {
-        &valid_current_directory
-    } else {
-        directory
-    };
-
-    let entries = match read_dir(directory) {
+    let entries = match read_dir_or_current(directory) {
         Err(error) => {
             eprintln!("⮾ Cannot read {:?} as a directory: {}", directory, error,);
             return error.pipe(Failure::from).into();
@@ -76,6 +79,22 @@ pub fn outdated(args: OutdatedArgs) -> Status {
         }
     }
 
+    let current_versions: IndexMap<String, String> = RepositoryDatabase::load(repository)
+        .map_err(|error| {
+            eprintln!("⮾ Cannot read {:?}: {}", repository, error);
+            Failure::from(error)
+        })?
+        .packages()
+        .filter_map(|(directory, record)| match record {
+            Err(error) => {
+                eprintln!("⮾ Error in {}: {}", directory, error);
+                error_count += 1;
+                None
+            }
+            Ok(record) => Some((record.name, record.version)),
+        })
+        .collect();
+
     let failed_builds = manifest
         .global_settings
         .record_failed_builds
@@ -83,23 +102,34 @@ pub fn outdated(args: OutdatedArgs) -> Status {
         .map_err(|error| {
             eprintln!("⮾ {}", error);
             Failure::from(Code::FailedBuildRecordLoadingFailure)
-        })?;
+        })?
+        .into_iter()
+        .map(|entry| entry.package)
+        .collect::<Vec<_>>();
 
-    for (
-        ref file_name,
-        PackageFileName {
-            pkgname,
-            version,
-            arch,
-        },
-    ) in outdated_packages(&latest_packages, &current_packages, &failed_builds)
-    {
+    for OutdatedPackage {
+        file_name,
+        latest:
+            PackageFileName {
+                pkgname,
+                version,
+                arch,
+            },
+        current_version,
+        status,
+    } in outdated_packages(
+        latest_packages,
+        &current_versions,
+        &current_packages,
+        &failed_builds,
+        package_extension,
+    ) {
         match details {
             OutdatedDetails::PkgName => {
-                println!("{}", pkgname);
+                println!("{} {}", pkgname, status);
             }
             OutdatedDetails::PkgFilePath => {
-                println!("{}", file_name);
+                println!("{} {}", file_name, status);
             }
             OutdatedDetails::LossyYaml => {
                 println!("---");
@@ -107,6 +137,8 @@ pub fn outdated(args: OutdatedArgs) -> Status {
                 println!("pkgname: {}", pkgname);
                 println!("version: {}", version);
                 println!("arch: {}", arch);
+                println!("current-version: {}", current_version.unwrap_or("null"));
+                println!("status: {}", status);
             }
             OutdatedDetails::StrictYaml => {
                 println!("---");
@@ -114,6 +146,11 @@ pub fn outdated(args: OutdatedArgs) -> Status {
                 println!("pkgname: {:?}", pkgname);
                 println!("version: {:?}", version);
                 println!("arch: {:?}", arch);
+                match current_version {
+                    Some(current_version) => println!("current-version: {:?}", current_version),
+                    None => println!("current-version: null"),
+                }
+                println!("status: {:?}", status.name());
             }
         }
     }
@@ -1,8 +1,8 @@
 use super::super::{
     args::PrintConfigArgs,
     manifest::{
-        ArchFilter, BuildMetadata, BuildPacmanRepo, Member, OwnedGlobalSettings, OwnedMember,
-        Wrapper,
+        ArchFilter, BorrowedBuildPacmanRepo, BuildMetadata, Member, OwnedGlobalSettings,
+        OwnedMember, Wrapper,
     },
     status::{Code, Status},
 };
@@ -31,6 +31,13 @@ pub fn print_config(args: PrintConfigArgs) -> Status {
         with_packager,
         with_allow_failure,
         with_dereference_database_symlinks,
+        with_log_directory,
+        with_sign_packages,
+        with_sign_database,
+        with_signing_key,
+        with_keep_versions,
+        with_protected_packages,
+        with_package_extension,
     } = args;
 
     let read_build_metadata = Some(match (args.require_pkgbuild, args.require_srcinfo) {
@@ -54,6 +61,17 @@ pub fn print_config(args: PrintConfigArgs) -> Status {
         packager: with_packager.map(Wrapper::from_inner),
         allow_failure: with_allow_failure,
         dereference_database_symlinks: with_dereference_database_symlinks,
+        log_directory: with_log_directory.map(Wrapper::from_inner),
+        sign_packages: with_sign_packages,
+        sign_database: with_sign_database,
+        signing_key: with_signing_key.map(Wrapper::from_inner),
+        keep_versions: with_keep_versions,
+        protected_packages: if with_protected_packages.is_empty() {
+            None
+        } else {
+            Some(Wrapper::from_inner(with_protected_packages))
+        },
+        package_extension: with_package_extension.map(Wrapper::from_inner),
     };
 
     let mut members = Vec::new();
@@ -108,7 +126,8 @@ pub fn print_config(args: PrintConfigArgs) -> Status {
     members.sort_by(|a, b| a.directory.cmp(&b.directory));
     let members: Vec<_> = members.iter().map(Member::as_borrowed).collect();
 
-    let manifest = BuildPacmanRepo {
+    let manifest = BorrowedBuildPacmanRepo {
+        include: Vec::new(),
         global_settings: global_settings.as_borrowed(),
         members,
     };
@@ -0,0 +1,28 @@
+use super::super::{
+    args::PruneArgs,
+    manifest::{BuildPacmanRepo, ManifestOptions},
+    status::{Code, Failure, Status},
+    utils::prune_repository,
+};
+use std::num::NonZeroUsize;
+
+pub fn prune(args: PruneArgs, manifest: ManifestOptions) -> Status {
+    let PruneArgs {
+        keep_versions,
+        dry_run,
+    } = args;
+
+    let manifest = BuildPacmanRepo::from_env(manifest).map_err(|error| {
+        eprintln!("⮾ {}", error);
+        Failure::from(Code::ManifestLoadingFailure)
+    })?;
+    let global_settings = &manifest.global_settings;
+    let keep_versions = keep_versions
+        .or(global_settings.keep_versions)
+        .unwrap_or_else(|| NonZeroUsize::new(1).unwrap());
+
+    prune_repository(global_settings.repository.as_ref(), keep_versions, dry_run).map_err(|error| {
+        eprintln!("⮾ {}", error);
+        Failure::from(error)
+    })
+}
@@ -0,0 +1,111 @@
+use super::super::{
+    args::RemoveOrphansArgs,
+    manifest::ManifestOptions,
+    repository_database::RepositoryDatabase,
+    srcinfo::database::text_wrapper::PkgName,
+    status::{Code, Failure, Status},
+    utils::{run_deref_db, DbInit, DbInitValue},
+};
+use std::fs::remove_file;
+
+pub fn remove_orphans(args: RemoveOrphansArgs, manifest: ManifestOptions) -> Status {
+    let RemoveOrphansArgs { dry_run } = args;
+
+    let mut db_init = DbInit::default().with_manifest(manifest);
+    let DbInitValue {
+        manifest,
+        database,
+        error_count,
+    } = db_init.init()?;
+
+    if error_count != 0 {
+        eprintln!(
+            "⮾ Refusing to remove orphans because {} errors occurred while loading members",
+            error_count,
+        );
+        return Code::GenericFailure.into();
+    }
+
+    let global_settings = &manifest.global_settings;
+    let repository = global_settings.repository.as_ref();
+    let repository_directory = repository.parent().expect("get repository directory");
+    if !repository.exists() {
+        eprintln!(
+            "🛈 Repository database {:?} does not exist. Nothing to remove.",
+            repository,
+        );
+        return Ok(());
+    }
+
+    let protected_packages = global_settings
+        .protected_packages
+        .as_ref()
+        .map_or(&[] as &[String], AsRef::as_ref);
+
+    let mut repository_database = RepositoryDatabase::load(repository).map_err(|error| {
+        eprintln!("⮾ Cannot read {:?}: {}", repository, error);
+        Failure::from(error)
+    })?;
+
+    let orphans: Vec<(String, String)> = repository_database
+        .records()
+        .values()
+        .filter_map(|record| Some((record.name()?, record.file_name()?)))
+        .filter(|(name, _)| !database.pkgname().contains_key(&PkgName(name)))
+        .filter(|(name, _)| !protected_packages.iter().any(|protected| protected == name))
+        .map(|(name, file_name)| (name.to_string(), file_name.to_string()))
+        .collect();
+
+    if orphans.is_empty() {
+        eprintln!("🛈 No orphaned packages.");
+        return Ok(());
+    }
+
+    eprintln!("🛈 Orphaned packages:");
+    for (name, file_name) in &orphans {
+        eprintln!("  ● {} ({})", name, file_name);
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    eprintln!("  → remove from {}", repository.to_string_lossy());
+    for (name, _) in &orphans {
+        repository_database.remove(name);
+    }
+    let sign_database = global_settings.sign_database.unwrap_or(false);
+    let signing_key = global_settings.signing_key.as_ref().map(AsRef::as_ref);
+    repository_database
+        .write(repository, sign_database, signing_key)
+        .map_err(|error| {
+            eprintln!("⮾ Cannot update {:?}: {}", repository, error);
+            Failure::from(error)
+        })?;
+
+    for (_, file_name) in &orphans {
+        for file_name in [file_name.to_string(), format!("{}.sig", file_name)] {
+            let path = repository_directory.join(file_name);
+            if path.symlink_metadata().is_err() {
+                continue;
+            }
+            eprintln!("  → Delete {:?}", path);
+            remove_file(&path).map_err(|error| {
+                eprintln!("⮾ Cannot delete {:?}: {}", path, error);
+                Failure::from(error)
+            })?;
+        }
+    }
+
+    if global_settings
+        .dereference_database_symlinks
+        .unwrap_or(false)
+    {
+        run_deref_db(repository_directory).map_err(|error| {
+            eprintln!("⮾ {}", error);
+            Failure::from(error)
+        })?;
+    }
+
+    Ok(())
+}
@@ -1,13 +1,17 @@
 use super::super::{
     args::SortArgs,
+    manifest::ManifestOptions,
     status::{Code, Status},
     utils::{DbInit, DbInitValue},
 };
 
-pub fn sort(args: SortArgs) -> Status {
-    let SortArgs {} = args;
+pub fn sort(args: SortArgs, manifest: ManifestOptions) -> Status {
+    let SortArgs { arch, profile } = args;
 
-    let mut db_init = DbInit::default();
+    let mut db_init = DbInit::default()
+        .with_manifest(manifest)
+        .with_profile(profile.as_deref())
+        .with_target_arch(arch);
     let DbInitValue {
         database,
         mut error_count,
@@ -1,6 +1,6 @@
 use super::super::{
     args::SyncSrcInfoArgs,
-    manifest::{BuildMetadata, BuildPacmanRepo, Member},
+    manifest::{BuildMetadata, BuildPacmanRepo, ManifestOptions, Member},
     status::{Code, Failure, Status},
     utils::read_srcinfo_from_pkgbuild,
 };
@@ -12,13 +12,13 @@ use std::{
     path::Path,
 };
 
-pub fn sync_srcinfo(args: SyncSrcInfoArgs) -> Status {
+pub fn sync_srcinfo(args: SyncSrcInfoArgs, manifest: ManifestOptions) -> Status {
     let SyncSrcInfoArgs { update } = args;
 
     let mut outdated = 0u32;
     let mut error_count = 0u32;
 
-    let manifest = BuildPacmanRepo::from_env().map_err(|error| {
+    let manifest = BuildPacmanRepo::from_env(manifest).map_err(|error| {
         eprintln!("⮾ {}", error);
         Failure::from(Code::ManifestLoadingFailure)
     })?;
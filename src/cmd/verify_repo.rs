@@ -0,0 +1,40 @@
+use super::super::{
+    args::VerifyRepoArgs,
+    manifest::{BuildPacmanRepo, ManifestOptions},
+    repository_database::verify::verify,
+    status::{Code, Failure, Status},
+};
+
+pub fn verify_repo(_: VerifyRepoArgs, manifest: ManifestOptions) -> Status {
+    let manifest = BuildPacmanRepo::from_env(manifest).map_err(|error| {
+        eprintln!("⮾ {}", error);
+        Failure::from(Code::ManifestLoadingFailure)
+    })?;
+    let global_settings = &manifest.global_settings;
+    let repository = global_settings.repository.as_ref();
+    if !repository.exists() {
+        eprintln!("⮾ Repository database {:?} does not exist", repository);
+        return Code::RepositoryInconsistency.into();
+    }
+
+    let inconsistencies = verify(
+        repository,
+        global_settings.sign_packages.unwrap_or(false),
+        global_settings.sign_database.unwrap_or(false),
+    )
+    .map_err(|error| {
+        eprintln!("⮾ Cannot verify {:?}: {}", repository, error);
+        Failure::from(error)
+    })?;
+
+    if inconsistencies.is_empty() {
+        eprintln!("🛈 Repository is consistent.");
+        return Ok(());
+    }
+
+    for inconsistency in &inconsistencies {
+        eprintln!("⮾ {}", inconsistency);
+    }
+    eprintln!("{} inconsistencies found", inconsistencies.len());
+    Code::RepositoryInconsistency.into()
+}
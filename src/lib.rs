@@ -1,11 +1,15 @@
+#[cfg(feature = "alpm")]
 pub mod app;
 pub mod args;
+#[cfg(feature = "alpm")]
 pub mod cmd;
 pub mod manifest;
+pub mod repository_database;
 pub mod srcinfo;
 pub mod status;
 pub mod utils;
 
+#[cfg(feature = "alpm")]
 pub fn main() {
     use pipe_trait::*;
     app::App::from_env()
@@ -14,6 +18,7 @@ pub fn main() {
         .pipe(std::process::exit)
 }
 
+#[cfg(feature = "alpm")]
 pub use alpm;
 pub use argh;
 pub use git2;
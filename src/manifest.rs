@@ -1,9 +1,13 @@
 mod arch_filter;
 mod build_metadata;
 mod build_pacman_repo;
+mod fragment;
 mod global_settings;
 mod init_aur_builder;
+mod manifest_options;
 mod member;
+mod profile;
+mod setting_override;
 mod tristate;
 mod wrapper;
 
@@ -12,19 +16,29 @@ pub use build_metadata::BuildMetadata;
 pub use build_pacman_repo::{
     BorrowedBuildPacmanRepo, BuildPacmanRepo, OwnedBuildPacmanRepo, BUILD_PACMAN_REPO,
 };
-pub use global_settings::{BorrowedGlobalSettings, GlobalSettings, OwnedGlobalSettings};
+pub use fragment::{Fragment, FragmentSettings};
+pub use global_settings::{
+    BorrowedGlobalSettings, GlobalSettings, OwnedGlobalSettings, GLOBAL_SETTINGS_KEYS,
+};
 pub use init_aur_builder::{
     BorrowedInitAurBuilder, InitAurBuilder, OwnedInitAurBuilder, INIT_AUR_BUILDER,
 };
+pub use manifest_options::ManifestOptions;
 pub use member::{BorrowedMember, Member, OwnedMember};
+pub use profile::{MemberFilter, Profile};
+pub use setting_override::{SettingOverride, SETTING_OVERRIDE_PREFIX};
 pub use tristate::TriState;
 pub use wrapper::{
     ArchCollection, ArchCollectionWrapper, Associations, AurCollection, AurCollectionWrapper,
     BorrowedArchCollection, BorrowedAurCollection, BorrowedContainer, BorrowedDirectory,
-    BorrowedFailedBuildRecord, BorrowedInner, BorrowedPackager, BorrowedPacman, BorrowedRepository,
-    BorrowedWrapper, Container, ContainerWrapper, Directory, DirectoryWrapper, FailedBuildRecord,
-    FailedBuildRecordWrapper, OwnedArchCollection, OwnedAurCollection, OwnedContainer,
-    OwnedDirectory, OwnedFailedBuildRecord, OwnedInner, OwnedPackager, OwnedPacman,
-    OwnedRepository, OwnedWrapper, Packager, PackagerWrapper, Pacman, PacmanWrapper, Repository,
-    RepositoryWrapper, Wrapper,
+    BorrowedFailedBuildRecord, BorrowedInclude, BorrowedInner, BorrowedLogDirectory,
+    BorrowedPackageCollection, BorrowedPackageExtension, BorrowedPackager, BorrowedPacman,
+    BorrowedRepository, BorrowedSigningKey, BorrowedWrapper, Container, ContainerWrapper,
+    Directory, DirectoryWrapper, FailedBuildRecord, FailedBuildRecordWrapper, Include,
+    IncludeWrapper, LogDirectory, LogDirectoryWrapper, OwnedArchCollection, OwnedAurCollection,
+    OwnedContainer, OwnedDirectory, OwnedFailedBuildRecord, OwnedInclude, OwnedInner,
+    OwnedLogDirectory, OwnedPackageCollection, OwnedPackageExtension, OwnedPackager, OwnedPacman,
+    OwnedRepository, OwnedSigningKey, OwnedWrapper, PackageCollection, PackageCollectionWrapper,
+    PackageExtension, PackageExtensionWrapper, Packager, PackagerWrapper, Pacman, PacmanWrapper,
+    Repository, RepositoryWrapper, SigningKey, SigningKeyWrapper, Wrapper,
 };
@@ -1,13 +1,25 @@
+use super::super::{repository_database::archive::Compression, utils::is_package_extension};
 use super::{
     ArchCollectionWrapper, BorrowedArchCollection, BorrowedContainer, BorrowedDirectory,
-    BorrowedFailedBuildRecord, BorrowedPackager, BorrowedPacman, BorrowedRepository,
-    ContainerWrapper, DirectoryWrapper, FailedBuildRecordWrapper, GlobalSettings, Member,
-    OwnedArchCollection, OwnedContainer, OwnedDirectory, OwnedFailedBuildRecord, OwnedMember,
-    OwnedPackager, OwnedPacman, OwnedRepository, PackagerWrapper, PacmanWrapper, RepositoryWrapper,
+    BorrowedFailedBuildRecord, BorrowedInclude, BorrowedLogDirectory, BorrowedPackageCollection,
+    BorrowedPackageExtension, BorrowedPackager, BorrowedPacman, BorrowedRepository,
+    BorrowedSigningKey, ContainerWrapper, DirectoryWrapper, FailedBuildRecordWrapper, Fragment,
+    GlobalSettings, IncludeWrapper, LogDirectoryWrapper, ManifestOptions, Member, MemberFilter,
+    OwnedArchCollection, OwnedContainer, OwnedDirectory, OwnedFailedBuildRecord, OwnedInclude,
+    OwnedLogDirectory, OwnedMember, OwnedPackageCollection, OwnedPackageExtension, OwnedPackager,
+    OwnedPacman, OwnedRepository, OwnedSigningKey, PackageCollectionWrapper,
+    PackageExtensionWrapper, PackagerWrapper, PacmanWrapper, Profile, RepositoryWrapper,
+    SettingOverride, SigningKeyWrapper, Wrapper,
 };
+use indexmap::IndexMap;
 use pipe_trait::*;
 use serde::{Deserialize, Serialize};
-use std::{fs::File, io::ErrorKind, path::Path};
+use serde_yaml::{Mapping, Value};
+use std::{
+    env::current_dir,
+    fs::{canonicalize, File},
+    path::{Path, PathBuf},
+};
 
 pub const BUILD_PACMAN_REPO: &str = "build-pacman-repo.yaml";
 
@@ -20,7 +32,12 @@ pub struct BuildPacmanRepo<
     ArchCollection,
     Pacman,
     Packager,
+    LogDirectory,
+    SigningKey,
+    PackageCollection,
+    PackageExtension,
     Directory,
+    Include,
 > where
     Repository: RepositoryWrapper,
     Container: ContainerWrapper,
@@ -28,10 +45,29 @@ pub struct BuildPacmanRepo<
     ArchCollection: ArchCollectionWrapper,
     Pacman: PacmanWrapper,
     Packager: PackagerWrapper,
+    LogDirectory: LogDirectoryWrapper,
+    SigningKey: SigningKeyWrapper,
+    PackageCollection: PackageCollectionWrapper,
+    PackageExtension: PackageExtensionWrapper,
     Directory: DirectoryWrapper,
+    Include: IncludeWrapper,
 {
-    pub global_settings:
-        GlobalSettings<Repository, Container, FailedBuildRecord, ArchCollection, Pacman, Packager>,
+    /// Manifest fragments that add members, relative to this manifest.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<Include>,
+    pub global_settings: GlobalSettings<
+        Repository,
+        Container,
+        FailedBuildRecord,
+        ArchCollection,
+        Pacman,
+        Packager,
+        LogDirectory,
+        SigningKey,
+        PackageCollection,
+        PackageExtension,
+    >,
+    #[serde(bound(deserialize = "Member<Directory, Pacman>: Deserialize<'de>"))]
     pub members: Vec<Member<Directory, Pacman>>,
 }
 
@@ -42,7 +78,12 @@ pub type OwnedBuildPacmanRepo = BuildPacmanRepo<
     OwnedArchCollection,
     OwnedPacman,
     OwnedPackager,
+    OwnedLogDirectory,
+    OwnedSigningKey,
+    OwnedPackageCollection,
+    OwnedPackageExtension,
     OwnedDirectory,
+    OwnedInclude,
 >;
 pub type BorrowedBuildPacmanRepo<'a> = BuildPacmanRepo<
     BorrowedRepository<'a>,
@@ -51,10 +92,28 @@ pub type BorrowedBuildPacmanRepo<'a> = BuildPacmanRepo<
     BorrowedArchCollection<'a>,
     BorrowedPacman<'a>,
     BorrowedPackager<'a>,
+    BorrowedLogDirectory<'a>,
+    BorrowedSigningKey<'a>,
+    BorrowedPackageCollection<'a>,
+    BorrowedPackageExtension<'a>,
     BorrowedDirectory<'a>,
+    BorrowedInclude<'a>,
 >;
 
-impl<Repository, Container, FailedBuildRecord, ArchCollection, Pacman, Packager, Directory>
+impl<
+        Repository,
+        Container,
+        FailedBuildRecord,
+        ArchCollection,
+        Pacman,
+        Packager,
+        LogDirectory,
+        SigningKey,
+        PackageCollection,
+        PackageExtension,
+        Directory,
+        Include,
+    >
     BuildPacmanRepo<
         Repository,
         Container,
@@ -62,7 +121,12 @@ impl<Repository, Container, FailedBuildRecord, ArchCollection, Pacman, Packager,
         ArchCollection,
         Pacman,
         Packager,
+        LogDirectory,
+        SigningKey,
+        PackageCollection,
+        PackageExtension,
         Directory,
+        Include,
     >
 where
     Repository: RepositoryWrapper,
@@ -71,10 +135,20 @@ where
     ArchCollection: ArchCollectionWrapper,
     Pacman: PacmanWrapper,
     Packager: PackagerWrapper,
+    LogDirectory: LogDirectoryWrapper,
+    SigningKey: SigningKeyWrapper,
+    PackageCollection: PackageCollectionWrapper,
+    PackageExtension: PackageExtensionWrapper,
     Directory: DirectoryWrapper,
+    Include: IncludeWrapper,
 {
     pub fn as_borrowed(&self) -> BorrowedBuildPacmanRepo<'_> {
         BuildPacmanRepo {
+            include: self
+                .include
+                .iter()
+                .map(|include| include.as_ref().pipe(Wrapper::from_inner))
+                .collect(),
             global_settings: self.global_settings.as_borrowed(),
             members: self.members.iter().map(Member::as_borrowed).collect(),
         }
@@ -88,25 +162,206 @@ where
 }
 
 impl OwnedBuildPacmanRepo {
-    pub fn from_env() -> Result<Self, String> {
-        BuildPacmanRepo::from_file(BUILD_PACMAN_REPO.as_ref())
+    /// Load the manifest file of `options`, or the manifest that [`locate`](Self::locate) finds if it is `None`.
+    ///
+    /// Relative paths in the manifest are resolved against the directory of the manifest.
+    /// Global settings are overridden by `PACMAN_REPO_BUILDER_<KEY>` environment variables, then by `options.overrides`.
+    pub fn from_env(options: ManifestOptions) -> Result<Self, String> {
+        BuildPacmanRepo::from_env_with_profile(options, None)
+    }
+
+    /// Same as [`from_env`](Self::from_env), but apply the profile named `profile` if it is not `None`.
+    pub fn from_env_with_profile(
+        options: ManifestOptions,
+        profile: Option<&str>,
+    ) -> Result<Self, String> {
+        let file = match options.file {
+            Some(file) => file.to_path_buf(),
+            None => BuildPacmanRepo::locate()?,
+        };
+        let mut overrides = SettingOverride::from_env_vars();
+        overrides.extend_from_slice(options.overrides);
+        let (mut manifest, member_filter, changes) = if profile.is_none() && overrides.is_empty() {
+            let manifest = BuildPacmanRepo::from_file(&file)?;
+            (manifest, MemberFilter::default(), Mapping::new())
+        } else {
+            BuildPacmanRepo::from_file_with_changes(&file, profile, &overrides)?
+        };
+        if let Some(directory) = file.parent() {
+            manifest.resolve_paths(directory);
+        }
+        manifest.expand_members()?;
+        manifest.include_fragments(&file, &changes)?;
+        manifest
+            .members
+            .retain(|member| member_filter.matches(member.directory.as_ref()));
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Reject settings that deserialize fine but cannot be used.
+    fn validate(&self) -> Result<(), String> {
+        if let Some(package_extension) = &self.global_settings.package_extension {
+            let package_extension: &str = package_extension.as_ref();
+            if !is_package_extension(package_extension)
+                || Compression::from_extension(package_extension).is_none()
+            {
+                return Err(format!(
+                    "package-extension must be .pkg.tar followed by .gz, .xz, .zst, or nothing (such as .pkg.tar.zst) but it is {:?}",
+                    package_extension,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Find `build-pacman-repo.yaml` in the current directory or the closest of its ancestors.
+    ///
+    /// The returned path is relative to the current directory.
+    pub fn locate() -> Result<PathBuf, String> {
+        let working_directory = current_dir()
+            .map_err(|error| format!("cannot get the current directory: {}", error))?;
+        let mut relative = PathBuf::new();
+        for directory in working_directory.ancestors() {
+            if directory.join(BUILD_PACMAN_REPO).is_file() {
+                return Ok(relative.join(BUILD_PACMAN_REPO));
+            }
+            relative.push("..");
+        }
+        Err(format!(
+            "cannot find {} in {:?} or any of its parent directories",
+            BUILD_PACMAN_REPO, working_directory,
+        ))
     }
 
     pub fn from_file(file: &Path) -> Result<Self, String> {
-        match File::open(file) {
-            Ok(content) => content
-                .pipe(serde_yaml::from_reader::<_, OwnedBuildPacmanRepo>)
-                .map_err(|error| {
-                    format!(
-                        "cannot deserialize {:?} as BuildPacmanRepo: {}",
-                        file, error,
-                    )
-                })?
-                .pipe(Ok),
-            Err(error) => match error.kind() {
-                ErrorKind::NotFound => Ok(BuildPacmanRepo::default()),
-                _ => Err(format!("cannot open {:?} as a file: {}", file, error)),
-            },
+        File::open(file)
+            .map_err(|error| format!("cannot open {:?} as a file: {}", file, error))?
+            .pipe(serde_yaml::from_reader::<_, OwnedBuildPacmanRepo>)
+            .map_err(|error| {
+                format!(
+                    "cannot deserialize {:?} as BuildPacmanRepo: {}",
+                    file, error,
+                )
+            })
+    }
+
+    /// Load the manifest at `file` with the profile named `profile`, then `overrides`, applied to the global settings.
+    ///
+    /// Also return the global settings that the profile and `overrides` set, for the fragments to apply on top of their own.
+    fn from_file_with_changes(
+        file: &Path,
+        profile: Option<&str>,
+        overrides: &[SettingOverride],
+    ) -> Result<(Self, MemberFilter, Mapping), String> {
+        let mut document: Value = File::open(file)
+            .map_err(|error| format!("cannot open {:?} as a file: {}", file, error))?
+            .pipe(serde_yaml::from_reader)
+            .map_err(|error| format!("cannot deserialize {:?} as YAML: {}", file, error))?;
+        let mut changed_keys: Vec<Value> = Vec::new();
+        let member_filter = match profile {
+            Some(profile) => {
+                let profile = Profile::take(&mut document, profile)
+                    .and_then(|profile| profile.apply(&mut document).map(|()| profile))
+                    .map_err(|error| format!("{:?}: {}", file, error))?;
+                changed_keys.extend(profile.global_settings.keys().cloned());
+                profile.members
+            }
+            None => MemberFilter::default(),
+        };
+        let global_settings = document
+            .get_mut("global-settings")
+            .and_then(Value::as_mapping_mut)
+            .ok_or_else(|| format!("{:?}: global-settings is not a mapping", file))?;
+        for setting_override in overrides {
+            setting_override.apply(global_settings)?;
+            changed_keys.push(setting_override.key.as_str().into());
+        }
+        let changes: Mapping = changed_keys
+            .into_iter()
+            .filter_map(|key| {
+                let value = global_settings.get(&key)?.clone();
+                Some((key, value))
+            })
+            .collect();
+        let manifest = serde_yaml::from_value(document).map_err(|error| {
+            format!(
+                "cannot deserialize {:?} as BuildPacmanRepo: {}",
+                file, error,
+            )
+        })?;
+        Ok((manifest, member_filter, changes))
+    }
+
+    /// Replace `glob` and `scan` members with the members they expand into.
+    fn expand_members(&mut self) -> Result<(), String> {
+        let container = match &self.global_settings.container {
+            Some(container) => container.inner().to_path_buf(),
+            None => PathBuf::new(),
+        };
+        let mut members = Vec::new();
+        for member in &self.members {
+            members.extend(member.expand(&container)?);
+        }
+        self.members = members;
+        Ok(())
+    }
+
+    /// Add the members of every fragment in `include`, then check that no member is listed twice.
+    ///
+    /// `changes` are the global settings that a profile or an override set, which take precedence over the fragments.
+    fn include_fragments(&mut self, manifest_file: &Path, changes: &Mapping) -> Result<(), String> {
+        let directory = manifest_file.parent().unwrap_or_else(|| Path::new(""));
+        let mut sources = vec![manifest_file.to_path_buf(); self.members.len()];
+        for include in &self.include {
+            let file = directory.join(include.inner());
+            let members = Fragment::load_members(&file, changes)?;
+            sources.resize(sources.len() + members.len(), file);
+            self.members.extend(members);
+        }
+
+        let mut duplications: IndexMap<PathBuf, Vec<&Path>> = IndexMap::new();
+        for (member, source) in self.resolve_members().zip(&sources) {
+            let directory = member.directory.into_inner();
+            let directory = canonicalize(&directory).unwrap_or(directory);
+            duplications.entry(directory).or_default().push(source);
+        }
+        duplications.retain(|_, sources| sources.len() > 1);
+        if duplications.is_empty() {
+            return Ok(());
+        }
+        let mut message = "duplicate members detected".to_string();
+        for (directory, sources) in duplications {
+            message += &format!("\n  * directory: {}", directory.to_string_lossy());
+            for source in sources {
+                message += &format!("\n    - manifest: {}", source.to_string_lossy());
+            }
+        }
+        Err(message)
+    }
+
+    /// Make relative paths of the global settings relative to `directory` instead.
+    ///
+    /// Member directories follow because they are relative to `container`.
+    fn resolve_paths(&mut self, directory: &Path) {
+        if directory.as_os_str().is_empty() {
+            return;
         }
+        let global_settings = &mut self.global_settings;
+        global_settings.repository =
+            Wrapper::from_inner(directory.join(global_settings.repository.inner()));
+        let container = match &global_settings.container {
+            Some(container) => directory.join(container.inner()),
+            None => directory.to_path_buf(),
+        };
+        global_settings.container = Some(Wrapper::from_inner(container));
+        global_settings.record_failed_builds = global_settings
+            .record_failed_builds
+            .as_ref()
+            .map(|file| Wrapper::from_inner(directory.join(file.inner())));
+        global_settings.log_directory = global_settings
+            .log_directory
+            .as_ref()
+            .map(|log_directory| Wrapper::from_inner(directory.join(log_directory.inner())));
     }
 }
@@ -0,0 +1,137 @@
+use super::{BuildMetadata, OwnedGlobalSettings, OwnedMember, TriState, Wrapper};
+use pipe_trait::*;
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::{
+    env::current_dir,
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+/// Manifest file that contributes members to the manifest that includes it.
+#[derive(Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Fragment {
+    #[serde(default)]
+    pub global_settings: FragmentSettings,
+    #[serde(default)]
+    pub members: Vec<OwnedMember>,
+}
+
+/// Global settings that a fragment can override for its own members.
+#[derive(Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct FragmentSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_build_metadata: Option<BuildMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub install_missing_dependencies: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clean_before_build: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clean_after_build: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub force_rebuild: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check: Option<TriState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pacman: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_failure: Option<bool>,
+}
+
+/// Keys of the fields of [`FragmentSettings`] that profiles and setting overrides take the place of.
+///
+/// `container` is left out because it is relative to the fragment.
+const OVERRIDABLE_KEYS: &[&str] = &[
+    "read-build-metadata",
+    "install-missing-dependencies",
+    "clean-before-build",
+    "clean-after-build",
+    "force-rebuild",
+    "check",
+    "pacman",
+    "allow-failure",
+];
+
+impl Fragment {
+    pub fn from_file(file: &Path) -> Result<Self, String> {
+        File::open(file)
+            .map_err(|error| format!("cannot open {:?} as a file: {}", file, error))?
+            .pipe(serde_yaml::from_reader::<_, Fragment>)
+            .map_err(|error| format!("cannot deserialize {:?} as Fragment: {}", file, error))
+    }
+
+    /// Load the fragment at `file` and resolve its members against its own settings,
+    /// except the ones that `changes` (a profile or setting overrides of the including manifest) set.
+    ///
+    /// Directories of the returned members are absolute, so the container of the including manifest does not apply to them.
+    pub fn load_members(file: &Path, changes: &Mapping) -> Result<Vec<OwnedMember>, String> {
+        let Fragment {
+            global_settings,
+            members,
+        } = Fragment::from_file(file)?;
+        let global_settings = global_settings.overridden_by(changes)?;
+
+        let directory = current_dir()
+            .map_err(|error| format!("cannot get the current directory: {}", error))?
+            .join(file.parent().unwrap_or_else(|| Path::new("")));
+        let container = match &global_settings.container {
+            Some(container) => directory.join(container),
+            None => directory,
+        };
+        let settings = global_settings.with_container(container.clone());
+
+        let mut resolved_members = Vec::new();
+        for member in &members {
+            let expanded = member
+                .expand(&container)
+                .map_err(|error| format!("{:?}: {}", file, error))?;
+            resolved_members.extend(expanded.iter().map(|member| member.resolve(&settings)));
+        }
+        Ok(resolved_members)
+    }
+}
+
+impl FragmentSettings {
+    /// Replace the fields that `changes` set, other than `container`.
+    fn overridden_by(self, changes: &Mapping) -> Result<Self, String> {
+        let changes: Mapping = changes
+            .iter()
+            .filter(|(key, _)| matches!(key.as_str(), Some(key) if OVERRIDABLE_KEYS.contains(&key)))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        let changes: FragmentSettings = serde_yaml::from_value(Value::Mapping(changes))
+            .map_err(|error| format!("cannot apply changes to a fragment: {}", error))?;
+        Ok(FragmentSettings {
+            container: self.container,
+            read_build_metadata: changes.read_build_metadata.or(self.read_build_metadata),
+            install_missing_dependencies: changes
+                .install_missing_dependencies
+                .or(self.install_missing_dependencies),
+            clean_before_build: changes.clean_before_build.or(self.clean_before_build),
+            clean_after_build: changes.clean_after_build.or(self.clean_after_build),
+            force_rebuild: changes.force_rebuild.or(self.force_rebuild),
+            check: changes.check.or(self.check),
+            pacman: changes.pacman.or(self.pacman),
+            allow_failure: changes.allow_failure.or(self.allow_failure),
+        })
+    }
+
+    fn with_container(self, container: PathBuf) -> OwnedGlobalSettings {
+        OwnedGlobalSettings {
+            container: Some(Wrapper::from_inner(container)),
+            read_build_metadata: self.read_build_metadata,
+            install_missing_dependencies: self.install_missing_dependencies,
+            clean_before_build: self.clean_before_build,
+            clean_after_build: self.clean_after_build,
+            force_rebuild: self.force_rebuild,
+            check: self.check,
+            pacman: self.pacman.map(Wrapper::from_inner),
+            allow_failure: self.allow_failure,
+            ..Default::default()
+        }
+    }
+}
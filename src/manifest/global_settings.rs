@@ -1,13 +1,17 @@
 use super::{
     ArchCollectionWrapper, ArchFilter, BorrowedArchCollection, BorrowedContainer,
-    BorrowedFailedBuildRecord, BorrowedPackager, BorrowedPacman, BorrowedRepository,
-    BorrowedWrapper, BuildMetadata, ContainerWrapper, FailedBuildRecordWrapper,
-    OwnedArchCollection, OwnedContainer, OwnedFailedBuildRecord, OwnedPackager, OwnedPacman,
-    OwnedRepository, OwnedWrapper, PackagerWrapper, PacmanWrapper, RepositoryWrapper, TriState,
-    Wrapper,
+    BorrowedFailedBuildRecord, BorrowedLogDirectory, BorrowedPackageCollection,
+    BorrowedPackageExtension, BorrowedPackager, BorrowedPacman, BorrowedRepository,
+    BorrowedSigningKey, BorrowedWrapper, BuildMetadata, ContainerWrapper, FailedBuildRecordWrapper,
+    LogDirectoryWrapper, OwnedArchCollection, OwnedContainer, OwnedFailedBuildRecord,
+    OwnedLogDirectory, OwnedPackageCollection, OwnedPackageExtension, OwnedPackager, OwnedPacman,
+    OwnedRepository, OwnedSigningKey, OwnedWrapper, PackageCollectionWrapper,
+    PackageExtensionWrapper, PackagerWrapper, PacmanWrapper, RepositoryWrapper, SigningKeyWrapper,
+    TriState, Wrapper,
 };
 use pipe_trait::*;
 use serde::{Deserialize, Serialize};
+use std::num::NonZeroUsize;
 
 #[derive(Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "kebab-case")]
@@ -18,6 +22,10 @@ pub struct GlobalSettings<
     ArchCollection,
     Pacman,
     Packager,
+    LogDirectory,
+    SigningKey,
+    PackageCollection,
+    PackageExtension,
 > where
     Repository: RepositoryWrapper,
     Container: ContainerWrapper,
@@ -25,6 +33,10 @@ pub struct GlobalSettings<
     ArchCollection: ArchCollectionWrapper,
     Pacman: PacmanWrapper,
     Packager: PackagerWrapper,
+    LogDirectory: LogDirectoryWrapper,
+    SigningKey: SigningKeyWrapper,
+    PackageCollection: PackageCollectionWrapper,
+    PackageExtension: PackageExtensionWrapper,
 {
     pub repository: Repository,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -53,8 +65,47 @@ pub struct GlobalSettings<
     pub allow_failure: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dereference_database_symlinks: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_directory: Option<LogDirectory>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sign_packages: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sign_database: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_key: Option<SigningKey>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_versions: Option<NonZeroUsize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protected_packages: Option<PackageCollection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package_extension: Option<PackageExtension>,
 }
 
+/// Keys of the fields of [`GlobalSettings`] in the manifest file.
+pub const GLOBAL_SETTINGS_KEYS: &[&str] = &[
+    "repository",
+    "container",
+    "read-build-metadata",
+    "record-failed-builds",
+    "install-missing-dependencies",
+    "clean-before-build",
+    "clean-after-build",
+    "force-rebuild",
+    "arch-filter",
+    "check",
+    "pacman",
+    "packager",
+    "allow-failure",
+    "dereference-database-symlinks",
+    "log-directory",
+    "sign-packages",
+    "sign-database",
+    "signing-key",
+    "keep-versions",
+    "protected-packages",
+    "package-extension",
+];
+
 pub type OwnedGlobalSettings = GlobalSettings<
     OwnedRepository,
     OwnedContainer,
@@ -62,6 +113,10 @@ pub type OwnedGlobalSettings = GlobalSettings<
     OwnedArchCollection,
     OwnedPacman,
     OwnedPackager,
+    OwnedLogDirectory,
+    OwnedSigningKey,
+    OwnedPackageCollection,
+    OwnedPackageExtension,
 >;
 pub type BorrowedGlobalSettings<'a> = GlobalSettings<
     BorrowedRepository<'a>,
@@ -70,10 +125,36 @@ pub type BorrowedGlobalSettings<'a> = GlobalSettings<
     BorrowedArchCollection<'a>,
     BorrowedPacman<'a>,
     BorrowedPackager<'a>,
+    BorrowedLogDirectory<'a>,
+    BorrowedSigningKey<'a>,
+    BorrowedPackageCollection<'a>,
+    BorrowedPackageExtension<'a>,
 >;
 
-impl<Repository, Container, FailedBuildRecord, ArchCollection, Pacman, Packager>
-    GlobalSettings<Repository, Container, FailedBuildRecord, ArchCollection, Pacman, Packager>
+impl<
+        Repository,
+        Container,
+        FailedBuildRecord,
+        ArchCollection,
+        Pacman,
+        Packager,
+        LogDirectory,
+        SigningKey,
+        PackageCollection,
+        PackageExtension,
+    >
+    GlobalSettings<
+        Repository,
+        Container,
+        FailedBuildRecord,
+        ArchCollection,
+        Pacman,
+        Packager,
+        LogDirectory,
+        SigningKey,
+        PackageCollection,
+        PackageExtension,
+    >
 where
     Repository: RepositoryWrapper,
     Container: ContainerWrapper,
@@ -81,6 +162,10 @@ where
     ArchCollection: ArchCollectionWrapper,
     Pacman: PacmanWrapper,
     Packager: PackagerWrapper,
+    LogDirectory: LogDirectoryWrapper,
+    SigningKey: SigningKeyWrapper,
+    PackageCollection: PackageCollectionWrapper,
+    PackageExtension: PackageExtensionWrapper,
 {
     pub fn as_borrowed(&self) -> BorrowedGlobalSettings<'_> {
         macro_rules! convert_option {
@@ -104,6 +189,13 @@ where
             packager: convert_option!(packager),
             allow_failure: self.allow_failure,
             dereference_database_symlinks: self.dereference_database_symlinks,
+            log_directory: convert_option!(log_directory),
+            sign_packages: self.sign_packages,
+            sign_database: self.sign_database,
+            signing_key: convert_option!(signing_key),
+            keep_versions: self.keep_versions,
+            protected_packages: convert_option!(protected_packages),
+            package_extension: convert_option!(package_extension),
         }
     }
 
@@ -129,6 +221,13 @@ where
             packager: convert_option!(packager),
             allow_failure: self.allow_failure,
             dereference_database_symlinks: self.dereference_database_symlinks,
+            log_directory: convert_option!(log_directory),
+            sign_packages: self.sign_packages,
+            sign_database: self.sign_database,
+            signing_key: convert_option!(signing_key),
+            keep_versions: self.keep_versions,
+            protected_packages: convert_option!(protected_packages),
+            package_extension: convert_option!(package_extension),
         }
     }
 }
@@ -1,9 +1,13 @@
 use super::{
     ArchCollectionWrapper, AurCollectionWrapper, BorrowedArchCollection, BorrowedAurCollection,
-    BorrowedContainer, BorrowedFailedBuildRecord, BorrowedPackager, BorrowedPacman,
-    BorrowedRepository, ContainerWrapper, FailedBuildRecordWrapper, GlobalSettings,
-    OwnedArchCollection, OwnedAurCollection, OwnedContainer, OwnedFailedBuildRecord, OwnedPackager,
-    OwnedPacman, OwnedRepository, PackagerWrapper, PacmanWrapper, RepositoryWrapper, Wrapper,
+    BorrowedContainer, BorrowedFailedBuildRecord, BorrowedLogDirectory, BorrowedPackageCollection,
+    BorrowedPackageExtension, BorrowedPackager, BorrowedPacman, BorrowedRepository,
+    BorrowedSigningKey, ContainerWrapper, FailedBuildRecordWrapper, GlobalSettings,
+    LogDirectoryWrapper, OwnedArchCollection, OwnedAurCollection, OwnedContainer,
+    OwnedFailedBuildRecord, OwnedLogDirectory, OwnedPackageCollection, OwnedPackageExtension,
+    OwnedPackager, OwnedPacman, OwnedRepository, OwnedSigningKey, PackageCollectionWrapper,
+    PackageExtensionWrapper, PackagerWrapper, PacmanWrapper, RepositoryWrapper, SigningKeyWrapper,
+    Wrapper,
 };
 use pipe_trait::*;
 use serde::{Deserialize, Serialize};
@@ -20,6 +24,10 @@ pub struct InitAurBuilder<
     ArchCollection,
     Pacman,
     Packager,
+    LogDirectory,
+    SigningKey,
+    PackageCollection,
+    PackageExtension,
     AurCollection,
 > where
     Repository: RepositoryWrapper,
@@ -28,10 +36,24 @@ pub struct InitAurBuilder<
     ArchCollection: ArchCollectionWrapper,
     Pacman: PacmanWrapper,
     Packager: PackagerWrapper,
+    LogDirectory: LogDirectoryWrapper,
+    SigningKey: SigningKeyWrapper,
+    PackageCollection: PackageCollectionWrapper,
+    PackageExtension: PackageExtensionWrapper,
     AurCollection: AurCollectionWrapper,
 {
-    pub global_settings:
-        GlobalSettings<Repository, Container, FailedBuildRecord, ArchCollection, Pacman, Packager>,
+    pub global_settings: GlobalSettings<
+        Repository,
+        Container,
+        FailedBuildRecord,
+        ArchCollection,
+        Pacman,
+        Packager,
+        LogDirectory,
+        SigningKey,
+        PackageCollection,
+        PackageExtension,
+    >,
     pub aur_package_names: AurCollection,
 }
 
@@ -42,6 +64,10 @@ pub type OwnedInitAurBuilder = InitAurBuilder<
     OwnedArchCollection,
     OwnedPacman,
     OwnedPackager,
+    OwnedLogDirectory,
+    OwnedSigningKey,
+    OwnedPackageCollection,
+    OwnedPackageExtension,
     OwnedAurCollection,
 >;
 
@@ -52,10 +78,26 @@ pub type BorrowedInitAurBuilder<'a> = InitAurBuilder<
     BorrowedArchCollection<'a>,
     BorrowedPacman<'a>,
     BorrowedPackager<'a>,
+    BorrowedLogDirectory<'a>,
+    BorrowedSigningKey<'a>,
+    BorrowedPackageCollection<'a>,
+    BorrowedPackageExtension<'a>,
     BorrowedAurCollection<'a>,
 >;
 
-impl<Repository, Container, FailedBuildRecord, ArchCollection, Pacman, Packager, AurCollection>
+impl<
+        Repository,
+        Container,
+        FailedBuildRecord,
+        ArchCollection,
+        Pacman,
+        Packager,
+        LogDirectory,
+        SigningKey,
+        PackageCollection,
+        PackageExtension,
+        AurCollection,
+    >
     InitAurBuilder<
         Repository,
         Container,
@@ -63,6 +105,10 @@ impl<Repository, Container, FailedBuildRecord, ArchCollection, Pacman, Packager,
         ArchCollection,
         Pacman,
         Packager,
+        LogDirectory,
+        SigningKey,
+        PackageCollection,
+        PackageExtension,
         AurCollection,
     >
 where
@@ -72,6 +118,10 @@ where
     ArchCollection: ArchCollectionWrapper,
     Pacman: PacmanWrapper,
     Packager: PackagerWrapper,
+    LogDirectory: LogDirectoryWrapper,
+    SigningKey: SigningKeyWrapper,
+    PackageCollection: PackageCollectionWrapper,
+    PackageExtension: PackageExtensionWrapper,
     AurCollection: AurCollectionWrapper,
 {
     pub fn with_global_settings(
@@ -83,6 +133,10 @@ where
             ArchCollection,
             Pacman,
             Packager,
+            LogDirectory,
+            SigningKey,
+            PackageCollection,
+            PackageExtension,
         >,
     ) -> Self {
         self.global_settings = global_settings;
@@ -0,0 +1,20 @@
+use super::SettingOverride;
+use std::path::Path;
+
+/// How the commands find and adjust the manifest.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct ManifestOptions<'a> {
+    /// Path to the manifest file, or `None` to search for it.
+    pub file: Option<&'a Path>,
+    /// Global settings from `--set`, which take priority over the environment variables.
+    pub overrides: &'a [SettingOverride],
+}
+
+impl<'a> ManifestOptions<'a> {
+    pub fn with_file(file: &'a Path) -> Self {
+        ManifestOptions {
+            file: Some(file),
+            ..Default::default()
+        }
+    }
+}
@@ -1,20 +1,40 @@
+use super::super::utils::{glob_directories, wildcard_match};
 use super::{
     ArchCollectionWrapper, BorrowedDirectory, BorrowedPacman, BorrowedWrapper, BuildMetadata,
-    ContainerWrapper, DirectoryWrapper, FailedBuildRecordWrapper, GlobalSettings, OwnedDirectory,
-    OwnedPacman, OwnedWrapper, PackagerWrapper, PacmanWrapper, RepositoryWrapper, TriState,
-    Wrapper,
+    ContainerWrapper, DirectoryWrapper, FailedBuildRecordWrapper, GlobalSettings,
+    LogDirectoryWrapper, OwnedDirectory, OwnedPacman, OwnedWrapper, PackageCollectionWrapper,
+    PackageExtensionWrapper, PackagerWrapper, PacmanWrapper, RepositoryWrapper, SigningKeyWrapper,
+    TriState, Wrapper,
 };
 use pipe_trait::*;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
-#[serde(rename_all = "kebab-case")]
+#[serde(
+    rename_all = "kebab-case",
+    try_from = "UncheckedMember<Directory, Pacman>"
+)]
 pub struct Member<Directory, Pacman>
 where
     Directory: DirectoryWrapper,
     Pacman: PacmanWrapper,
 {
+    #[serde(default, skip_serializing_if = "is_empty_directory")]
     pub directory: Directory,
+    /// Expand into a member for every directory that matches the pattern.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub glob: Option<Directory>,
+    /// Expand into a member for every subdirectory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scan: Option<Directory>,
+    /// Patterns of directories to leave out of `glob` and `scan`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<Directory>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_pkgbuild: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_srcinfo: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub read_build_metadata: Option<BuildMetadata>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -36,6 +56,84 @@ where
 pub type OwnedMember = Member<OwnedDirectory, OwnedPacman>;
 pub type BorrowedMember<'a> = Member<BorrowedDirectory<'a>, BorrowedPacman<'a>>;
 
+const MISSING_LOCATION: &str = "member needs one of directory, glob, or scan";
+const CONFLICTING_LOCATION: &str = "member cannot have more than one of directory, glob, and scan";
+
+/// Fields of a [`Member`] as written in the manifest, before they are checked.
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct UncheckedMember<Directory, Pacman> {
+    #[serde(default)]
+    directory: Directory,
+    glob: Option<Directory>,
+    scan: Option<Directory>,
+    #[serde(default)]
+    exclude: Vec<Directory>,
+    require_pkgbuild: Option<bool>,
+    require_srcinfo: Option<bool>,
+    read_build_metadata: Option<BuildMetadata>,
+    install_missing_dependencies: Option<bool>,
+    clean_before_build: Option<bool>,
+    clean_after_build: Option<bool>,
+    force_rebuild: Option<bool>,
+    check: Option<TriState>,
+    pacman: Option<Pacman>,
+    allow_failure: Option<bool>,
+}
+
+impl<Directory, Pacman> TryFrom<UncheckedMember<Directory, Pacman>> for Member<Directory, Pacman>
+where
+    Directory: DirectoryWrapper,
+    Pacman: PacmanWrapper,
+{
+    type Error = &'static str;
+
+    fn try_from(member: UncheckedMember<Directory, Pacman>) -> Result<Self, Self::Error> {
+        let UncheckedMember {
+            directory,
+            glob,
+            scan,
+            exclude,
+            require_pkgbuild,
+            require_srcinfo,
+            read_build_metadata,
+            install_missing_dependencies,
+            clean_before_build,
+            clean_after_build,
+            force_rebuild,
+            check,
+            pacman,
+            allow_failure,
+        } = member;
+        let member = Member {
+            directory,
+            glob,
+            scan,
+            exclude,
+            require_pkgbuild,
+            require_srcinfo,
+            read_build_metadata,
+            install_missing_dependencies,
+            clean_before_build,
+            clean_after_build,
+            force_rebuild,
+            check,
+            pacman,
+            allow_failure,
+        };
+        member.check_location()?;
+        Ok(member)
+    }
+}
+
+fn directory_to_owned(directory: &impl DirectoryWrapper) -> OwnedDirectory {
+    directory.as_ref().to_path_buf().pipe(Wrapper::from_inner)
+}
+
+fn is_empty_directory(directory: &impl DirectoryWrapper) -> bool {
+    directory.as_ref().as_os_str().is_empty()
+}
+
 impl<Directory, Pacman> Member<Directory, Pacman>
 where
     Directory: DirectoryWrapper,
@@ -44,6 +142,21 @@ where
     pub fn as_borrowed(&self) -> BorrowedMember<'_> {
         BorrowedMember {
             directory: self.directory.as_ref().pipe(Wrapper::from_inner),
+            glob: self
+                .glob
+                .as_ref()
+                .map(|glob| glob.as_ref().pipe(Wrapper::from_inner)),
+            scan: self
+                .scan
+                .as_ref()
+                .map(|scan| scan.as_ref().pipe(Wrapper::from_inner)),
+            exclude: self
+                .exclude
+                .iter()
+                .map(|pattern| pattern.as_ref().pipe(Wrapper::from_inner))
+                .collect(),
+            require_pkgbuild: self.require_pkgbuild,
+            require_srcinfo: self.require_srcinfo,
             read_build_metadata: self.read_build_metadata,
             install_missing_dependencies: self.install_missing_dependencies,
             clean_before_build: self.clean_before_build,
@@ -62,6 +175,11 @@ where
                 .as_ref()
                 .to_path_buf()
                 .pipe(Wrapper::from_inner),
+            glob: self.glob.as_ref().map(directory_to_owned),
+            scan: self.scan.as_ref().map(directory_to_owned),
+            exclude: self.exclude.iter().map(directory_to_owned).collect(),
+            require_pkgbuild: self.require_pkgbuild,
+            require_srcinfo: self.require_srcinfo,
             read_build_metadata: self.read_build_metadata,
             install_missing_dependencies: self.install_missing_dependencies,
             clean_before_build: self.clean_before_build,
@@ -82,6 +200,10 @@ where
             impl ArchCollectionWrapper,
             impl PacmanWrapper,
             impl PackagerWrapper,
+            impl LogDirectoryWrapper,
+            impl SigningKeyWrapper,
+            impl PackageCollectionWrapper,
+            impl PackageExtensionWrapper,
         >,
     ) -> OwnedMember {
         macro_rules! resolve_memcpy_option {
@@ -116,6 +238,11 @@ where
             } else {
                 self.directory.as_ref().to_path_buf()
             }),
+            glob: None,
+            scan: None,
+            exclude: Vec::new(),
+            require_pkgbuild: None,
+            require_srcinfo: None,
             read_build_metadata: resolve_memcpy_option!(read_build_metadata),
             install_missing_dependencies: resolve_memcpy_option!(install_missing_dependencies),
             clean_before_build: resolve_memcpy_option!(clean_before_build),
@@ -126,4 +253,76 @@ where
             allow_failure: resolve_memcpy_option!(allow_failure),
         }
     }
+
+    /// Check that exactly one of `directory`, `glob`, and `scan` is set.
+    fn check_location(&self) -> Result<(), &'static str> {
+        match (is_empty_directory(&self.directory), &self.glob, &self.scan) {
+            (false, None, None) | (true, Some(_), None) | (true, None, Some(_)) => Ok(()),
+            (true, None, None) => Err(MISSING_LOCATION),
+            _ => Err(CONFLICTING_LOCATION),
+        }
+    }
+
+    /// Replace a `glob` or `scan` member with a member for each directory it finds under `container`.
+    ///
+    /// Other fields are copied into the new members. A `directory` member is returned as is.
+    pub fn expand(&self, container: &Path) -> Result<Vec<OwnedMember>, String> {
+        self.check_location()?;
+        let pattern = match (is_empty_directory(&self.directory), &self.glob, &self.scan) {
+            (false, None, None) => {
+                let has_filters = !self.exclude.is_empty()
+                    || self.require_pkgbuild.is_some()
+                    || self.require_srcinfo.is_some();
+                if has_filters {
+                    return Err(format!(
+                        "member {:?}: exclude, require-pkgbuild, and require-srcinfo only apply to glob and scan",
+                        self.directory.as_ref(),
+                    ));
+                }
+                return Ok(vec![self.to_owned()]);
+            }
+            (true, Some(glob), None) => glob.as_ref().to_path_buf(),
+            (true, None, Some(scan)) => scan.as_ref().join("*"),
+            _ => unreachable!("location has been checked"),
+        };
+
+        let is_excluded = |directory: &Path| {
+            let path = directory.to_string_lossy();
+            let name = directory
+                .file_name()
+                .map(|name| name.to_string_lossy())
+                .unwrap_or_default();
+            self.exclude.iter().any(|pattern| {
+                let pattern = pattern.as_ref().to_string_lossy();
+                if pattern.contains('/') {
+                    wildcard_match(pattern.trim_end_matches('/'), &path)
+                } else {
+                    wildcard_match(&pattern, &name)
+                }
+            })
+        };
+        let file_exists =
+            |directory: &Path, name: &str| container.join(directory).join(name).is_file();
+
+        let members = glob_directories(container, &pattern)?
+            .into_iter()
+            .filter(|directory| !is_excluded(directory))
+            .filter(|directory| {
+                !self.require_pkgbuild.unwrap_or(false) || file_exists(directory, "PKGBUILD")
+            })
+            .filter(|directory| {
+                !self.require_srcinfo.unwrap_or(false) || file_exists(directory, ".SRCINFO")
+            })
+            .map(|directory: PathBuf| OwnedMember {
+                directory: Wrapper::from_inner(directory),
+                glob: None,
+                scan: None,
+                exclude: Vec::new(),
+                require_pkgbuild: None,
+                require_srcinfo: None,
+                ..self.to_owned()
+            })
+            .collect();
+        Ok(members)
+    }
 }
@@ -0,0 +1,74 @@
+use super::super::utils::wildcard_match;
+use super::setting_override::replace_global_setting;
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::path::Path;
+
+/// Settings that replace parts of the manifest when selected with `--profile`.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Profile {
+    /// Fields that take the place of the same fields in `global-settings`.
+    #[serde(default, skip_serializing_if = "Mapping::is_empty")]
+    pub global_settings: Mapping,
+    #[serde(default)]
+    pub members: MemberFilter,
+}
+
+/// Patterns of the names of the build directories to keep.
+#[derive(Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct MemberFilter {
+    /// Keep only the members that match one of these patterns, or every member if empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub only: Vec<String>,
+    /// Leave out the members that match one of these patterns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+}
+
+impl Profile {
+    /// Take the profile named `name` out of the `profiles` of the manifest document `manifest`.
+    pub fn take(manifest: &mut Value, name: &str) -> Result<Self, String> {
+        let profile = manifest
+            .as_mapping_mut()
+            .and_then(|manifest| manifest.remove("profiles"))
+            .and_then(|mut profiles| profiles.as_mapping_mut()?.remove(name))
+            .ok_or_else(|| format!("profile {:?} is not defined", name))?;
+        serde_yaml::from_value(profile)
+            .map_err(|error| format!("cannot deserialize profile {:?}: {}", name, error))
+    }
+
+    /// Overwrite fields of `global-settings` of the manifest document `manifest`.
+    ///
+    /// Keys and values are checked the same way as [`SettingOverride`](super::SettingOverride)s are.
+    pub fn apply(&self, manifest: &mut Value) -> Result<(), String> {
+        let global_settings = manifest
+            .get_mut("global-settings")
+            .and_then(Value::as_mapping_mut)
+            .ok_or("global-settings is not a mapping")?;
+        for (key, value) in &self.global_settings {
+            let key = key
+                .as_str()
+                .ok_or_else(|| format!("profile: global setting {:?} is not a string", key))?;
+            let origin = format!("profile: global-settings.{}", key);
+            replace_global_setting(global_settings, key, value.clone(), origin)?;
+        }
+        Ok(())
+    }
+}
+
+impl MemberFilter {
+    pub fn matches(&self, directory: &Path) -> bool {
+        let name = directory
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        let matches_any = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|pattern| wildcard_match(pattern, &name))
+        };
+        (self.only.is_empty() || matches_any(&self.only)) && !matches_any(&self.exclude)
+    }
+}
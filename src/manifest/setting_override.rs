@@ -0,0 +1,137 @@
+use super::{OwnedGlobalSettings, GLOBAL_SETTINGS_KEYS};
+use serde_yaml::{Mapping, Value};
+use std::{
+    env::vars,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// Prefix of the environment variables that override global settings.
+pub const SETTING_OVERRIDE_PREFIX: &str = "PACMAN_REPO_BUILDER_";
+
+/// Replacement of a field of `global-settings` from outside the manifest file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SettingOverride {
+    /// Key of the field, such as `force-rebuild`.
+    pub key: String,
+    /// New value of the field as YAML, such as `true` or `[x86_64]`.
+    pub value: String,
+    /// Where the override comes from.
+    pub origin: String,
+}
+
+impl FromStr for SettingOverride {
+    type Err = String;
+
+    /// Parse `key=value` from `--set`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (key, value) = text
+            .split_once('=')
+            .ok_or_else(|| format!("expected key=value but got {:?}", text))?;
+        Ok(SettingOverride {
+            key: key.trim().to_string(),
+            value: value.to_string(),
+            origin: format!("--set {}", text),
+        })
+    }
+}
+
+impl Display for SettingOverride {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}", self.origin)
+    }
+}
+
+impl SettingOverride {
+    /// Collect the overrides from `PACMAN_REPO_BUILDER_<KEY>` environment variables, sorted by key.
+    ///
+    /// `<KEY>` is the key in upper case with `-` replaced by `_`, such as `FORCE_REBUILD`.
+    pub fn from_env_vars() -> Vec<Self> {
+        let mut overrides: Vec<_> = vars()
+            .filter_map(|(name, value)| {
+                let key = name
+                    .strip_prefix(SETTING_OVERRIDE_PREFIX)?
+                    .to_lowercase()
+                    .replace('_', "-");
+                Some(SettingOverride {
+                    key,
+                    origin: name,
+                    value,
+                })
+            })
+            .collect();
+        overrides.sort_by(|a, b| a.key.cmp(&b.key));
+        overrides
+    }
+
+    /// Replace the field of `global_settings`, after checking the key and the type of the value.
+    pub fn apply(&self, global_settings: &mut Mapping) -> Result<(), String> {
+        let value: Value = serde_yaml::from_str(&self.value)
+            .map_err(|error| format!("{}: invalid YAML: {}", self, error))?;
+        replace_global_setting(global_settings, &self.key, value, self)
+    }
+}
+
+/// Replace the field `key` of `global_settings` with `value`, after checking the key and the type of the value.
+///
+/// Errors are prefixed with `origin`, which tells where the replacement comes from.
+pub fn replace_global_setting(
+    global_settings: &mut Mapping,
+    key: &str,
+    value: Value,
+    origin: impl Display,
+) -> Result<(), String> {
+    if !GLOBAL_SETTINGS_KEYS.contains(&key) {
+        return Err(format!(
+            "{}: unknown global setting {:?}, expected one of {}",
+            origin,
+            key,
+            GLOBAL_SETTINGS_KEYS.join(", "),
+        ));
+    }
+
+    // Check the value alone, so that the error points at the replacement instead of the manifest.
+    let mut sample = Mapping::new();
+    sample.insert("repository".into(), "".into());
+    sample.insert(key.into(), value.clone());
+    serde_yaml::from_value::<OwnedGlobalSettings>(Value::Mapping(sample))
+        .map_err(|error| format!("{}: {}", origin, error))?;
+
+    global_settings.insert(key.into(), value);
+    Ok(())
+}
+
+#[test]
+fn test_from_str() {
+    let actual: SettingOverride = "arch-filter=[x86_64, i686]".parse().unwrap();
+    let expected = SettingOverride {
+        key: "arch-filter".to_string(),
+        value: "[x86_64, i686]".to_string(),
+        origin: "--set arch-filter=[x86_64, i686]".to_string(),
+    };
+    assert_eq!(actual, expected);
+    assert!("force-rebuild".parse::<SettingOverride>().is_err());
+}
+
+#[test]
+fn test_apply() {
+    let set = |text: &str| {
+        let mut global_settings = Mapping::new();
+        text.parse::<SettingOverride>()
+            .unwrap()
+            .apply(&mut global_settings)
+            .map(|()| global_settings)
+    };
+    let global_settings = set("force-rebuild=true").unwrap();
+    assert_eq!(
+        global_settings.get("force-rebuild"),
+        Some(&Value::Bool(true))
+    );
+    assert_eq!(
+        set("force-rebuild=maybe").unwrap_err(),
+        "--set force-rebuild=maybe: invalid type: string \"maybe\", expected a boolean",
+    );
+    assert!(set("forse-rebuild=true")
+        .unwrap_err()
+        .starts_with("--set forse-rebuild=true: unknown global setting \"forse-rebuild\""));
+}
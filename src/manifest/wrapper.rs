@@ -135,6 +135,15 @@ wrapper_type!(
     Path
 );
 
+wrapper_type!(
+    LogDirectory,
+    LogDirectoryWrapper,
+    OwnedLogDirectory,
+    BorrowedLogDirectory,
+    PathBuf,
+    Path
+);
+
 wrapper_type!(
     Directory,
     DirectoryWrapper,
@@ -144,6 +153,15 @@ wrapper_type!(
     Path
 );
 
+wrapper_type!(
+    Include,
+    IncludeWrapper,
+    OwnedInclude,
+    BorrowedInclude,
+    PathBuf,
+    Path
+);
+
 wrapper_type!(
     Pacman,
     PacmanWrapper,
@@ -162,6 +180,24 @@ wrapper_type!(
     str
 );
 
+wrapper_type!(
+    SigningKey,
+    SigningKeyWrapper,
+    OwnedSigningKey,
+    BorrowedSigningKey,
+    String,
+    str
+);
+
+wrapper_type!(
+    PackageExtension,
+    PackageExtensionWrapper,
+    OwnedPackageExtension,
+    BorrowedPackageExtension,
+    String,
+    str
+);
+
 type OwnedArchVec = Vec<String>;
 type BorrowedArchArray = [String];
 wrapper_type!(
@@ -181,6 +217,25 @@ impl<Item: Into<String>> FromIterator<Item> for OwnedArchCollection {
     }
 }
 
+type OwnedPackageVec = Vec<String>;
+type BorrowedPackageArray = [String];
+wrapper_type!(
+    PackageCollection,
+    PackageCollectionWrapper,
+    OwnedPackageCollection,
+    BorrowedPackageCollection,
+    OwnedPackageVec,
+    BorrowedPackageArray
+);
+impl<Item: Into<String>> FromIterator<Item> for OwnedPackageCollection {
+    fn from_iter<Iter: IntoIterator<Item = Item>>(iter: Iter) -> Self {
+        iter.into_iter()
+            .map(Into::into)
+            .collect::<Vec<_>>()
+            .pipe(OwnedPackageCollection::from_inner)
+    }
+}
+
 type OwnedAurVec = Vec<String>;
 type BorrowedAurArray = [String];
 wrapper_type!(
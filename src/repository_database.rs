@@ -0,0 +1,96 @@
+pub mod archive;
+pub mod error;
+pub mod journal;
+pub mod load;
+pub mod package_entry;
+pub mod package_info;
+pub mod package_record;
+pub mod paths;
+pub mod verify;
+pub mod write;
+
+pub use error::Error;
+
+use indexmap::IndexMap;
+use package_entry::PackageEntry;
+use package_record::{MissingEntry, PackageRecord};
+
+/// In-memory copy of a repository database and its files database.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct RepositoryDatabase {
+    /// Directories of the archives, such as `foo-1.0.0-1`.
+    records: IndexMap<String, DatabaseRecord>,
+}
+
+/// Content of a directory in the database archives.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DatabaseRecord {
+    /// Content of `desc`.
+    pub desc: String,
+    /// Content of `files`, which only the files database has.
+    pub files: Option<String>,
+}
+
+impl DatabaseRecord {
+    /// Value of `%NAME%` in `desc`.
+    pub fn name(&self) -> Option<&str> {
+        self.field("%NAME%")
+    }
+
+    /// Value of `%FILENAME%` in `desc`.
+    pub fn file_name(&self) -> Option<&str> {
+        self.field("%FILENAME%")
+    }
+
+    pub fn parse(&self) -> Result<PackageRecord, MissingEntry> {
+        PackageRecord::parse(&self.desc)
+    }
+
+    fn field(&self, header: &str) -> Option<&str> {
+        let mut lines = self.desc.lines();
+        lines.find(|line| *line == header)?;
+        lines.next()
+    }
+}
+
+impl RepositoryDatabase {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn records(&self) -> &IndexMap<String, DatabaseRecord> {
+        &self.records
+    }
+
+    /// Parse every record, keyed by the name of its directory.
+    pub fn packages(
+        &self,
+    ) -> impl Iterator<Item = (&str, Result<PackageRecord, MissingEntry>)> + '_ {
+        self.records
+            .iter()
+            .map(|(directory, record)| (directory.as_str(), record.parse()))
+    }
+
+    /// Add a package, replacing every other version of it like `repo-add` does.
+    pub fn add(&mut self, entry: &PackageEntry) {
+        self.remove(&entry.info.pkgname);
+        self.records.insert(
+            entry.directory_name(),
+            DatabaseRecord {
+                desc: entry.desc(),
+                files: Some(entry.files()),
+            },
+        );
+    }
+
+    /// Remove every version of the package named `pkgname`, return whether there was any.
+    pub fn remove(&mut self, pkgname: &str) -> bool {
+        let count = self.records.len();
+        self.records
+            .retain(|_, record| record.name() != Some(pkgname));
+        self.records.len() != count
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,87 @@
+use super::Error;
+use flate2::{read::GzDecoder, write::GzEncoder};
+use std::{
+    fs::File,
+    io::{self, BufReader, Read, Write},
+    path::Path,
+};
+use tar::Archive;
+use xz2::{read::XzDecoder, write::XzEncoder};
+
+/// Compression of a tar archive, judged by the extension of its file name.
+///
+/// Only gzip (the default of `repo-add`), xz, zstd (the default of `makepkg`), and no compression are supported.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Xz,
+    Zstd,
+}
+
+impl Compression {
+    /// Compression of an archive whose file name ends with `extension`, such as `.pkg.tar.zst` or `.db.tar.gz`.
+    ///
+    /// Return `None` if `extension` does not end with `.tar` and at most one supported compression suffix.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        if extension.ends_with(".tar") {
+            return Some(Compression::None);
+        }
+        let (rest, suffix) = extension.rsplit_once('.')?;
+        let compression = match suffix {
+            "gz" => Compression::Gzip,
+            "xz" => Compression::Xz,
+            "zst" => Compression::Zstd,
+            _ => return None,
+        };
+        rest.ends_with(".tar").then_some(compression)
+    }
+
+    /// Compression of the archive at `path`, or an error if it is not supported.
+    pub fn from_path(path: &Path) -> Result<Self, Error> {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .and_then(Compression::from_extension)
+            .ok_or_else(|| Error::UnsupportedCompression(path.to_path_buf()))
+    }
+}
+
+/// Open the tar archive at `path` for reading, decompressing it on the fly.
+pub fn open(path: &Path) -> Result<Archive<Box<dyn Read>>, Error> {
+    let file = File::open(path).map(BufReader::new)?;
+    let reader: Box<dyn Read> = match Compression::from_path(path)? {
+        Compression::None => Box::new(file),
+        Compression::Gzip => Box::new(GzDecoder::new(file)),
+        Compression::Xz => Box::new(XzDecoder::new(file)),
+        Compression::Zstd => Box::new(zstd::Decoder::with_buffer(file)?),
+    };
+    Ok(Archive::new(reader))
+}
+
+/// Write `tar`, which is an uncompressed tar archive, to `path` with `compression`.
+pub fn create(path: &Path, compression: Compression, tar: &[u8]) -> io::Result<()> {
+    let file = File::create(path)?;
+    match compression {
+        Compression::None => write_all(file, tar),
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(file, flate2::Compression::default());
+            encoder.write_all(tar)?;
+            encoder.finish().and_then(|file| file.sync_all())
+        }
+        Compression::Xz => {
+            let mut encoder = XzEncoder::new(file, 6);
+            encoder.write_all(tar)?;
+            encoder.finish().and_then(|file| file.sync_all())
+        }
+        Compression::Zstd => {
+            let mut encoder = zstd::Encoder::new(file, 0)?;
+            encoder.write_all(tar)?;
+            encoder.finish().and_then(|file| file.sync_all())
+        }
+    }
+}
+
+fn write_all(mut file: File, content: &[u8]) -> io::Result<()> {
+    file.write_all(content)?;
+    file.sync_all()
+}
@@ -0,0 +1,63 @@
+use super::super::status::{Code, Failure};
+use super::package_info::MissingField;
+use std::{
+    fmt::{self, Display, Formatter},
+    io,
+    num::NonZeroI32,
+    path::PathBuf,
+    process::ExitStatus,
+};
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Command {
+        program: &'static str,
+        status: ExitStatus,
+    },
+    InvalidPackage {
+        file: PathBuf,
+        reason: MissingField,
+    },
+    UnsupportedCompression(PathBuf),
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(formatter, "{}", error),
+            Error::Command { program, status } => {
+                write!(formatter, "{} exits with {}", program, status)
+            }
+            Error::InvalidPackage { file, reason } => {
+                write!(formatter, "Invalid package {:?}: {}", file, reason)
+            }
+            Error::UnsupportedCompression(file) => write!(
+                formatter,
+                "Unsupported archive {:?}: expected .tar followed by .gz, .xz, .zst, or nothing",
+                file,
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<Error> for Failure {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Io(error) => Failure::from(error),
+            Error::Command { status, .. } => status
+                .code()
+                .and_then(NonZeroI32::new)
+                .map_or_else(|| Failure::from(Code::GenericFailure), Failure::Code),
+            Error::InvalidPackage { .. } | Error::UnsupportedCompression(_) => {
+                Failure::from(Code::GenericFailure)
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::{
+    fs::{read_to_string, remove_file, OpenOptions},
+    io::{self, ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+/// List of package files that were copied to the repository but may not be in the database yet.
+///
+/// `build` only writes the database once all packages are done, so an interrupted build
+/// leaves the names of the package files that it copied here for the next build to add.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    /// Journal of the database at `repository`, which is a file next to it.
+    pub fn new(repository: &Path) -> Self {
+        let mut path = repository.as_os_str().to_owned();
+        path.push(".journal");
+        Journal { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Names of the package files in the journal, which is empty if the journal does not exist.
+    pub fn read(&self) -> io::Result<Vec<String>> {
+        match read_to_string(&self.path) {
+            Ok(content) => Ok(content.lines().map(ToString::to_string).collect()),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Add the name of a package file that has been copied to the repository.
+    pub fn append(&self, file_name: &str) -> io::Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        // A single write keeps lines from concurrent builds apart.
+        file.write_all(format!("{}\n", file_name).as_bytes())?;
+        file.sync_data()
+    }
+
+    /// Delete the journal once the database has every package file in it.
+    pub fn remove(&self) -> io::Result<()> {
+        match remove_file(&self.path) {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(error),
+            Ok(()) | Err(_) => Ok(()),
+        }
+    }
+}
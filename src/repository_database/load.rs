@@ -0,0 +1,64 @@
+use super::{archive, paths::DatabasePaths, DatabaseRecord, Error, RepositoryDatabase};
+use indexmap::IndexMap;
+use std::{
+    io::{self, ErrorKind, Read},
+    path::Path,
+};
+
+impl RepositoryDatabase {
+    /// Read the database at `repository` along with its files database.
+    ///
+    /// An empty database is returned if neither of them exists.
+    pub fn load(repository: &Path) -> Result<Self, Error> {
+        let archive = match DatabasePaths::new(repository) {
+            Some(paths) if paths.files_archive.exists() => paths.files_archive,
+            Some(_) | None => repository.to_path_buf(),
+        };
+        if !archive.exists() {
+            return Ok(RepositoryDatabase::new());
+        }
+        RepositoryDatabase::load_archive(&archive)
+    }
+
+    /// Read a single database archive, be it a database or a files database.
+    ///
+    /// The archive is read in memory, so nothing is written next to it.
+    pub fn load_archive(archive: &Path) -> Result<Self, Error> {
+        let mut descs = IndexMap::new();
+        let mut files = IndexMap::new();
+        for entry in archive::open(archive)?.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.to_string_lossy().into_owned();
+            let (name, file) = match path.split_once('/') {
+                Some((name, file)) => (name.to_string(), file),
+                None => continue,
+            };
+            let target = match file {
+                "desc" => &mut descs,
+                "files" => &mut files,
+                _ => continue,
+            };
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            target.insert(name, content);
+        }
+
+        let mut database = RepositoryDatabase::new();
+        descs.sort_keys();
+        for (name, desc) in descs {
+            let files = files.swap_remove(&name);
+            database
+                .records
+                .insert(name, DatabaseRecord { desc, files });
+        }
+        if let Some(name) = files.keys().next() {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("{:?} has {}/files but not {}/desc", archive, name, name),
+            )
+            .into());
+        }
+
+        Ok(database)
+    }
+}
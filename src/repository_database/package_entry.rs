@@ -0,0 +1,185 @@
+use super::{archive, package_info::PackageInfo, Error};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hex_fmt::HexFmt;
+use pipe_trait::*;
+use sha2::{Digest, Sha256};
+use std::{
+    fmt::Write,
+    fs::{metadata, read, File},
+    io::{self, copy, ErrorKind, Read},
+    path::Path,
+};
+
+/// Signatures larger than this are not embedded into the database, same as `repo-add`.
+const MAX_SIGNATURE_SIZE: u64 = 16384;
+
+/// Everything that the database needs to know about a package file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PackageEntry {
+    /// Base name of the package file.
+    pub file_name: String,
+    pub info: PackageInfo,
+    /// Size of the package file.
+    pub compressed_size: u64,
+    /// Lowercase hexadecimal SHA-256 of the package file.
+    pub sha256sum: String,
+    /// Base64 of the detached signature of the package file, if any.
+    ///
+    /// It is written as `%PGPSIG%`, like `repo-add --include-sigs` does.
+    pub pgpsig: Option<String>,
+    /// Paths inside the package, except the metadata files whose names start with a dot.
+    pub files: Vec<String>,
+}
+
+impl PackageEntry {
+    /// Read a package file and its detached signature (if any).
+    pub fn from_package_file(package_file: &Path) -> Result<Self, Error> {
+        let file_name = package_file
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| {
+                io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("{:?} does not have a valid file name", package_file),
+                )
+            })?
+            .to_string();
+
+        let mut pkginfo = None;
+        let mut files = Vec::new();
+        for entry in archive::open(package_file)?.entries()? {
+            let mut entry = entry?;
+            let path = entry
+                .path_bytes()
+                .into_owned()
+                .pipe(String::from_utf8)
+                .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?;
+            if path == ".PKGINFO" {
+                let mut content = String::new();
+                entry.read_to_string(&mut content)?;
+                pkginfo = Some(content);
+            } else if !path.starts_with('.') {
+                files.push(path);
+            }
+        }
+        files.sort_unstable();
+        files.dedup();
+
+        // A package without .PKGINFO is reported as one whose .PKGINFO lacks pkgname.
+        let info = pkginfo
+            .unwrap_or_default()
+            .pipe_as_ref(PackageInfo::parse)
+            .map_err(|reason| Error::InvalidPackage {
+                file: package_file.to_path_buf(),
+                reason,
+            })?;
+
+        let compressed_size = metadata(package_file)?.len();
+        let sha256sum = sha256sum(package_file)?;
+
+        let mut signature_file = package_file.as_os_str().to_owned();
+        signature_file.push(".sig");
+        let pgpsig = match metadata(&signature_file) {
+            Ok(metadata) if metadata.len() <= MAX_SIGNATURE_SIZE => signature_file
+                .pipe(read)?
+                .pipe(|signature| BASE64.encode(signature))
+                .pipe(Some),
+            Ok(_) | Err(_) => None,
+        };
+
+        Ok(PackageEntry {
+            file_name,
+            info,
+            compressed_size,
+            sha256sum,
+            pgpsig,
+            files,
+        })
+    }
+
+    /// Name of the directory of this package in the database archives.
+    pub fn directory_name(&self) -> String {
+        format!("{}-{}", self.info.pkgname, self.info.pkgver)
+    }
+
+    /// Content of `desc`, formatted the way `repo-add` does.
+    pub fn desc(&self) -> String {
+        let PackageInfo {
+            pkgname,
+            pkgbase,
+            pkgver,
+            pkgdesc,
+            size,
+            url,
+            builddate,
+            packager,
+            arch,
+            groups,
+            licenses,
+            replaces,
+            conflicts,
+            provides,
+            depends,
+            optdepends,
+            makedepends,
+            checkdepends,
+        } = &self.info;
+
+        let mut desc = String::new();
+        let mut entry = |name: &str, values: &[&str]| {
+            if values.iter().all(|value| value.is_empty()) {
+                return;
+            }
+            writeln!(desc, "%{}%", name).expect("write to a string");
+            for value in values {
+                writeln!(desc, "{}", value).expect("write to a string");
+            }
+            desc.push('\n');
+        };
+        fn list(values: &[String]) -> Vec<&str> {
+            values.iter().map(String::as_str).collect()
+        }
+
+        entry("FILENAME", &[&self.file_name]);
+        entry("NAME", &[pkgname]);
+        entry("BASE", &[pkgbase]);
+        entry("VERSION", &[pkgver]);
+        entry("DESC", &[pkgdesc]);
+        entry("GROUPS", &list(groups));
+        entry("CSIZE", &[&self.compressed_size.to_string()]);
+        entry("ISIZE", &[size]);
+        entry("SHA256SUM", &[&self.sha256sum]);
+        entry("PGPSIG", &[self.pgpsig.as_deref().unwrap_or_default()]);
+        entry("URL", &[url]);
+        entry("LICENSE", &list(licenses));
+        entry("ARCH", &[arch]);
+        entry("BUILDDATE", &[builddate]);
+        entry("PACKAGER", &[packager]);
+        entry("REPLACES", &list(replaces));
+        entry("CONFLICTS", &list(conflicts));
+        entry("PROVIDES", &list(provides));
+        entry("DEPENDS", &list(depends));
+        entry("OPTDEPENDS", &list(optdepends));
+        entry("MAKEDEPENDS", &list(makedepends));
+        entry("CHECKDEPENDS", &list(checkdepends));
+
+        desc
+    }
+
+    /// Content of `files`, formatted the way `repo-add` does.
+    pub fn files(&self) -> String {
+        let mut files = String::from("%FILES%\n");
+        for path in &self.files {
+            files.push_str(path);
+            files.push('\n');
+        }
+        files
+    }
+}
+
+/// Lowercase hexadecimal SHA-256 of the content of `file`.
+pub fn sha256sum(file: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    copy(&mut File::open(file)?, &mut hasher)?;
+    Ok(HexFmt(hasher.finalize()).to_string())
+}
@@ -0,0 +1,85 @@
+use std::fmt::{self, Display, Formatter};
+
+/// Metadata of a package file, as written in its `.PKGINFO`.
+///
+/// Only the fields that `repo-add` copies into the database are kept.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct PackageInfo {
+    pub pkgname: String,
+    pub pkgbase: String,
+    pub pkgver: String,
+    pub pkgdesc: String,
+    pub size: String,
+    pub url: String,
+    pub builddate: String,
+    pub packager: String,
+    pub arch: String,
+    pub groups: Vec<String>,
+    pub licenses: Vec<String>,
+    pub replaces: Vec<String>,
+    pub conflicts: Vec<String>,
+    pub provides: Vec<String>,
+    pub depends: Vec<String>,
+    pub optdepends: Vec<String>,
+    pub makedepends: Vec<String>,
+    pub checkdepends: Vec<String>,
+}
+
+/// Required field that `.PKGINFO` lacks.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct MissingField(pub &'static str);
+
+impl Display for MissingField {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, ".PKGINFO does not have {}", self.0)
+    }
+}
+
+impl PackageInfo {
+    pub fn parse(text: &str) -> Result<Self, MissingField> {
+        let mut info = PackageInfo::default();
+
+        for line in text.lines() {
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim().to_string();
+            match key.trim() {
+                "pkgname" => info.pkgname = value,
+                "pkgbase" => info.pkgbase = value,
+                "pkgver" => info.pkgver = value,
+                "pkgdesc" => info.pkgdesc = value,
+                "size" => info.size = value,
+                "url" => info.url = value,
+                "builddate" => info.builddate = value,
+                "packager" => info.packager = value,
+                "arch" => info.arch = value,
+                "group" => info.groups.push(value),
+                "license" => info.licenses.push(value),
+                "replaces" => info.replaces.push(value),
+                "conflict" => info.conflicts.push(value),
+                "provides" => info.provides.push(value),
+                "depend" => info.depends.push(value),
+                "optdepend" => info.optdepends.push(value),
+                "makedepend" => info.makedepends.push(value),
+                "checkdepend" => info.checkdepends.push(value),
+                _ => {}
+            }
+        }
+
+        if info.pkgname.is_empty() {
+            return Err(MissingField("pkgname"));
+        }
+        if info.pkgver.is_empty() {
+            return Err(MissingField("pkgver"));
+        }
+        if info.pkgbase.is_empty() {
+            info.pkgbase = info.pkgname.clone();
+        }
+
+        Ok(info)
+    }
+}
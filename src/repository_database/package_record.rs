@@ -0,0 +1,105 @@
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::fmt::{self, Display, Formatter};
+
+/// Package as described by a `desc` entry of the repository database.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackageRecord {
+    pub name: String,
+    pub base: Option<String>,
+    pub version: String,
+    pub description: Option<String>,
+    pub file_name: String,
+    pub arch: Option<String>,
+    pub compressed_size: Option<u64>,
+    pub installed_size: Option<u64>,
+    pub md5sum: Option<String>,
+    pub sha256sum: Option<String>,
+    /// Base64 of the detached signature of the package file.
+    #[serde(skip)]
+    pub pgpsig: Option<String>,
+    pub url: Option<String>,
+    pub licenses: Vec<String>,
+    pub build_date: Option<u64>,
+    pub packager: Option<String>,
+    pub groups: Vec<String>,
+    pub replaces: Vec<String>,
+    pub conflicts: Vec<String>,
+    pub provides: Vec<String>,
+    pub depends: Vec<String>,
+    pub optdepends: Vec<String>,
+    pub makedepends: Vec<String>,
+    pub checkdepends: Vec<String>,
+}
+
+/// Required entry that `desc` lacks.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct MissingEntry(pub &'static str);
+
+impl Display for MissingEntry {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "desc does not have %{}%", self.0)
+    }
+}
+
+impl PackageRecord {
+    pub fn parse(desc: &str) -> Result<Self, MissingEntry> {
+        let mut entries: IndexMap<&str, Vec<&str>> = IndexMap::new();
+        let mut current = None;
+        for line in desc.lines() {
+            if line.is_empty() {
+                current = None;
+            } else if let Some(values) = current.and_then(|name| entries.get_mut(name)) {
+                values.push(line);
+            } else if let Some(name) = line
+                .strip_prefix('%')
+                .and_then(|line| line.strip_suffix('%'))
+            {
+                entries.insert(name, Vec::new());
+                current = Some(name);
+            }
+        }
+
+        let list = |name: &str| -> Vec<String> {
+            entries
+                .get(name)
+                .map(|values| values.iter().map(ToString::to_string).collect())
+                .unwrap_or_default()
+        };
+        let single = |name: &str| -> Option<String> {
+            entries
+                .get(name)
+                .and_then(|values| values.first())
+                .map(ToString::to_string)
+        };
+        let required = |name: &'static str| single(name).ok_or(MissingEntry(name));
+        let number = |name: &str| single(name).and_then(|value| value.parse().ok());
+
+        Ok(PackageRecord {
+            name: required("NAME")?,
+            base: single("BASE"),
+            version: required("VERSION")?,
+            description: single("DESC"),
+            file_name: required("FILENAME")?,
+            arch: single("ARCH"),
+            compressed_size: number("CSIZE"),
+            installed_size: number("ISIZE"),
+            md5sum: single("MD5SUM"),
+            sha256sum: single("SHA256SUM"),
+            pgpsig: single("PGPSIG"),
+            url: single("URL"),
+            licenses: list("LICENSE"),
+            build_date: number("BUILDDATE"),
+            packager: single("PACKAGER"),
+            groups: list("GROUPS"),
+            replaces: list("REPLACES"),
+            conflicts: list("CONFLICTS"),
+            provides: list("PROVIDES"),
+            depends: list("DEPENDS"),
+            optdepends: list("OPTDEPENDS"),
+            makedepends: list("MAKEDEPENDS"),
+            checkdepends: list("CHECKDEPENDS"),
+        })
+    }
+}
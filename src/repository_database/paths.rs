@@ -0,0 +1,44 @@
+use std::path::{Path, PathBuf};
+
+/// Files that make up the repository database at a path such as `repo/repo.db.tar.gz`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DatabasePaths {
+    pub directory: PathBuf,
+    /// The database itself, such as `repo.db.tar.gz`.
+    pub db_archive: PathBuf,
+    /// Symlink to the database, such as `repo.db`.
+    pub db_link: Option<PathBuf>,
+    /// The files database, such as `repo.files.tar.gz`.
+    pub files_archive: PathBuf,
+    /// Symlink to the files database, such as `repo.files`.
+    pub files_link: Option<PathBuf>,
+}
+
+impl DatabasePaths {
+    /// Split the path of a database the way `repo-add` does.
+    ///
+    /// Return `None` if the file name does not contain `.db`.
+    pub fn new(repository: &Path) -> Option<Self> {
+        let file_name = repository.file_name()?.to_str()?;
+        let index = file_name.rfind(".db")?;
+        let prefix = &file_name[..index];
+        let suffix = &file_name[index + ".db".len()..];
+        let directory = repository
+            .parent()
+            .map_or_else(PathBuf::new, Path::to_path_buf);
+        let link = |kind: &str| {
+            if suffix.is_empty() {
+                None
+            } else {
+                Some(directory.join(format!("{}.{}", prefix, kind)))
+            }
+        };
+        Some(DatabasePaths {
+            db_archive: repository.to_path_buf(),
+            db_link: link("db"),
+            files_archive: directory.join(format!("{}.files{}", prefix, suffix)),
+            files_link: link("files"),
+            directory,
+        })
+    }
+}
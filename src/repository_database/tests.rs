@@ -0,0 +1,247 @@
+use super::{
+    archive::Compression,
+    package_entry::PackageEntry,
+    package_info::PackageInfo,
+    package_record::{MissingEntry, PackageRecord},
+    paths::DatabasePaths,
+    RepositoryDatabase,
+};
+use pretty_assertions::assert_eq;
+use std::path::{Path, PathBuf};
+
+const PKGINFO: &str = r#"
+# Generated by makepkg 6.1.0
+# using fakeroot version 1.36
+pkgname = foo-bin
+pkgbase = foo
+xdata = pkgtype=split
+pkgver = 1:1.2.3-4
+pkgdesc = Foo = the best tool
+url = https://example.com/foo
+builddate = 1700000000
+packager = Bob <bob@example.com>
+size = 12345
+arch = x86_64
+license = MIT
+license = Apache-2.0
+provides = foo=1.2.3
+conflict = foo-git
+depend = glibc
+depend = bar>=2
+optdepend = baz: for extra features
+makedepend = cargo
+"#;
+
+fn entry(pkgname: &str, pkgver: &str) -> PackageEntry {
+    PackageEntry {
+        file_name: format!("{}-{}-x86_64.pkg.tar.zst", pkgname, pkgver),
+        info: PackageInfo {
+            pkgname: pkgname.to_string(),
+            pkgbase: pkgname.to_string(),
+            pkgver: pkgver.to_string(),
+            arch: "x86_64".to_string(),
+            ..Default::default()
+        },
+        compressed_size: 100,
+        sha256sum: "00".repeat(32),
+        pgpsig: None,
+        files: vec!["usr/".to_string()],
+    }
+}
+
+#[test]
+fn parse_package_info() {
+    let info = PackageInfo::parse(PKGINFO).expect("parse .PKGINFO");
+    assert_eq!(
+        info,
+        PackageInfo {
+            pkgname: "foo-bin".to_string(),
+            pkgbase: "foo".to_string(),
+            pkgver: "1:1.2.3-4".to_string(),
+            pkgdesc: "Foo = the best tool".to_string(),
+            size: "12345".to_string(),
+            url: "https://example.com/foo".to_string(),
+            builddate: "1700000000".to_string(),
+            packager: "Bob <bob@example.com>".to_string(),
+            arch: "x86_64".to_string(),
+            groups: vec![],
+            licenses: vec!["MIT".to_string(), "Apache-2.0".to_string()],
+            replaces: vec![],
+            conflicts: vec!["foo-git".to_string()],
+            provides: vec!["foo=1.2.3".to_string()],
+            depends: vec!["glibc".to_string(), "bar>=2".to_string()],
+            optdepends: vec!["baz: for extra features".to_string()],
+            makedepends: vec!["cargo".to_string()],
+            checkdepends: vec![],
+        },
+    );
+}
+
+#[test]
+fn parse_package_info_without_pkgver() {
+    let error = PackageInfo::parse("pkgname = foo\n").expect_err("pkgver is missing");
+    assert_eq!(error.to_string(), ".PKGINFO does not have pkgver");
+}
+
+#[test]
+fn desc() {
+    let entry = PackageEntry {
+        file_name: "foo-bin-1:1.2.3-4-x86_64.pkg.tar.zst".to_string(),
+        info: PackageInfo::parse(PKGINFO).unwrap(),
+        compressed_size: 4096,
+        sha256sum: "ab".repeat(32),
+        pgpsig: Some("c2lnbmF0dXJl".to_string()),
+        files: vec![],
+    };
+    let expected = [
+        "%FILENAME%\nfoo-bin-1:1.2.3-4-x86_64.pkg.tar.zst\n\n",
+        "%NAME%\nfoo-bin\n\n",
+        "%BASE%\nfoo\n\n",
+        "%VERSION%\n1:1.2.3-4\n\n",
+        "%DESC%\nFoo = the best tool\n\n",
+        "%CSIZE%\n4096\n\n",
+        "%ISIZE%\n12345\n\n",
+        &format!("%SHA256SUM%\n{}\n\n", "ab".repeat(32)),
+        "%PGPSIG%\nc2lnbmF0dXJl\n\n",
+        "%URL%\nhttps://example.com/foo\n\n",
+        "%LICENSE%\nMIT\nApache-2.0\n\n",
+        "%ARCH%\nx86_64\n\n",
+        "%BUILDDATE%\n1700000000\n\n",
+        "%PACKAGER%\nBob <bob@example.com>\n\n",
+        "%CONFLICTS%\nfoo-git\n\n",
+        "%PROVIDES%\nfoo=1.2.3\n\n",
+        "%DEPENDS%\nglibc\nbar>=2\n\n",
+        "%OPTDEPENDS%\nbaz: for extra features\n\n",
+        "%MAKEDEPENDS%\ncargo\n\n",
+    ]
+    .concat();
+    assert_eq!(entry.desc(), expected);
+    assert_eq!(entry.directory_name(), "foo-bin-1:1.2.3-4");
+}
+
+#[test]
+fn parse_package_record() {
+    let entry = PackageEntry {
+        file_name: "foo-bin-1:1.2.3-4-x86_64.pkg.tar.zst".to_string(),
+        info: PackageInfo::parse(PKGINFO).unwrap(),
+        compressed_size: 4096,
+        sha256sum: "ab".repeat(32),
+        pgpsig: Some("c2lnbmF0dXJl".to_string()),
+        files: vec![],
+    };
+    let record = PackageRecord::parse(&entry.desc()).expect("parse desc");
+    assert_eq!(
+        record,
+        PackageRecord {
+            name: "foo-bin".to_string(),
+            base: Some("foo".to_string()),
+            version: "1:1.2.3-4".to_string(),
+            description: Some("Foo = the best tool".to_string()),
+            file_name: "foo-bin-1:1.2.3-4-x86_64.pkg.tar.zst".to_string(),
+            arch: Some("x86_64".to_string()),
+            compressed_size: Some(4096),
+            installed_size: Some(12345),
+            md5sum: None,
+            sha256sum: Some("ab".repeat(32)),
+            pgpsig: Some("c2lnbmF0dXJl".to_string()),
+            url: Some("https://example.com/foo".to_string()),
+            licenses: vec!["MIT".to_string(), "Apache-2.0".to_string()],
+            build_date: Some(1700000000),
+            packager: Some("Bob <bob@example.com>".to_string()),
+            conflicts: vec!["foo-git".to_string()],
+            provides: vec!["foo=1.2.3".to_string()],
+            depends: vec!["glibc".to_string(), "bar>=2".to_string()],
+            optdepends: vec!["baz: for extra features".to_string()],
+            makedepends: vec!["cargo".to_string()],
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn parse_package_record_without_version() {
+    let desc = "%FILENAME%\nfoo-1-1-any.pkg.tar.zst\n\n%NAME%\nfoo\n\n";
+    assert_eq!(PackageRecord::parse(desc), Err(MissingEntry("VERSION")));
+}
+
+#[test]
+fn files() {
+    let mut entry = entry("foo", "1-1");
+    entry.files = vec![
+        "usr/".to_string(),
+        "usr/bin/".to_string(),
+        "usr/bin/foo".to_string(),
+    ];
+    assert_eq!(entry.files(), "%FILES%\nusr/\nusr/bin/\nusr/bin/foo\n");
+}
+
+#[test]
+fn add_replaces_other_versions() {
+    let mut database = RepositoryDatabase::new();
+    database.add(&entry("foo", "1-1"));
+    database.add(&entry("bar", "1-1"));
+    database.add(&entry("foo", "2-1"));
+    let directories: Vec<_> = database.records().keys().collect();
+    assert_eq!(directories, ["bar-1-1", "foo-2-1"]);
+    let file_names: Vec<_> = database
+        .records()
+        .values()
+        .map(|record| record.file_name())
+        .collect();
+    assert_eq!(
+        file_names,
+        [
+            Some("bar-1-1-x86_64.pkg.tar.zst"),
+            Some("foo-2-1-x86_64.pkg.tar.zst"),
+        ],
+    );
+}
+
+#[test]
+fn remove() {
+    let mut database = RepositoryDatabase::new();
+    database.add(&entry("foo", "1-1"));
+    database.add(&entry("bar", "1-1"));
+    assert!(database.remove("foo"));
+    assert!(!database.remove("foo"));
+    let directories: Vec<_> = database.records().keys().collect();
+    assert_eq!(directories, ["bar-1-1"]);
+}
+
+#[test]
+fn database_paths() {
+    assert_eq!(
+        DatabasePaths::new(Path::new("repo/repo.db.tar.gz")),
+        Some(DatabasePaths {
+            directory: PathBuf::from("repo"),
+            db_archive: PathBuf::from("repo/repo.db.tar.gz"),
+            db_link: Some(PathBuf::from("repo/repo.db")),
+            files_archive: PathBuf::from("repo/repo.files.tar.gz"),
+            files_link: Some(PathBuf::from("repo/repo.files")),
+        }),
+    );
+    assert_eq!(
+        DatabasePaths::new(Path::new("my.dbs.db.tar.zst")),
+        Some(DatabasePaths {
+            directory: PathBuf::from(""),
+            db_archive: PathBuf::from("my.dbs.db.tar.zst"),
+            db_link: Some(PathBuf::from("my.dbs.db")),
+            files_archive: PathBuf::from("my.dbs.files.tar.zst"),
+            files_link: Some(PathBuf::from("my.dbs.files")),
+        }),
+    );
+    assert_eq!(DatabasePaths::new(Path::new("repo/repo.tar.gz")), None);
+}
+
+#[test]
+fn archive_compression() {
+    let compression = |extension| Compression::from_extension(extension);
+    assert_eq!(compression(".pkg.tar"), Some(Compression::None));
+    assert_eq!(compression(".db.tar.gz"), Some(Compression::Gzip));
+    assert_eq!(compression(".pkg.tar.xz"), Some(Compression::Xz));
+    assert_eq!(compression(".pkg.tar.zst"), Some(Compression::Zstd));
+    assert_eq!(compression("repo.files.tar.zst"), Some(Compression::Zstd));
+    assert_eq!(compression(".pkg.tar.bz2"), None);
+    assert_eq!(compression(".pkg.zst"), None);
+    assert_eq!(compression("repo.db"), None);
+}
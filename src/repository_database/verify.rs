@@ -0,0 +1,263 @@
+use super::super::{
+    srcinfo::version::vercmp,
+    utils::{read_dir_or_current, PackageFileName},
+};
+use super::{
+    package_entry::sha256sum, package_record::MissingEntry, paths::DatabasePaths, Error,
+    RepositoryDatabase,
+};
+use indexmap::IndexMap;
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+/// Disagreement between the repository database and the repository directory.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Inconsistency {
+    /// A `desc` entry cannot be parsed.
+    InvalidEntry {
+        directory: String,
+        error: MissingEntry,
+    },
+    /// The database refers to a package file that does not exist.
+    MissingPackageFile {
+        pkgname: String,
+        file: PathBuf,
+    },
+    /// A package file is newer than what the database has, or its package is not in the database.
+    UnregisteredPackageFile {
+        file: PathBuf,
+    },
+    SizeMismatch {
+        file: PathBuf,
+        expected: u64,
+        actual: u64,
+    },
+    Sha256Mismatch {
+        file: PathBuf,
+        expected: String,
+        actual: String,
+    },
+    MissingSignature {
+        file: PathBuf,
+    },
+    MissingFilesDatabase {
+        file: PathBuf,
+    },
+    /// An entry of the database is not in the files database.
+    NotInFilesDatabase {
+        directory: String,
+    },
+    /// An entry of the files database is not in the database.
+    NotInDatabase {
+        directory: String,
+    },
+    /// The `desc` of an entry differs between the database and the files database.
+    DescMismatch {
+        directory: String,
+    },
+}
+
+impl Display for Inconsistency {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        use Inconsistency::*;
+        match self {
+            InvalidEntry { directory, error } => write!(formatter, "{}: {}", directory, error),
+            MissingPackageFile { pkgname, file } => {
+                write!(formatter, "{:?} of {} does not exist", file, pkgname)
+            }
+            UnregisteredPackageFile { file } => {
+                write!(formatter, "{:?} is not in the database", file)
+            }
+            SizeMismatch {
+                file,
+                expected,
+                actual,
+            } => write!(
+                formatter,
+                "{:?} has {} bytes but the database says {}",
+                file, actual, expected,
+            ),
+            Sha256Mismatch {
+                file,
+                expected,
+                actual,
+            } => write!(
+                formatter,
+                "SHA-256 of {:?} is {} but the database says {}",
+                file, actual, expected,
+            ),
+            MissingSignature { file } => write!(formatter, "{:?} is not signed", file),
+            MissingFilesDatabase { file } => write!(formatter, "{:?} does not exist", file),
+            NotInFilesDatabase { directory } => {
+                write!(
+                    formatter,
+                    "{} is missing from the files database",
+                    directory
+                )
+            }
+            NotInDatabase { directory } => {
+                write!(formatter, "{} is only in the files database", directory)
+            }
+            DescMismatch { directory } => write!(
+                formatter,
+                "desc of {} differs between the database and the files database",
+                directory,
+            ),
+        }
+    }
+}
+
+/// Cross-check the database at `repository` with the files next to it.
+///
+/// `.sig` files are required for package files if `sign_packages` is set,
+/// and for the databases if `sign_database` is set.
+pub fn verify(
+    repository: &Path,
+    sign_packages: bool,
+    sign_database: bool,
+) -> Result<Vec<Inconsistency>, Error> {
+    let mut inconsistencies = Vec::new();
+    let database = RepositoryDatabase::load_archive(repository)?;
+    let paths = DatabasePaths::new(repository);
+    let directory = repository
+        .parent()
+        .map_or_else(PathBuf::new, Path::to_path_buf);
+
+    let signature_of = |file: &Path| {
+        let mut signature = file.as_os_str().to_owned();
+        signature.push(".sig");
+        PathBuf::from(signature)
+    };
+    let check_signature = |file: &Path, inconsistencies: &mut Vec<Inconsistency>| {
+        if !signature_of(file).exists() {
+            inconsistencies.push(Inconsistency::MissingSignature {
+                file: file.to_path_buf(),
+            });
+        }
+    };
+
+    if sign_database {
+        check_signature(repository, &mut inconsistencies);
+    }
+
+    if let Some(paths) = &paths {
+        if paths.files_archive.exists() {
+            let files_database = RepositoryDatabase::load_archive(&paths.files_archive)?;
+            for (name, record) in database.records() {
+                match files_database.records().get(name) {
+                    None => inconsistencies.push(Inconsistency::NotInFilesDatabase {
+                        directory: name.to_string(),
+                    }),
+                    Some(files_record) if files_record.desc != record.desc => {
+                        inconsistencies.push(Inconsistency::DescMismatch {
+                            directory: name.to_string(),
+                        })
+                    }
+                    Some(_) => {}
+                }
+            }
+            for name in files_database.records().keys() {
+                if !database.records().contains_key(name) {
+                    inconsistencies.push(Inconsistency::NotInDatabase {
+                        directory: name.to_string(),
+                    });
+                }
+            }
+            if sign_database {
+                check_signature(&paths.files_archive, &mut inconsistencies);
+            }
+        } else {
+            inconsistencies.push(Inconsistency::MissingFilesDatabase {
+                file: paths.files_archive.clone(),
+            });
+        }
+    }
+
+    // NOTE: (pkgname, arch) → (version, file name)
+    let mut registered: IndexMap<(String, String), (String, String)> = IndexMap::new();
+    for (name, record) in database.packages() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                inconsistencies.push(Inconsistency::InvalidEntry {
+                    directory: name.to_string(),
+                    error,
+                });
+                continue;
+            }
+        };
+        let arch = match &record.arch {
+            Some(arch) => arch.clone(),
+            None => PackageFileName::parse(&record.file_name)
+                .map_or_else(String::new, |name| name.arch.to_string()),
+        };
+        registered.insert(
+            (record.name.clone(), arch),
+            (record.version.clone(), record.file_name.clone()),
+        );
+
+        let file = directory.join(&record.file_name);
+        let Ok(metadata) = file.metadata() else {
+            inconsistencies.push(Inconsistency::MissingPackageFile {
+                pkgname: record.name,
+                file,
+            });
+            continue;
+        };
+        if let Some(expected) = record.compressed_size {
+            if metadata.len() != expected {
+                inconsistencies.push(Inconsistency::SizeMismatch {
+                    file: file.clone(),
+                    expected,
+                    actual: metadata.len(),
+                });
+            }
+        }
+        if let Some(expected) = record.sha256sum {
+            let actual = sha256sum(&file)?;
+            if actual != expected {
+                inconsistencies.push(Inconsistency::Sha256Mismatch {
+                    file: file.clone(),
+                    expected,
+                    actual,
+                });
+            }
+        }
+        if sign_packages {
+            check_signature(&file, &mut inconsistencies);
+        }
+    }
+
+    let mut file_names = Vec::new();
+    for entry in read_dir_or_current(&directory)? {
+        if let Ok(file_name) = entry?.file_name().into_string() {
+            file_names.push(file_name);
+        }
+    }
+    file_names.sort();
+    for file_name in &file_names {
+        let Some(name) = PackageFileName::parse(file_name) else {
+            continue;
+        };
+        // NOTE: older versions are left for prune, the current version must be the exact file
+        let key = (name.pkgname.to_string(), name.arch.to_string());
+        let is_registered = match registered.get(&key) {
+            Some((version, registered_file_name)) => match vercmp(name.version, version) {
+                Ordering::Less => true,
+                Ordering::Equal => file_name == registered_file_name,
+                Ordering::Greater => false,
+            },
+            None => false,
+        };
+        if !is_registered {
+            inconsistencies.push(Inconsistency::UnregisteredPackageFile {
+                file: directory.join(file_name),
+            });
+        }
+    }
+
+    Ok(inconsistencies)
+}
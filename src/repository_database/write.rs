@@ -0,0 +1,127 @@
+use super::{
+    archive::{self, Compression},
+    paths::DatabasePaths,
+    Error, RepositoryDatabase,
+};
+use command_extra::CommandExtra;
+use std::{
+    fs::{create_dir_all, remove_file, rename},
+    io::{self, ErrorKind},
+    os::unix::fs::symlink,
+    path::Path,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tar::{Builder, EntryType, Header};
+
+impl RepositoryDatabase {
+    /// Write the database to `repository` and the files database next to it, replacing the old ones.
+    ///
+    /// The archives are signed with `gpg` if `sign` is `true`.
+    pub fn write(
+        &self,
+        repository: &Path,
+        sign: bool,
+        signing_key: Option<&str>,
+    ) -> Result<(), Error> {
+        let paths = DatabasePaths::new(repository).ok_or_else(|| {
+            io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("{:?} is not a path to a repository database", repository),
+            )
+        })?;
+        let compression = Compression::from_path(&paths.db_archive)?;
+        create_dir_all(&paths.directory)?;
+
+        let mut names: Vec<_> = self.records.keys().collect();
+        names.sort();
+        let mtime = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+
+        for (archive, link, with_files) in [
+            (&paths.db_archive, &paths.db_link, false),
+            (&paths.files_archive, &paths.files_link, true),
+        ] {
+            let mut builder = Builder::new(Vec::new());
+            for name in &names {
+                let record = &self.records[*name];
+                let mut append = |path: String, content: &[u8]| {
+                    let mut header = Header::new_gnu();
+                    if content.is_empty() && path.ends_with('/') {
+                        header.set_entry_type(EntryType::Directory);
+                        header.set_mode(0o755);
+                    } else {
+                        header.set_entry_type(EntryType::Regular);
+                        header.set_mode(0o644);
+                    }
+                    header.set_size(content.len() as u64);
+                    header.set_mtime(mtime);
+                    builder.append_data(&mut header, path, content)
+                };
+                append(format!("{}/", name), b"")?;
+                append(format!("{}/desc", name), record.desc.as_bytes())?;
+                if let (true, Some(files)) = (with_files, &record.files) {
+                    append(format!("{}/files", name), files.as_bytes())?;
+                }
+            }
+
+            // The new archive replaces the old one only once it is complete.
+            let file_name = archive.file_name().expect("get file name of archive");
+            let mut new_file_name = file_name.to_owned();
+            new_file_name.push(".new");
+            let new_archive = paths.directory.join(new_file_name);
+            archive::create(&new_archive, compression, &builder.into_inner()?)?;
+            rename(&new_archive, archive)?;
+
+            let mut signature = archive.as_os_str().to_owned();
+            signature.push(".sig");
+            remove_if_exists(Path::new(&signature))?;
+            if sign {
+                sign_file(archive, signing_key)?;
+            }
+
+            if let Some(link) = link {
+                remove_if_exists(link)?;
+                symlink(file_name, link)?;
+                let mut link_signature = link.as_os_str().to_owned();
+                link_signature.push(".sig");
+                remove_if_exists(Path::new(&link_signature))?;
+                if sign {
+                    let mut target = file_name.to_owned();
+                    target.push(".sig");
+                    symlink(target, link_signature)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn sign_file(file: &Path, signing_key: Option<&str>) -> Result<(), Error> {
+    let mut command = Command::new("gpg")
+        .with_arg("--detach-sign")
+        .with_arg("--use-agent")
+        .with_arg("--no-armor")
+        .with_arg("--yes");
+    if let Some(signing_key) = signing_key {
+        command = command.with_arg("--local-user").with_arg(signing_key);
+    }
+    let status = command.with_arg(file).status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::Command {
+            program: "gpg",
+            status,
+        })
+    }
+}
+
+fn remove_if_exists(path: &Path) -> Result<(), Error> {
+    match remove_file(path) {
+        Err(error) if error.kind() != ErrorKind::NotFound => Err(error.into()),
+        Ok(()) | Err(_) => Ok(()),
+    }
+}
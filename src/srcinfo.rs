@@ -1,113 +1,127 @@
 pub mod database;
 pub mod dependency;
+pub mod parsed;
 pub mod version;
 
-use super::utils::{extract_value_from_line, PackageFileName};
-use dependency::{ReasonedDependency, UnreasonedDependency};
-use itertools::Itertools;
-use pipe_trait::*;
-use std::str::Lines;
+use super::utils::PackageFileName;
+use dependency::{DependencyField, ReasonedDependency, UnreasonedDependency};
+use parsed::ParsedSrcInfo;
 use version::Version;
 
+/// Text of a .SRCINFO file.
+///
+/// Every method parses the text again, use [`parse`](Self::parse) to query the same text more than once.
 #[derive(Debug, Copy, Clone)]
 pub struct SrcInfo<Text: AsRef<str>>(pub Text);
 
-macro_rules! line_extractor {
-    ($prefix:expr) => {
-        move |line| extract_value_from_line($prefix, line)
-    };
-}
-
 impl<Text: AsRef<str>> SrcInfo<Text> {
-    fn lines(&self) -> Lines {
-        self.0.as_ref().lines()
+    pub fn parse(&self) -> ParsedSrcInfo<'_> {
+        ParsedSrcInfo::parse(self.0.as_ref())
     }
 
     pub fn pkgbase(&self) -> Option<&str> {
-        self.lines().find_map(line_extractor!("pkgbase"))
+        self.parse().pkgbase()
     }
 
     pub fn pkgname(&self) -> impl Iterator<Item = &str> {
-        self.lines().filter_map(line_extractor!("pkgname"))
+        self.parse().pkgname()
     }
 
     pub fn arch(&self) -> impl Iterator<Item = &str> {
-        self.lines().filter_map(line_extractor!("arch"))
+        self.parse().arch()
     }
 
     pub fn version(&self) -> Result<Version<&str, &str, &str>, &'static str> {
-        let pkgver = self
-            .lines()
-            .find_map(line_extractor!("pkgver"))
-            .ok_or("missing pkgver")?;
-        let pkgrel = self
-            .lines()
-            .find_map(line_extractor!("pkgrel"))
-            .ok_or("missing pkgrel")?;
-        let epoch = self
-            .lines()
-            .find_map(line_extractor!("epoch"))
-            .unwrap_or("");
-        Ok(Version {
-            pkgver,
-            pkgrel,
-            epoch,
-        })
-    }
-
-    fn get_dependencies(
+        self.parse().version()
+    }
+
+    /// Dependencies listed in `field` including architecture-specific variants.
+    ///
+    /// If `arch` is `None`, variants of every architecture are included.
+    pub fn dependencies_of(
         &self,
-        key: &'static str,
+        field: DependencyField,
+        arch: Option<&str>,
     ) -> impl Iterator<Item = UnreasonedDependency<&str, &str>> {
-        self.lines()
-            .filter_map(line_extractor!(key))
-            .map(UnreasonedDependency::new)
+        self.parse().dependencies_of(field, arch)
     }
 
     pub fn depends(&self) -> impl Iterator<Item = UnreasonedDependency<&str, &str>> {
-        self.get_dependencies("depends")
+        self.parse().depends()
+    }
+
+    pub fn depends_for_arch(
+        &self,
+        arch: &str,
+    ) -> impl Iterator<Item = UnreasonedDependency<&str, &str>> {
+        self.parse().depends_for_arch(arch)
     }
 
     pub fn makedepends(&self) -> impl Iterator<Item = UnreasonedDependency<&str, &str>> {
-        self.get_dependencies("makedepends")
+        self.parse().makedepends()
+    }
+
+    pub fn makedepends_for_arch(
+        &self,
+        arch: &str,
+    ) -> impl Iterator<Item = UnreasonedDependency<&str, &str>> {
+        self.parse().makedepends_for_arch(arch)
     }
 
     pub fn checkdepends(&self) -> impl Iterator<Item = UnreasonedDependency<&str, &str>> {
-        self.get_dependencies("checkdepends")
+        self.parse().checkdepends()
+    }
+
+    pub fn checkdepends_for_arch(
+        &self,
+        arch: &str,
+    ) -> impl Iterator<Item = UnreasonedDependency<&str, &str>> {
+        self.parse().checkdepends_for_arch(arch)
     }
 
     pub fn optdepends(&self) -> impl Iterator<Item = ReasonedDependency<&str, &str, &str>> {
-        self.lines()
-            .filter_map(line_extractor!("optdepends"))
-            .map(ReasonedDependency::new)
+        self.parse().optdepends()
     }
 
     pub fn conflicts(&self) -> impl Iterator<Item = UnreasonedDependency<&str, &str>> {
-        self.get_dependencies("conflicts")
+        self.parse().conflicts()
+    }
+
+    pub fn conflicts_for_arch(
+        &self,
+        arch: &str,
+    ) -> impl Iterator<Item = UnreasonedDependency<&str, &str>> {
+        self.parse().conflicts_for_arch(arch)
+    }
+
+    pub fn provides(&self) -> impl Iterator<Item = UnreasonedDependency<&str, &str>> {
+        self.parse().provides()
+    }
+
+    pub fn provides_for_arch(
+        &self,
+        arch: &str,
+    ) -> impl Iterator<Item = UnreasonedDependency<&str, &str>> {
+        self.parse().provides_for_arch(arch)
     }
 
     pub fn all_required_dependencies(
         &self,
     ) -> impl Iterator<Item = UnreasonedDependency<&str, &str>> {
-        let depends = self.depends();
-        let makedepends = self.makedepends();
-        let checkdepends = self.checkdepends();
-        depends.chain(makedepends).chain(checkdepends)
+        self.parse().all_required_dependencies()
+    }
+
+    pub fn all_required_dependencies_for_arch(
+        &self,
+        arch: &str,
+    ) -> impl Iterator<Item = UnreasonedDependency<&str, &str>> {
+        self.parse().all_required_dependencies_for_arch(arch)
     }
 
     pub fn package_file_base_names(
         &self,
         filter_arch: impl Fn(&&str) -> bool,
     ) -> Result<impl Iterator<Item = PackageFileName<&str, String, &str>> + '_, String> {
-        let version = self.version().map_err(String::from)?.try_to_string()?;
-
-        self.pkgname()
-            .cartesian_product(self.arch().filter(filter_arch).collect::<Vec<_>>())
-            .map(move |(pkgname, arch)| PackageFileName {
-                pkgname,
-                arch,
-                version: version.clone(),
-            })
-            .pipe(Ok)
+        self.parse().package_file_base_names(filter_arch)
     }
 }
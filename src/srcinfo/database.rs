@@ -1,32 +1,32 @@
 pub mod build_order;
+pub mod dependants;
 pub mod insert_srcinfo;
 pub mod package_file_base_names;
+pub mod resolve_dependencies;
 pub mod text_wrapper;
 
-use super::SrcInfo;
+use super::{dependency::DependencyField, parsed::ParsedSrcInfo};
 use indexmap::{IndexMap, IndexSet};
 use smart_default::SmartDefault;
 use std::{hash::Hash, path::Path};
 use text_wrapper::{PkgBase, PkgName};
 
 #[derive(Debug, SmartDefault)]
-pub struct Database<PkgBase, PkgName, SrcInfoContent, BuildDir>
+pub struct Database<PkgBase, PkgName, SrcInfoData, BuildDir>
 where
     PkgBase: AsRef<str> + Hash + Eq + Clone,
     PkgName: AsRef<str> + Hash + Eq + Clone,
-    SrcInfoContent: AsRef<str>,
     BuildDir: AsRef<Path>,
 {
-    pkgbase: IndexMap<PkgBase, DatabaseValue<PkgBase, PkgName, SrcInfoContent, BuildDir>>,
+    pkgbase: IndexMap<PkgBase, DatabaseValue<PkgBase, PkgName, SrcInfoData, BuildDir>>,
     pkgname: IndexMap<PkgName, PkgBase>,
+    provides: IndexMap<PkgName, IndexSet<PkgBase>>,
 }
 
-impl<PkgBase, PkgName, SrcInfoContent, BuildDir>
-    Database<PkgBase, PkgName, SrcInfoContent, BuildDir>
+impl<PkgBase, PkgName, SrcInfoData, BuildDir> Database<PkgBase, PkgName, SrcInfoData, BuildDir>
 where
     PkgBase: AsRef<str> + Hash + Eq + Clone,
     PkgName: AsRef<str> + Hash + Eq + Clone,
-    SrcInfoContent: AsRef<str>,
     BuildDir: AsRef<Path>,
 {
     pub fn new() -> Self {
@@ -35,24 +35,36 @@ where
 
     pub fn pkgbase(
         &self,
-    ) -> &IndexMap<PkgBase, DatabaseValue<PkgBase, PkgName, SrcInfoContent, BuildDir>> {
+    ) -> &IndexMap<PkgBase, DatabaseValue<PkgBase, PkgName, SrcInfoData, BuildDir>> {
         &self.pkgbase
     }
+
+    pub fn pkgname(&self) -> &IndexMap<PkgName, PkgBase> {
+        &self.pkgname
+    }
+
+    pub fn provides(&self) -> &IndexMap<PkgName, IndexSet<PkgBase>> {
+        &self.provides
+    }
 }
 
 #[derive(Debug)]
-pub struct DatabaseValue<PkgBase, PkgName, SrcInfoContent, BuildDir>
+pub struct DatabaseValue<PkgBase, PkgName, SrcInfoData, BuildDir>
 where
     PkgBase: AsRef<str> + Hash + Eq + Clone,
     PkgName: AsRef<str> + Hash + Eq + Clone,
-    SrcInfoContent: AsRef<str>,
     BuildDir: AsRef<Path>,
 {
     pub names: IndexSet<PkgName>,
-    pub dependencies: IndexSet<PkgBase>,
-    pub srcinfo: SrcInfo<SrcInfoContent>,
+    /// Pkgbases that this pkgbase depends on and the fields that list them.
+    pub dependencies: IndexMap<PkgBase, IndexSet<DependencyField>>,
+    pub srcinfo: SrcInfoData,
     pub directory: BuildDir,
 }
 
-pub type SimpleDatabase<'a> = Database<PkgBase<'a>, PkgName<'a>, &'a str, &'a Path>;
-pub type SimpleDatabaseValue<'a> = DatabaseValue<PkgBase<'a>, PkgName<'a>, &'a str, &'a Path>;
+pub type SimpleDatabase<'a> = Database<PkgBase<'a>, PkgName<'a>, &'a ParsedSrcInfo<'a>, &'a Path>;
+pub type SimpleDatabaseValue<'a> =
+    DatabaseValue<PkgBase<'a>, PkgName<'a>, &'a ParsedSrcInfo<'a>, &'a Path>;
+
+#[cfg(test)]
+mod tests;
@@ -1,20 +1,23 @@
 use super::super::super::status::Code;
+use super::super::dependency::DependencyField;
 use super::{Database, DatabaseValue};
-use indexmap::IndexMap;
-use petgraph::{algo::toposort, graph::Graph};
+use indexmap::{IndexMap, IndexSet};
+use petgraph::{
+    algo::{tarjan_scc, toposort},
+    graph::Graph,
+};
 use pipe_trait::*;
 use std::{
     fmt::{self, Debug, Display, Formatter},
     hash::Hash,
+    iter::once,
     path::Path,
 };
 
-impl<PkgBase, PkgName, SrcInfoContent, BuildDir>
-    Database<PkgBase, PkgName, SrcInfoContent, BuildDir>
+impl<PkgBase, PkgName, SrcInfoData, BuildDir> Database<PkgBase, PkgName, SrcInfoData, BuildDir>
 where
     PkgBase: AsRef<str> + Hash + Eq + Clone,
     PkgName: AsRef<str> + Hash + Eq + Clone,
-    SrcInfoContent: AsRef<str>,
     BuildDir: AsRef<Path>,
 {
     pub fn build_order(&self) -> Result<impl Iterator<Item = &PkgBase>, BuildOrderError<&PkgBase>> {
@@ -33,7 +36,7 @@ where
                 .get(dependant)
                 .copied()
                 .expect("get index of dependant");
-            for dependency in dependencies {
+            for dependency in dependencies.keys() {
                 let dependency_index = pkgbase_to_node_index
                     .get(dependency)
                     .copied()
@@ -42,19 +45,211 @@ where
             }
         }
 
-        toposort(&graph, None)
-            .map_err(|cycle| cycle.node_id())
-            .map_err(|index| *graph.node_weight(index).expect("get cyclic point"))
-            .map_err(BuildOrderError::CyclicDependency)?
+        match toposort(&graph, None) {
+            Ok(order) => order
+                .into_iter()
+                .filter_map(move |index| graph.node_weight(index).copied())
+                .pipe(Ok),
+            Err(_) => self
+                .dependency_cycles(&graph)
+                .pipe(BuildOrderError::CyclicDependency)
+                .pipe(Err),
+        }
+    }
+
+    /// List the elementary cycles of every strongly connected component of `graph`.
+    ///
+    /// At most [`MAX_CYCLES_PER_COMPONENT`] cycles are listed for each component.
+    fn dependency_cycles<'r>(&'r self, graph: &Graph<&'r PkgBase, ()>) -> Vec<Cycle<&'r PkgBase>> {
+        let mut components: Vec<IndexSet<&PkgBase>> = tarjan_scc(graph)
             .into_iter()
-            .filter_map(move |index| graph.node_weight(index).copied())
-            .pipe(Ok)
+            .filter(|component| component.len() > 1)
+            .map(|component| {
+                let mut members: Vec<_> = component
+                    .into_iter()
+                    .map(|index| *graph.node_weight(index).expect("get cyclic member"))
+                    .collect();
+                members.sort_by_key(|pkgbase| self.pkgbase().get_index_of(*pkgbase));
+                members.into_iter().collect()
+            })
+            .collect();
+        components.sort_by_key(|members| self.pkgbase().get_index_of(members[0]));
+
+        components
+            .iter()
+            .flat_map(|members| {
+                members
+                    .iter()
+                    .map(|member| {
+                        let dependencies = self.pkgbase()[*member]
+                            .dependencies
+                            .keys()
+                            .filter(|dependency| members.contains(dependency))
+                            .collect();
+                        (*member, dependencies)
+                    })
+                    .collect::<IndexMap<_, _>>()
+                    .pipe(elementary_cycles)
+            })
+            .map(|chain| {
+                let closing = (chain[chain.len() - 1], chain[0]);
+                chain
+                    .windows(2)
+                    .map(|pair| (pair[0], pair[1]))
+                    .chain(once(closing))
+                    .map(|(dependant, dependency)| CycleEdge {
+                        dependant,
+                        dependency,
+                        fields: self.pkgbase()[dependant].dependencies[dependency]
+                            .iter()
+                            .copied()
+                            .collect(),
+                    })
+                    .collect::<Vec<_>>()
+                    .pipe(|edges| Cycle { edges })
+            })
+            .collect()
+    }
+}
+
+/// Maximum number of cycles to list for each strongly connected component.
+const MAX_CYCLES_PER_COMPONENT: usize = 32;
+
+/// Find the elementary cycles of a strongly connected component with Johnson's algorithm.
+///
+/// `dependencies` maps every member of the component to its dependencies within the component.
+/// Each cycle is listed once, starting from its member that comes first in `dependencies`.
+fn elementary_cycles<'r, Node: Hash + Eq>(
+    dependencies: IndexMap<&'r Node, Vec<&'r Node>>,
+) -> Vec<Vec<&'r Node>> {
+    let mut search = CycleSearch {
+        dependencies,
+        stack: Vec::new(),
+        blocked: IndexSet::new(),
+        blocked_by: IndexMap::new(),
+        cycles: Vec::new(),
+    };
+    for start in 0..search.dependencies.len() {
+        search.blocked.clear();
+        search.blocked_by.clear();
+        search.circuit(start, start);
+        if search.cycles.len() >= MAX_CYCLES_PER_COMPONENT {
+            break;
+        }
+    }
+    search.cycles
+}
+
+struct CycleSearch<'r, Node: Hash + Eq> {
+    dependencies: IndexMap<&'r Node, Vec<&'r Node>>,
+    stack: Vec<&'r Node>,
+    blocked: IndexSet<usize>,
+    /// Nodes to unblock when the key is unblocked.
+    blocked_by: IndexMap<usize, IndexSet<usize>>,
+    cycles: Vec<Vec<&'r Node>>,
+}
+
+impl<'r, Node: Hash + Eq> CycleSearch<'r, Node> {
+    /// Dependencies of the node at `index`, leaving out the nodes that come before `start`.
+    fn next_nodes(&self, index: usize, start: usize) -> Vec<usize> {
+        let (_, dependencies) = self
+            .dependencies
+            .get_index(index)
+            .expect("get dependencies of node");
+        dependencies
+            .iter()
+            .filter_map(|dependency| self.dependencies.get_index_of(*dependency))
+            .filter(|next| *next >= start)
+            .collect()
+    }
+
+    fn circuit(&mut self, index: usize, start: usize) -> bool {
+        let (node, _) = self.dependencies.get_index(index).expect("get node");
+        self.stack.push(node);
+        self.blocked.insert(index);
+
+        let mut found = false;
+        let next_nodes = self.next_nodes(index, start);
+        for next in next_nodes.iter().copied() {
+            if self.cycles.len() >= MAX_CYCLES_PER_COMPONENT {
+                break;
+            }
+            if next == start {
+                self.cycles.push(self.stack.clone());
+                found = true;
+            } else if !self.blocked.contains(&next) && self.circuit(next, start) {
+                found = true;
+            }
+        }
+
+        if found {
+            self.unblock(index);
+        } else {
+            for next in next_nodes {
+                self.blocked_by.entry(next).or_default().insert(index);
+            }
+        }
+
+        self.stack.pop();
+        found
+    }
+
+    fn unblock(&mut self, index: usize) {
+        self.blocked.swap_remove(&index);
+        for blocked in self.blocked_by.swap_remove(&index).unwrap_or_default() {
+            if self.blocked.contains(&blocked) {
+                self.unblock(blocked);
+            }
+        }
+    }
+}
+
+/// Closed chain of dependencies, the dependency of the last edge is the dependant of the first.
+#[derive(Debug, Clone)]
+pub struct Cycle<PkgBase> {
+    pub edges: Vec<CycleEdge<PkgBase>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CycleEdge<PkgBase> {
+    pub dependant: PkgBase,
+    pub dependency: PkgBase,
+    /// Fields of the dependant that list the dependency.
+    pub fields: Vec<DependencyField>,
+}
+
+impl<PkgBase> Display for Cycle<PkgBase>
+where
+    PkgBase: Display,
+{
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(first) = self.edges.first() {
+            write!(formatter, "{}", first.dependant)?;
+        }
+        for edge in &self.edges {
+            write!(formatter, " -> {}", edge.dependency)?;
+        }
+        Ok(())
+    }
+}
+
+impl<PkgBase> Display for CycleEdge<PkgBase>
+where
+    PkgBase: Display,
+{
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{} -> {}:", self.dependant, self.dependency)?;
+        for (index, field) in self.fields.iter().enumerate() {
+            let separator = if index == 0 { " " } else { ", " };
+            write!(formatter, "{}{}", separator, field)?;
+        }
+        Ok(())
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub enum BuildOrderError<PkgBase> {
-    CyclicDependency(PkgBase),
+    CyclicDependency(Vec<Cycle<PkgBase>>),
 }
 
 impl<PkgBase> BuildOrderError<PkgBase> {
@@ -71,8 +266,15 @@ where
 {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            BuildOrderError::CyclicDependency(pkgbase) => {
-                write!(formatter, "Dependency cycle detected at {}", pkgbase)
+            BuildOrderError::CyclicDependency(cycles) => {
+                write!(formatter, "Dependency cycle detected")?;
+                for cycle in cycles {
+                    write!(formatter, "\n  * {}", cycle)?;
+                    for edge in &cycle.edges {
+                        write!(formatter, "\n    - {}", edge)?;
+                    }
+                }
+                Ok(())
             }
         }
     }
@@ -0,0 +1,33 @@
+use super::{Database, DatabaseValue};
+use indexmap::IndexSet;
+use std::{hash::Hash, path::Path};
+
+impl<PkgBase, PkgName, SrcInfoData, BuildDir> Database<PkgBase, PkgName, SrcInfoData, BuildDir>
+where
+    PkgBase: AsRef<str> + Hash + Eq + Clone,
+    PkgName: AsRef<str> + Hash + Eq + Clone,
+    BuildDir: AsRef<Path>,
+{
+    /// List every pkgbase that depends on `pkgbase`, directly or through other pkgbases.
+    ///
+    /// Dependencies are only known after [`resolve_dependencies`](Self::resolve_dependencies).
+    pub fn transitive_dependants(&self, pkgbase: &PkgBase) -> IndexSet<&PkgBase> {
+        let mut dependants = IndexSet::new();
+        let mut index = 0;
+        let mut current = pkgbase;
+
+        loop {
+            for (dependant, DatabaseValue { dependencies, .. }) in self.pkgbase() {
+                if dependant != pkgbase && dependencies.contains_key(current) {
+                    dependants.insert(dependant);
+                }
+            }
+
+            let Some(next) = dependants.get_index(index) else {
+                return dependants;
+            };
+            current = next;
+            index += 1;
+        }
+    }
+}
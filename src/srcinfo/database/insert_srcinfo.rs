@@ -1,9 +1,10 @@
-use super::super::SrcInfo;
+use super::super::parsed::ParsedSrcInfo;
 use super::{
+    resolve_dependencies::provisions,
     text_wrapper::{PkgBase, PkgName},
     SimpleDatabase, SimpleDatabaseValue,
 };
-use indexmap::IndexSet;
+use indexmap::{IndexMap, IndexSet};
 use pipe_trait::*;
 use std::{
     fmt::{self, Display, Formatter},
@@ -13,41 +14,39 @@ use std::{
 impl<'a> SimpleDatabase<'a> {
     pub fn insert_srcinfo(
         &mut self,
-        srcinfo: &'a SrcInfo<&'a str>,
+        srcinfo: &'a ParsedSrcInfo<'a>,
         directory: &'a Path,
+        target_arch: Option<&str>,
     ) -> Result<Option<RemovalInfo<'a>>, InsertionError> {
         let pkgbase = srcinfo
             .pkgbase()
             .ok_or(InsertionError::MissingPkgBase)?
             .pipe(PkgBase);
 
-        let mut dependencies = IndexSet::<PkgBase>::new();
         let mut names = IndexSet::<PkgName>::new();
 
         for pkgname in srcinfo.pkgname() {
             let pkgname = PkgName(pkgname);
             names.insert(pkgname);
             self.pkgname.insert(pkgname, pkgbase);
+        }
 
-            for dependency in srcinfo.all_required_dependencies() {
-                if let Some(dependency_pkgbase) = dependency
-                    .name()
-                    .pipe(PkgName)
-                    .pipe_ref(|name| self.pkgname.get(name))
-                    .copied()
-                {
-                    dependencies.insert(dependency_pkgbase);
-                }
-            }
+        for provision in provisions(srcinfo, target_arch) {
+            self.provides
+                .entry(PkgName(provision.name))
+                .or_default()
+                .insert(pkgbase);
         }
 
+        // NOTE: dependencies are linked later by resolve_dependencies,
+        //       after every member has been registered
         self.pkgbase
             .insert(
                 pkgbase,
                 SimpleDatabaseValue {
                     names,
-                    dependencies,
-                    srcinfo: *srcinfo,
+                    dependencies: IndexMap::new(),
+                    srcinfo,
                     directory,
                 },
             )
@@ -1,23 +1,18 @@
 use super::super::super::utils::PackageFileName;
-use super::super::SrcInfo;
-use super::{Database, DatabaseValue};
+use super::super::parsed::ParsedSrcInfo;
+use super::{text_wrapper::PkgBase, DatabaseValue, SimpleDatabase};
 use pipe_trait::*;
-use std::{hash::Hash, path::Path};
 
-impl<PkgBase, PkgName, SrcInfoContent, BuildDir>
-    Database<PkgBase, PkgName, SrcInfoContent, BuildDir>
-where
-    PkgBase: AsRef<str> + Hash + Eq + Clone,
-    PkgName: AsRef<str> + Hash + Eq + Clone,
-    SrcInfoContent: AsRef<str>,
-    BuildDir: AsRef<Path>,
-{
-    pub fn package_file_base_names<'a>(
-        &'a self,
-        filter_arch: impl Fn(&&str) -> bool + Copy + 'a,
+impl<'a> SimpleDatabase<'a> {
+    pub fn package_file_base_names<'r>(
+        &'r self,
+        filter_arch: impl Fn(&&'a str) -> bool + Copy + 'r,
     ) -> impl Iterator<
-        Item = Result<PackageFileName<&str, String, &str>, Error<PkgBase, SrcInfoContent>>,
-    > + 'a {
+        Item = Result<
+            PackageFileName<&'a str, String, &'a str>,
+            Error<'r, PkgBase<'a>, &'a ParsedSrcInfo<'a>>,
+        >,
+    > + 'r {
         self.pkgbase()
             .iter()
             .flat_map(move |(pkgbase, value)| -> Box<dyn Iterator<Item = _>> {
@@ -38,11 +33,8 @@ where
 }
 
 #[derive(Debug, Clone)]
-pub struct Error<'a, PkgBase, SrcInfoContent>
-where
-    SrcInfoContent: AsRef<str>,
-{
+pub struct Error<'a, PkgBase, SrcInfoData> {
     pub pkgbase: &'a PkgBase,
-    pub srcinfo: &'a SrcInfo<SrcInfoContent>,
+    pub srcinfo: &'a SrcInfoData,
     pub message: String,
 }
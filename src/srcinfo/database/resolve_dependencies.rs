@@ -0,0 +1,166 @@
+use super::super::{
+    dependency::{DependencyField, UnreasonedDependency},
+    parsed::ParsedSrcInfo,
+};
+use super::{
+    text_wrapper::{PkgBase, PkgName},
+    SimpleDatabase,
+};
+use indexmap::{IndexMap, IndexSet};
+use std::{
+    fmt::{self, Display, Formatter},
+    iter::once,
+};
+
+impl<'a> SimpleDatabase<'a> {
+    /// Link every registered pkgbase to the pkgbases it depends on.
+    ///
+    /// This must be called after all members have been inserted so that
+    /// the result does not depend on the order of members in the manifest.
+    ///
+    /// Returns the dependencies whose version ranges are not satisfied by the members they are linked to.
+    pub fn resolve_dependencies(
+        &mut self,
+        target_arch: Option<&str>,
+    ) -> Vec<UnsatisfiedDependency<'a>> {
+        let mut unsatisfied = Vec::new();
+        let resolved: Vec<_> = self
+            .pkgbase
+            .iter()
+            .map(|(pkgbase, value)| {
+                // NOTE: split packages that depend on their siblings must not create self loops
+                let mut dependencies: IndexMap<PkgBase<'a>, IndexSet<DependencyField>> =
+                    IndexMap::new();
+                for field in DependencyField::ALL {
+                    for dependency in value.srcinfo.dependencies_of(field, target_arch) {
+                        for provider in self.resolve_dependency(dependency, target_arch) {
+                            if provider == *pkgbase {
+                                continue;
+                            }
+                            if !self.does_member_satisfy(provider, dependency, target_arch) {
+                                unsatisfied.push(UnsatisfiedDependency {
+                                    dependant: *pkgbase,
+                                    dependency: UnreasonedDependency {
+                                        name: dependency.name.to_string(),
+                                        range: dependency.range.to_string(),
+                                    },
+                                    provider,
+                                });
+                            }
+                            dependencies.entry(provider).or_default().insert(field);
+                        }
+                    }
+                }
+
+                (*pkgbase, dependencies)
+            })
+            .collect();
+
+        for (pkgbase, dependencies) in resolved {
+            if let Some(value) = self.pkgbase.get_mut(&pkgbase) {
+                value.dependencies = dependencies;
+            }
+        }
+
+        unsatisfied
+    }
+
+    /// Find pkgbases that satisfy `dependency`, either by pkgname or by `provides`.
+    ///
+    /// A member whose pkgname matches but whose version is out of range is only
+    /// returned when no other member provides a satisfying version.
+    pub fn resolve_dependency(
+        &self,
+        dependency: UnreasonedDependency<&str, &str>,
+        target_arch: Option<&str>,
+    ) -> IndexSet<PkgBase<'a>> {
+        let name = PkgName(dependency.name);
+        let named = self.pkgname.get(&name).copied();
+
+        if let Some(pkgbase) = named {
+            if self.does_member_satisfy(pkgbase, dependency, target_arch) {
+                return once(pkgbase).collect();
+            }
+        }
+
+        let providers: IndexSet<_> = self
+            .provides
+            .get(&name)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|provider| match self.pkgbase.get(provider) {
+                Some(value) => provisions(value.srcinfo, target_arch)
+                    .iter()
+                    .any(|provision| dependency.is_satisfied_by_provision(provision)),
+                None => false,
+            })
+            .collect();
+
+        if providers.is_empty() {
+            return named.into_iter().collect();
+        }
+
+        providers
+    }
+
+    /// Check whether the packages of `pkgbase` satisfy `dependency` either by name and version or by `provides`.
+    pub fn does_member_satisfy(
+        &self,
+        pkgbase: PkgBase<'a>,
+        dependency: UnreasonedDependency<&str, &str>,
+        target_arch: Option<&str>,
+    ) -> bool {
+        let Some(value) = self.pkgbase.get(&pkgbase) else {
+            return false;
+        };
+
+        let version = value
+            .srcinfo
+            .version()
+            .and_then(|version| version.try_to_string().map_err(|_| "invalid version"))
+            .unwrap_or_default();
+        let satisfied_by_name = value
+            .names
+            .iter()
+            .any(|pkgname| dependency.is_satisfied_by(pkgname.0, &version));
+
+        satisfied_by_name
+            || provisions(value.srcinfo, target_arch)
+                .iter()
+                .any(|provision| dependency.is_satisfied_by_provision(provision))
+    }
+}
+
+/// A dependency that is linked to a member whose version is out of its range.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UnsatisfiedDependency<'a> {
+    pub dependant: PkgBase<'a>,
+    pub dependency: UnreasonedDependency<String, String>,
+    pub provider: PkgBase<'a>,
+}
+
+impl<'a> Display for UnsatisfiedDependency<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let UnsatisfiedDependency {
+            dependant,
+            dependency,
+            provider,
+        } = self;
+        write!(
+            f,
+            "{} requires {}{} which {} does not satisfy",
+            dependant, dependency.name, dependency.range, provider,
+        )
+    }
+}
+
+pub(super) fn provisions<'a>(
+    srcinfo: &ParsedSrcInfo<'a>,
+    target_arch: Option<&str>,
+) -> Vec<UnreasonedDependency<&'a str, &'a str>> {
+    match target_arch {
+        Some(arch) => srcinfo.provides_for_arch(arch).collect(),
+        None => srcinfo.provides().collect(),
+    }
+}
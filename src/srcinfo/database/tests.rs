@@ -0,0 +1,273 @@
+use super::super::parsed::ParsedSrcInfo;
+use super::{
+    text_wrapper::{PkgBase, PkgName},
+    SimpleDatabase,
+};
+use itertools::Itertools;
+use std::path::Path;
+
+const TOP: &str = r#"
+pkgbase = top
+	pkgver = 1
+	pkgrel = 1
+
+pkgname = top-foo
+
+pkgname = top-bar
+"#;
+
+const PROVIDER: &str = r#"
+pkgbase = provider
+	pkgver = 1
+	pkgrel = 1
+	provides = virtual=2
+
+pkgname = provider
+"#;
+
+const MIDDLE: &str = r#"
+pkgbase = middle
+	pkgver = 1
+	pkgrel = 1
+	depends = top-bar
+	makedepends = virtual>=1
+
+pkgname = middle
+"#;
+
+const BOTTOM: &str = r#"
+pkgbase = bottom
+	pkgver = 1
+	pkgrel = 1
+	checkdepends = middle
+
+pkgname = bottom
+"#;
+
+const EDGES: &[(&str, &str)] = &[
+    ("middle", "top"),
+    ("middle", "provider"),
+    ("bottom", "middle"),
+];
+
+#[test]
+fn build_order_does_not_depend_on_insertion_order() {
+    let srcinfo_list = [
+        ParsedSrcInfo::parse(TOP),
+        ParsedSrcInfo::parse(PROVIDER),
+        ParsedSrcInfo::parse(MIDDLE),
+        ParsedSrcInfo::parse(BOTTOM),
+    ];
+    let directory = Path::new("");
+
+    for permutation in srcinfo_list.iter().permutations(srcinfo_list.len()) {
+        let mut database = SimpleDatabase::new();
+        for srcinfo in permutation.iter().copied() {
+            database.insert_srcinfo(srcinfo, directory, None).unwrap();
+        }
+        assert_eq!(database.resolve_dependencies(None), []);
+
+        let build_order: Vec<_> = database
+            .build_order()
+            .unwrap()
+            .map(|pkgbase| pkgbase.0)
+            .collect();
+        let position = |pkgbase: &str| build_order.iter().position(|x| *x == pkgbase).unwrap();
+
+        for (dependant, dependency) in EDGES {
+            assert!(
+                position(dependency) < position(dependant),
+                "{dependency} must be built before {dependant} in {build_order:?}",
+            );
+        }
+    }
+}
+
+#[test]
+fn resolve_dependencies() {
+    let srcinfo_list = [
+        ParsedSrcInfo::parse(BOTTOM),
+        ParsedSrcInfo::parse(MIDDLE),
+        ParsedSrcInfo::parse(PROVIDER),
+        ParsedSrcInfo::parse(TOP),
+    ];
+    let mut database = SimpleDatabase::new();
+    for srcinfo in &srcinfo_list {
+        database
+            .insert_srcinfo(srcinfo, Path::new(""), None)
+            .unwrap();
+    }
+    let unsatisfied = database.resolve_dependencies(None);
+    assert_eq!(unsatisfied, []);
+
+    let actual: Vec<_> = database
+        .pkgbase()
+        .iter()
+        .map(|(pkgbase, value)| {
+            let dependencies: Vec<_> = value.dependencies.keys().map(|x| x.0).collect();
+            (pkgbase.0, dependencies)
+        })
+        .collect();
+    let expected = vec![
+        ("bottom", vec!["middle"]),
+        ("middle", vec!["top", "provider"]),
+        ("provider", vec![]),
+        ("top", vec![]),
+    ];
+    assert_eq!(actual, expected);
+    assert_eq!(
+        database.pkgname().get(&PkgName("top-bar")),
+        Some(&PkgBase("top")),
+    );
+}
+
+#[test]
+fn resolve_versioned_dependencies() {
+    let srcinfo_list = [
+        ParsedSrcInfo::parse("pkgbase = old\npkgver = 1\npkgrel = 1\npkgname = lib\n"),
+        ParsedSrcInfo::parse("pkgbase = new\npkgver = 1\npkgrel = 1\nprovides = lib=3\npkgname = lib-ng\n"),
+        ParsedSrcInfo::parse("pkgbase = solo\npkgver = 1\npkgrel = 1\npkgname = solo\n"),
+        ParsedSrcInfo::parse("pkgbase = consumer\npkgver = 1\npkgrel = 1\ndepends = lib>=2\ndepends = solo>=5\npkgname = consumer\n"),
+    ];
+    let mut database = SimpleDatabase::new();
+    for srcinfo in &srcinfo_list {
+        database
+            .insert_srcinfo(srcinfo, Path::new(""), None)
+            .unwrap();
+    }
+
+    let unsatisfied: Vec<_> = database
+        .resolve_dependencies(None)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        unsatisfied,
+        ["consumer requires solo>=5 which solo does not satisfy"],
+    );
+
+    let dependencies: Vec<_> = database.pkgbase()[&PkgBase("consumer")]
+        .dependencies
+        .keys()
+        .map(|x| x.0)
+        .collect();
+    assert_eq!(dependencies, ["new", "solo"]);
+}
+
+#[test]
+fn report_dependency_cycles() {
+    let srcinfo_list = [
+        ParsedSrcInfo::parse(
+            "pkgbase = a\npkgver = 1\npkgrel = 1\ndepends = b\nmakedepends = b\npkgname = a\n",
+        ),
+        ParsedSrcInfo::parse("pkgbase = b\npkgver = 1\npkgrel = 1\nmakedepends = c\npkgname = b\n"),
+        ParsedSrcInfo::parse(
+            "pkgbase = c\npkgver = 1\npkgrel = 1\ncheckdepends = a\npkgname = c\n",
+        ),
+        ParsedSrcInfo::parse(
+            "pkgbase = d\npkgver = 1\npkgrel = 1\ndepends = a\ndepends = e\npkgname = d\n",
+        ),
+        ParsedSrcInfo::parse("pkgbase = e\npkgver = 1\npkgrel = 1\ndepends = d\npkgname = e\n"),
+        ParsedSrcInfo::parse("pkgbase = f\npkgver = 1\npkgrel = 1\ndepends = a\npkgname = f\n"),
+    ];
+    let mut database = SimpleDatabase::new();
+    for srcinfo in &srcinfo_list {
+        database
+            .insert_srcinfo(srcinfo, Path::new(""), None)
+            .unwrap();
+    }
+    assert_eq!(database.resolve_dependencies(None), []);
+
+    let error = match database.build_order() {
+        Ok(build_order) => panic!(
+            "unexpected build order: {:?}",
+            build_order.collect::<Vec<_>>()
+        ),
+        Err(error) => error,
+    };
+    let actual = error.to_string();
+    let expected = [
+        "Dependency cycle detected",
+        "  * a -> b -> c -> a",
+        "    - a -> b: depends, makedepends",
+        "    - b -> c: makedepends",
+        "    - c -> a: checkdepends",
+        "  * d -> e -> d",
+        "    - d -> e: depends",
+        "    - e -> d: depends",
+    ]
+    .join("\n");
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn report_overlapping_dependency_cycles() {
+    let srcinfo_list = [
+        ParsedSrcInfo::parse("pkgbase = a\npkgver = 1\npkgrel = 1\ndepends = b\npkgname = a\n"),
+        ParsedSrcInfo::parse(
+            "pkgbase = b\npkgver = 1\npkgrel = 1\ndepends = a\nmakedepends = c\npkgname = b\n",
+        ),
+        ParsedSrcInfo::parse(
+            "pkgbase = c\npkgver = 1\npkgrel = 1\ndepends = a\ndepends = b\npkgname = c\n",
+        ),
+    ];
+    let mut database = SimpleDatabase::new();
+    for srcinfo in &srcinfo_list {
+        database
+            .insert_srcinfo(srcinfo, Path::new(""), None)
+            .unwrap();
+    }
+    assert_eq!(database.resolve_dependencies(None), []);
+
+    let actual = match database.build_order() {
+        Ok(build_order) => panic!(
+            "unexpected build order: {:?}",
+            build_order.collect::<Vec<_>>()
+        ),
+        Err(error) => error.to_string(),
+    };
+    let expected = [
+        "Dependency cycle detected",
+        "  * a -> b -> a",
+        "    - a -> b: depends",
+        "    - b -> a: depends",
+        "  * a -> b -> c -> a",
+        "    - a -> b: depends",
+        "    - b -> c: makedepends",
+        "    - c -> a: depends",
+        "  * b -> c -> b",
+        "    - b -> c: makedepends",
+        "    - c -> b: depends",
+    ]
+    .join("\n");
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn transitive_dependants() {
+    let srcinfo_list = [
+        ParsedSrcInfo::parse(TOP),
+        ParsedSrcInfo::parse(PROVIDER),
+        ParsedSrcInfo::parse(MIDDLE),
+        ParsedSrcInfo::parse(BOTTOM),
+    ];
+    let mut database = SimpleDatabase::new();
+    for srcinfo in &srcinfo_list {
+        database
+            .insert_srcinfo(srcinfo, Path::new(""), None)
+            .unwrap();
+    }
+    assert_eq!(database.resolve_dependencies(None), []);
+
+    let dependants = |pkgbase| -> Vec<_> {
+        database
+            .transitive_dependants(&PkgBase(pkgbase))
+            .into_iter()
+            .map(|x| x.0)
+            .collect()
+    };
+    assert_eq!(dependants("top"), ["middle", "bottom"]);
+    assert_eq!(dependants("provider"), ["middle", "bottom"]);
+    assert_eq!(dependants("middle"), ["bottom"]);
+    assert_eq!(dependants("bottom"), [] as [&str; 0]);
+}
@@ -1,5 +1,9 @@
+mod constraint;
+mod field;
 mod reasoned;
 mod unreasoned;
 
+pub use constraint::{ComparisonOperator, ConstraintParsingError, VersionConstraint};
+pub use field::DependencyField;
 pub use reasoned::ReasonedDependency;
 pub use unreasoned::UnreasonedDependency;
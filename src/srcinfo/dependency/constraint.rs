@@ -0,0 +1,127 @@
+use super::super::version::{vercmp, Version};
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum ComparisonOperator {
+    Equal,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl ComparisonOperator {
+    /// Longer operators come first so that `<=` is not mistaken for `<`.
+    const ALL: [ComparisonOperator; 5] = [
+        ComparisonOperator::LessOrEqual,
+        ComparisonOperator::GreaterOrEqual,
+        ComparisonOperator::Equal,
+        ComparisonOperator::Less,
+        ComparisonOperator::Greater,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ComparisonOperator::Equal => "=",
+            ComparisonOperator::Less => "<",
+            ComparisonOperator::LessOrEqual => "<=",
+            ComparisonOperator::Greater => ">",
+            ComparisonOperator::GreaterOrEqual => ">=",
+        }
+    }
+
+    /// Whether `ordering` (of the tested version relative to the required version) is accepted.
+    pub fn accepts(self, ordering: Ordering) -> bool {
+        match self {
+            ComparisonOperator::Equal => ordering == Ordering::Equal,
+            ComparisonOperator::Less => ordering == Ordering::Less,
+            ComparisonOperator::LessOrEqual => ordering != Ordering::Greater,
+            ComparisonOperator::Greater => ordering == Ordering::Greater,
+            ComparisonOperator::GreaterOrEqual => ordering != Ordering::Less,
+        }
+    }
+}
+
+impl Display for ComparisonOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Version range of a dependency, such as `>=1:2.0.0-3`.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub struct VersionConstraint<Text: AsRef<str>> {
+    pub operator: ComparisonOperator,
+    /// Required version in the form of `[epoch:]pkgver[-pkgrel]`.
+    pub version: Text,
+}
+
+impl<'a> VersionConstraint<&'a str> {
+    pub fn parse(range: &'a str) -> Result<Self, ConstraintParsingError> {
+        let (operator, version) = ComparisonOperator::ALL
+            .iter()
+            .find_map(|operator| {
+                range
+                    .strip_prefix(operator.as_str())
+                    .map(|version| (*operator, version))
+            })
+            .ok_or(ConstraintParsingError::MissingOperator)?;
+
+        if version.is_empty() {
+            return Err(ConstraintParsingError::MissingVersion);
+        }
+
+        Ok(VersionConstraint { operator, version })
+    }
+}
+
+impl<Text: AsRef<str>> VersionConstraint<Text> {
+    pub fn version(&self) -> &str {
+        self.version.as_ref()
+    }
+
+    /// Check a full version string such as `1:2.0.0-3` against the constraint.
+    ///
+    /// Just like pacman, the pkgrel is only compared when both sides specify it,
+    /// and a missing epoch is treated as `0`.
+    pub fn is_satisfied_by_str(&self, version: &str) -> bool {
+        self.operator.accepts(vercmp(version, self.version()))
+    }
+
+    pub fn is_satisfied_by(
+        &self,
+        version: &Version<impl AsRef<str>, impl AsRef<str>, impl AsRef<str>>,
+    ) -> bool {
+        match version.try_to_string() {
+            Ok(version) => self.is_satisfied_by_str(&version),
+            Err(_) => false,
+        }
+    }
+}
+
+impl<Text: AsRef<str>> Display for VersionConstraint<Text> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.operator, self.version())
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum ConstraintParsingError {
+    MissingOperator,
+    MissingVersion,
+}
+
+impl Display for ConstraintParsingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintParsingError::MissingOperator => write!(f, "missing comparison operator"),
+            ConstraintParsingError::MissingVersion => write!(f, "missing version"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
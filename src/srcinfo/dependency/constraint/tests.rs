@@ -0,0 +1,80 @@
+use super::super::super::version::Version;
+use super::{ComparisonOperator, ConstraintParsingError, VersionConstraint};
+
+#[test]
+fn parse() {
+    let actual = [
+        VersionConstraint::parse(">=1:2.0.0-3"),
+        VersionConstraint::parse("<=2"),
+        VersionConstraint::parse("=2"),
+        VersionConstraint::parse("<2"),
+        VersionConstraint::parse(">2"),
+        VersionConstraint::parse(">="),
+        VersionConstraint::parse("2.0.0"),
+    ];
+    let expected = [
+        Ok(VersionConstraint {
+            operator: ComparisonOperator::GreaterOrEqual,
+            version: "1:2.0.0-3",
+        }),
+        Ok(VersionConstraint {
+            operator: ComparisonOperator::LessOrEqual,
+            version: "2",
+        }),
+        Ok(VersionConstraint {
+            operator: ComparisonOperator::Equal,
+            version: "2",
+        }),
+        Ok(VersionConstraint {
+            operator: ComparisonOperator::Less,
+            version: "2",
+        }),
+        Ok(VersionConstraint {
+            operator: ComparisonOperator::Greater,
+            version: "2",
+        }),
+        Err(ConstraintParsingError::MissingVersion),
+        Err(ConstraintParsingError::MissingOperator),
+    ];
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn is_satisfied_by_str() {
+    let satisfies = |range, version| {
+        VersionConstraint::parse(range)
+            .unwrap()
+            .is_satisfied_by_str(version)
+    };
+    let actual = [
+        satisfies(">=2.0.0", "2.0.0-1"),
+        satisfies(">=2.0.0", "1.9.9-1"),
+        satisfies(">2.0.0", "2.0.0-1"),
+        satisfies("<2.0.0", "1.10.0-1"),
+        satisfies("<=2.0.0", "2.0.0-5"),
+        satisfies("=2.0.0", "2.0.0-5"),
+        satisfies("=2.0.0-4", "2.0.0-5"),
+        satisfies(">=2.0.0-4", "2.0.0-5"),
+        satisfies(">=1:1.0.0", "2.0.0-1"),
+        satisfies(">=1:1.0.0", "1:1.0.0-1"),
+        satisfies("<1:0", "9.9.9-1"),
+        satisfies(">=0:2.0.0", "2.0.0-1"),
+    ];
+    let expected = [
+        true, false, false, true, true, true, false, true, false, true, true, true,
+    ];
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn is_satisfied_by_version() {
+    let constraint = VersionConstraint::parse(">=1:2.0.0-3").unwrap();
+    let actual = [
+        constraint.is_satisfied_by(&Version::new("2.0.0", "3", "1")),
+        constraint.is_satisfied_by(&Version::new("2.0.0", "2", "1")),
+        constraint.is_satisfied_by(&Version::new("3.0.0", "1", "")),
+        constraint.is_satisfied_by(&Version::new("3.0.0", "1", "invalid")),
+    ];
+    let expected = [true, false, false, false];
+    assert_eq!(actual, expected);
+}
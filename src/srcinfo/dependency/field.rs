@@ -0,0 +1,31 @@
+use std::fmt::{self, Display, Formatter};
+
+/// Field of a `.SRCINFO` that lists the dependencies required to build a package.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash)]
+pub enum DependencyField {
+    Depends,
+    MakeDepends,
+    CheckDepends,
+}
+
+impl DependencyField {
+    pub const ALL: [DependencyField; 3] = [
+        DependencyField::Depends,
+        DependencyField::MakeDepends,
+        DependencyField::CheckDepends,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            DependencyField::Depends => "depends",
+            DependencyField::MakeDepends => "makedepends",
+            DependencyField::CheckDepends => "checkdepends",
+        }
+    }
+}
+
+impl Display for DependencyField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.key())
+    }
+}
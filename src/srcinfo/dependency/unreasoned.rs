@@ -1,5 +1,8 @@
 use super::super::super::utils::extract_pkgname_prefix;
-use super::reasoned::ReasonedDependency;
+use super::{
+    constraint::{ConstraintParsingError, VersionConstraint},
+    reasoned::ReasonedDependency,
+};
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
 pub struct UnreasonedDependency<Name, Range>
@@ -41,6 +44,49 @@ where
     pub fn range(&self) -> &str {
         self.range.as_ref()
     }
+
+    /// Parse the version range, `None` means that any version is accepted.
+    pub fn version_constraint(
+        &self,
+    ) -> Result<Option<VersionConstraint<&str>>, ConstraintParsingError> {
+        let range = self.range();
+        if range.is_empty() {
+            return Ok(None);
+        }
+        VersionConstraint::parse(range).map(Some)
+    }
+
+    /// Check whether a package of `pkgname` at `version` satisfies this dependency.
+    ///
+    /// An invalid version range is never satisfied.
+    pub fn is_satisfied_by(&self, pkgname: &str, version: &str) -> bool {
+        if self.name() != pkgname {
+            return false;
+        }
+
+        match self.version_constraint() {
+            Ok(None) => true,
+            Ok(Some(constraint)) => constraint.is_satisfied_by_str(version),
+            Err(_) => false,
+        }
+    }
+
+    /// Check whether a `provides` entry satisfies this dependency.
+    ///
+    /// Just like pacman, an unversioned provision only satisfies an unversioned dependency.
+    pub fn is_satisfied_by_provision(
+        &self,
+        provision: &UnreasonedDependency<impl AsRef<str>, impl AsRef<str>>,
+    ) -> bool {
+        if self.range().is_empty() {
+            return self.name() == provision.name();
+        }
+
+        match provision.range().strip_prefix('=') {
+            Some(provided_version) => self.is_satisfied_by(provision.name(), provided_version),
+            None => false,
+        }
+    }
 }
 
 impl<'a> UnreasonedDependency<&'a str, &'a str> {
@@ -49,3 +95,34 @@ impl<'a> UnreasonedDependency<&'a str, &'a str> {
         UnreasonedDependency { name, range }
     }
 }
+
+#[test]
+fn test_is_satisfied_by() {
+    let dependency = |text| UnreasonedDependency::new(text);
+    let actual = [
+        dependency("glibc").is_satisfied_by("glibc", "2.38-1"),
+        dependency("glibc>=2.38").is_satisfied_by("glibc", "2.38-1"),
+        dependency("glibc>=2.39").is_satisfied_by("glibc", "2.38-1"),
+        dependency("glibc>=2.38").is_satisfied_by("musl", "2.38-1"),
+        dependency("glibc~2.38").is_satisfied_by("glibc", "2.38-1"),
+    ];
+    let expected = [true, true, false, false, false];
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_is_satisfied_by_provision() {
+    let dependency = |text| UnreasonedDependency::new(text);
+    let actual = [
+        dependency("java-runtime").is_satisfied_by_provision(&dependency("java-runtime=17")),
+        dependency("java-runtime").is_satisfied_by_provision(&dependency("java-runtime")),
+        dependency("java-runtime>=11").is_satisfied_by_provision(&dependency("java-runtime=17")),
+        dependency("java-runtime>=21").is_satisfied_by_provision(&dependency("java-runtime=17")),
+        dependency("java-runtime>=11").is_satisfied_by_provision(&dependency("java-runtime")),
+        dependency("java-runtime<17").is_satisfied_by_provision(&dependency("java-runtime=17")),
+        dependency("java-runtime=17").is_satisfied_by_provision(&dependency("java-runtime=17")),
+        dependency("libfoo.so").is_satisfied_by_provision(&dependency("libbar.so=1-64")),
+    ];
+    let expected = [true, true, true, false, false, false, true, false];
+    assert_eq!(actual, expected);
+}
@@ -0,0 +1,446 @@
+use super::{
+    super::utils::PackageFileName,
+    dependency::{DependencyField, ReasonedDependency, UnreasonedDependency},
+    version::Version,
+};
+use itertools::Itertools;
+use std::vec::IntoIter;
+
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct ParsedSrcInfo<'a> {
+    pub base: Option<Section<'a>>,
+    pub packages: Vec<Section<'a>>,
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Section<'a> {
+    pub name: &'a str,
+    pub fields: Vec<Field<'a>>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Field<'a> {
+    pub key: &'a str,
+    pub value: &'a str,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Package<'r, 'a> {
+    pub base: Option<&'r Section<'a>>,
+    pub section: &'r Section<'a>,
+}
+
+impl<'a> ParsedSrcInfo<'a> {
+    pub fn parse(text: &'a str) -> Self {
+        let mut result = ParsedSrcInfo::default();
+
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim();
+            let value = value.trim();
+
+            match key {
+                "pkgbase" => match &mut result.base {
+                    // NOTE: fields before pkgbase belong to it
+                    Some(section) if section.name.is_empty() => section.name = value,
+                    _ => result.base = Some(Section::new(value)),
+                },
+                "pkgname" => {
+                    result.packages.push(Section::new(value));
+                }
+                _ => {
+                    let field = Field { key, value };
+                    if let Some(section) = result.packages.last_mut() {
+                        section.fields.push(field);
+                    } else {
+                        // NOTE: fields before any section are kept in an unnamed pkgbase section
+                        result
+                            .base
+                            .get_or_insert_with(|| Section::new(""))
+                            .fields
+                            .push(field);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    pub fn pkgbase(&self) -> Option<&'a str> {
+        self.base
+            .as_ref()
+            .map(|section| section.name)
+            .filter(|name| !name.is_empty())
+    }
+
+    pub fn pkgname(&self) -> impl Iterator<Item = &'a str> {
+        self.packages
+            .iter()
+            .map(|section| section.name)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    pub fn arch(&self) -> impl Iterator<Item = &'a str> {
+        self.effective_values("arch").into_iter()
+    }
+
+    /// First non-empty value of `key` in the order of the file.
+    ///
+    /// This is the value of the pkgbase section if it has one,
+    /// but a .SRCINFO without a pkgbase section still works.
+    pub fn first_value(&self, key: &str) -> Option<&'a str> {
+        self.base
+            .iter()
+            .chain(&self.packages)
+            .find_map(|section| section.value(key))
+    }
+
+    pub fn version(&self) -> Result<Version<&'a str, &'a str, &'a str>, &'static str> {
+        let pkgver = self.first_value("pkgver").ok_or("missing pkgver")?;
+        let pkgrel = self.first_value("pkgrel").ok_or("missing pkgrel")?;
+        let epoch = self.first_value("epoch").unwrap_or("");
+        Ok(Version {
+            pkgver,
+            pkgrel,
+            epoch,
+        })
+    }
+
+    /// Dependencies of `key` including architecture-specific variants.
+    ///
+    /// If `arch` is `None`, variants of every architecture are included.
+    fn get_dependencies(
+        &self,
+        key: &str,
+        arch: Option<&str>,
+    ) -> impl Iterator<Item = UnreasonedDependency<&'a str, &'a str>> {
+        self.effective_values_for_arch(key, arch)
+            .into_iter()
+            .map(UnreasonedDependency::new)
+    }
+
+    /// Dependencies listed in `field` including architecture-specific variants.
+    ///
+    /// If `arch` is `None`, variants of every architecture are included.
+    pub fn dependencies_of(
+        &self,
+        field: DependencyField,
+        arch: Option<&str>,
+    ) -> impl Iterator<Item = UnreasonedDependency<&'a str, &'a str>> {
+        self.get_dependencies(field.key(), arch)
+    }
+
+    pub fn depends(&self) -> impl Iterator<Item = UnreasonedDependency<&'a str, &'a str>> {
+        self.get_dependencies("depends", None)
+    }
+
+    pub fn depends_for_arch(
+        &self,
+        arch: &str,
+    ) -> impl Iterator<Item = UnreasonedDependency<&'a str, &'a str>> {
+        self.get_dependencies("depends", Some(arch))
+    }
+
+    pub fn makedepends(&self) -> impl Iterator<Item = UnreasonedDependency<&'a str, &'a str>> {
+        self.get_dependencies("makedepends", None)
+    }
+
+    pub fn makedepends_for_arch(
+        &self,
+        arch: &str,
+    ) -> impl Iterator<Item = UnreasonedDependency<&'a str, &'a str>> {
+        self.get_dependencies("makedepends", Some(arch))
+    }
+
+    pub fn checkdepends(&self) -> impl Iterator<Item = UnreasonedDependency<&'a str, &'a str>> {
+        self.get_dependencies("checkdepends", None)
+    }
+
+    pub fn checkdepends_for_arch(
+        &self,
+        arch: &str,
+    ) -> impl Iterator<Item = UnreasonedDependency<&'a str, &'a str>> {
+        self.get_dependencies("checkdepends", Some(arch))
+    }
+
+    pub fn optdepends(
+        &self,
+    ) -> impl Iterator<Item = ReasonedDependency<&'a str, &'a str, &'a str>> {
+        self.effective_values_for_arch("optdepends", None)
+            .into_iter()
+            .map(ReasonedDependency::new)
+    }
+
+    pub fn conflicts(&self) -> impl Iterator<Item = UnreasonedDependency<&'a str, &'a str>> {
+        self.get_dependencies("conflicts", None)
+    }
+
+    pub fn conflicts_for_arch(
+        &self,
+        arch: &str,
+    ) -> impl Iterator<Item = UnreasonedDependency<&'a str, &'a str>> {
+        self.get_dependencies("conflicts", Some(arch))
+    }
+
+    pub fn provides(&self) -> impl Iterator<Item = UnreasonedDependency<&'a str, &'a str>> {
+        self.get_dependencies("provides", None)
+    }
+
+    pub fn provides_for_arch(
+        &self,
+        arch: &str,
+    ) -> impl Iterator<Item = UnreasonedDependency<&'a str, &'a str>> {
+        self.get_dependencies("provides", Some(arch))
+    }
+
+    pub fn all_required_dependencies(
+        &self,
+    ) -> impl Iterator<Item = UnreasonedDependency<&'a str, &'a str>> {
+        let depends = self.depends();
+        let makedepends = self.makedepends();
+        let checkdepends = self.checkdepends();
+        depends.chain(makedepends).chain(checkdepends)
+    }
+
+    pub fn all_required_dependencies_for_arch(
+        &self,
+        arch: &str,
+    ) -> impl Iterator<Item = UnreasonedDependency<&'a str, &'a str>> {
+        let depends = self.depends_for_arch(arch);
+        let makedepends = self.makedepends_for_arch(arch);
+        let checkdepends = self.checkdepends_for_arch(arch);
+        depends.chain(makedepends).chain(checkdepends)
+    }
+
+    pub fn package_file_base_names(
+        &self,
+        filter_arch: impl Fn(&&'a str) -> bool,
+    ) -> Result<IntoIter<PackageFileName<&'a str, String, &'a str>>, String> {
+        let version = self.version().map_err(String::from)?.try_to_string()?;
+
+        let mut result = Vec::new();
+        for package in self.packages() {
+            for arch in package.arch().into_iter().filter(&filter_arch) {
+                result.push(PackageFileName {
+                    pkgname: package.name(),
+                    arch,
+                    version: version.clone(),
+                });
+            }
+        }
+
+        Ok(result.into_iter())
+    }
+
+    pub fn packages(&self) -> impl Iterator<Item = Package<'_, 'a>> {
+        self.packages.iter().map(move |section| Package {
+            base: self.base.as_ref(),
+            section,
+        })
+    }
+
+    pub fn package(&self, pkgname: &str) -> Option<Package<'_, 'a>> {
+        self.packages().find(|package| package.name() == pkgname)
+    }
+
+    /// Values of `key` that belong to the pkgbase section only.
+    pub fn base_values(&self, key: &str) -> Vec<&'a str> {
+        self.base
+            .as_ref()
+            .map(|section| section.values(key))
+            .unwrap_or_default()
+    }
+
+    /// Union of effective values of `key` over every package,
+    /// falls back to the pkgbase section when there is no package.
+    pub fn effective_values(&self, key: &str) -> Vec<&'a str> {
+        if self.packages.is_empty() {
+            return self.base_values(key);
+        }
+
+        self.packages()
+            .flat_map(|package| package.values(key))
+            .unique()
+            .collect()
+    }
+
+    /// Like [`effective_values`](Self::effective_values) but also includes architecture-specific
+    /// variants of `key` (e.g. `depends_x86_64`).
+    ///
+    /// If `arch` is `None`, variants of every architecture are included.
+    /// Otherwise, only variants of `arch` are included and packages that are not built for `arch` are skipped.
+    pub fn effective_values_for_arch(&self, key: &str, arch: Option<&str>) -> Vec<&'a str> {
+        if self.packages.is_empty() {
+            return self
+                .base
+                .as_ref()
+                .map(|section| section.values_for_arch(key, arch))
+                .unwrap_or_default();
+        }
+
+        self.packages()
+            .filter(|package| match arch {
+                Some(arch) => package.is_built_for(arch),
+                None => true,
+            })
+            .flat_map(|package| package.values_for_arch(key, arch))
+            .unique()
+            .collect()
+    }
+}
+
+impl<'a> Section<'a> {
+    pub fn new(name: &'a str) -> Self {
+        Section {
+            name,
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.fields.iter().any(|field| field.key == key)
+    }
+
+    pub fn value(&self, key: &str) -> Option<&'a str> {
+        self.fields
+            .iter()
+            .find(|field| field.key == key)
+            .map(|field| field.value)
+            .filter(|value| !value.is_empty())
+    }
+
+    pub fn values(&self, key: &str) -> Vec<&'a str> {
+        self.fields
+            .iter()
+            .filter(|field| field.key == key)
+            .map(|field| field.value)
+            .filter(|value| !value.is_empty())
+            .collect()
+    }
+
+    /// Keys of architecture-specific variants of `key` (e.g. `depends_x86_64` of `depends`).
+    pub fn arch_specific_keys(&self, key: &str) -> Vec<&'a str> {
+        self.fields
+            .iter()
+            .map(|field| field.key)
+            .filter(|field_key| arch_of_key(key, field_key).is_some())
+            .unique()
+            .collect()
+    }
+
+    pub fn values_for_arch(&self, key: &str, arch: Option<&str>) -> Vec<&'a str> {
+        let mut result = self.values(key);
+        match arch {
+            Some(arch) => result.extend(self.values(&arch_specific_key(key, arch))),
+            None => {
+                for variant in self.arch_specific_keys(key) {
+                    result.extend(self.values(variant));
+                }
+            }
+        }
+        result
+    }
+}
+
+impl<'r, 'a> Package<'r, 'a> {
+    pub fn name(&self) -> &'a str {
+        self.section.name
+    }
+
+    /// Section that `key` should be read from.
+    ///
+    /// A key that appears in the pkgname section overrides the whole
+    /// array of the pkgbase section, even when its value is empty.
+    fn source(&self, key: &str) -> Option<&'r Section<'a>> {
+        if self.section.contains(key) {
+            Some(self.section)
+        } else {
+            self.base
+        }
+    }
+
+    pub fn value(&self, key: &str) -> Option<&'a str> {
+        self.source(key).and_then(|section| section.value(key))
+    }
+
+    pub fn values(&self, key: &str) -> Vec<&'a str> {
+        self.source(key)
+            .map(|section| section.values(key))
+            .unwrap_or_default()
+    }
+
+    /// Keys of architecture-specific variants of `key` from both the pkgname section and the pkgbase section.
+    pub fn arch_specific_keys(&self, key: &str) -> Vec<&'a str> {
+        let own = self.section.arch_specific_keys(key);
+        let inherited = self
+            .base
+            .map(|section| section.arch_specific_keys(key))
+            .unwrap_or_default();
+        own.into_iter().chain(inherited).unique().collect()
+    }
+
+    /// Effective values of `key` and its architecture-specific variants.
+    ///
+    /// Each variant is overridden independently, just like makepkg does.
+    pub fn values_for_arch(&self, key: &str, arch: Option<&str>) -> Vec<&'a str> {
+        let mut result = self.values(key);
+        match arch {
+            Some(arch) => result.extend(self.values(&arch_specific_key(key, arch))),
+            None => {
+                for variant in self.arch_specific_keys(key) {
+                    result.extend(self.values(variant));
+                }
+            }
+        }
+        result
+    }
+
+    pub fn arch(&self) -> Vec<&'a str> {
+        self.values("arch")
+    }
+
+    /// Whether the package would be produced when building for `arch`.
+    ///
+    /// Packages that do not declare any architecture are assumed to be built for all of them.
+    pub fn is_built_for(&self, arch: &str) -> bool {
+        let arch_list = self.arch();
+        arch_list.is_empty() || arch_list.iter().any(|x| *x == arch || *x == "any")
+    }
+
+    pub fn depends(&self) -> Vec<&'a str> {
+        self.values("depends")
+    }
+
+    pub fn optdepends(&self) -> Vec<&'a str> {
+        self.values("optdepends")
+    }
+
+    pub fn conflicts(&self) -> Vec<&'a str> {
+        self.values("conflicts")
+    }
+}
+
+/// Name of the architecture-specific variant of `key`.
+pub fn arch_specific_key(key: &str, arch: &str) -> String {
+    format!("{}_{}", key, arch)
+}
+
+/// Extract the architecture from an architecture-specific variant of `key`.
+pub fn arch_of_key<'a>(key: &str, variant: &'a str) -> Option<&'a str> {
+    variant
+        .strip_prefix(key)?
+        .strip_prefix('_')
+        .filter(|arch| !arch.is_empty())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,188 @@
+use super::super::{dependency::UnreasonedDependency, SrcInfo};
+use super::{Field, ParsedSrcInfo, Section};
+
+const SPLIT_PACKAGE: &str = r#"
+# Generated by makepkg
+pkgbase = split
+	pkgver = 1.2.3
+	pkgrel = 1
+	arch = x86_64
+	arch = i686
+	makedepends = cmake
+	depends = glibc
+	conflicts = split-git
+
+pkgname = split-lib
+
+pkgname = split-bin
+	depends = split-lib
+	depends = zlib
+
+pkgname = split-doc
+	arch = any
+	depends =
+"#;
+
+#[test]
+fn parse_sections() {
+    let actual =
+        ParsedSrcInfo::parse("pkgbase = foo\n\tpkgver = 1\n\npkgname = foo\n\tarch = any\n");
+    let expected = ParsedSrcInfo {
+        base: Some(Section {
+            name: "foo",
+            fields: vec![Field {
+                key: "pkgver",
+                value: "1",
+            }],
+        }),
+        packages: vec![Section {
+            name: "foo",
+            fields: vec![Field {
+                key: "arch",
+                value: "any",
+            }],
+        }],
+    };
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn package_inheritance() {
+    let parsed = ParsedSrcInfo::parse(SPLIT_PACKAGE);
+    let actual: Vec<_> = parsed
+        .packages()
+        .map(|package| (package.name(), package.arch(), package.depends()))
+        .collect();
+    let expected = vec![
+        ("split-lib", vec!["x86_64", "i686"], vec!["glibc"]),
+        (
+            "split-bin",
+            vec!["x86_64", "i686"],
+            vec!["split-lib", "zlib"],
+        ),
+        ("split-doc", vec!["any"], vec![]),
+    ];
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn srcinfo_effective_values() {
+    let srcinfo = SrcInfo(SPLIT_PACKAGE);
+    let actual = (
+        srcinfo.pkgbase(),
+        srcinfo.pkgname().collect::<Vec<_>>(),
+        srcinfo.arch().collect::<Vec<_>>(),
+        srcinfo
+            .all_required_dependencies()
+            .map(|dependency| dependency.name)
+            .collect::<Vec<_>>(),
+        srcinfo
+            .conflicts()
+            .map(|dependency| dependency.name)
+            .collect::<Vec<_>>(),
+    );
+    let expected = (
+        Some("split"),
+        vec!["split-lib", "split-bin", "split-doc"],
+        vec!["x86_64", "i686", "any"],
+        vec!["glibc", "split-lib", "zlib", "cmake"],
+        vec!["split-git"],
+    );
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn srcinfo_package_file_base_names() {
+    let actual: Vec<_> = SrcInfo(SPLIT_PACKAGE)
+        .package_file_base_names(|arch| *arch != "i686")
+        .unwrap()
+        .map(|name| name.file_name(".pkg.tar.zst"))
+        .collect();
+    let expected = [
+        "split-lib-1.2.3-1-x86_64.pkg.tar.zst",
+        "split-bin-1.2.3-1-x86_64.pkg.tar.zst",
+        "split-doc-1.2.3-1-any.pkg.tar.zst",
+    ];
+    assert_eq!(actual, expected);
+}
+
+const ARCH_SPECIFIC: &str = r#"
+pkgbase = multiarch
+	pkgver = 1.0.0
+	pkgrel = 1
+	arch = x86_64
+	arch = aarch64
+	makedepends = cmake
+	makedepends_aarch64 = cross-toolchain
+	depends = glibc
+	depends_x86_64 = lib32-glibc
+	conflicts_aarch64 = multiarch-bin
+
+pkgname = multiarch
+
+pkgname = multiarch-extra
+	arch = x86_64
+	depends_x86_64 = multiarch
+"#;
+
+#[test]
+fn arch_specific_dependencies() {
+    fn names<'a>(
+        dependencies: impl Iterator<Item = UnreasonedDependency<&'a str, &'a str>>,
+    ) -> Vec<&'a str> {
+        dependencies.map(|dependency| dependency.name).collect()
+    }
+
+    let srcinfo = SrcInfo(ARCH_SPECIFIC);
+    let actual = (
+        names(srcinfo.all_required_dependencies()),
+        names(srcinfo.all_required_dependencies_for_arch("x86_64")),
+        names(srcinfo.all_required_dependencies_for_arch("aarch64")),
+        names(srcinfo.conflicts_for_arch("x86_64")),
+        names(srcinfo.conflicts_for_arch("aarch64")),
+    );
+    let expected = (
+        vec![
+            "glibc",
+            "lib32-glibc",
+            "multiarch",
+            "cmake",
+            "cross-toolchain",
+        ],
+        vec!["glibc", "lib32-glibc", "multiarch", "cmake"],
+        vec!["glibc", "cmake", "cross-toolchain"],
+        vec![],
+        vec!["multiarch-bin"],
+    );
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn arch_of_key() {
+    use super::arch_of_key;
+    let actual = [
+        arch_of_key("depends", "depends_x86_64"),
+        arch_of_key("depends", "depends"),
+        arch_of_key("depends", "depends_"),
+        arch_of_key("depends", "makedepends_x86_64"),
+    ];
+    let expected = [Some("x86_64"), None, None, None];
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn version_without_pkgbase() {
+    let with_pkgbase = SrcInfo("pkgbase = foo\n\tpkgver = 1\n\tpkgrel = 2\n\npkgname = foo\n");
+    let leading_fields = SrcInfo("pkgver = 1\npkgrel = 2\nepoch = 3\npkgname = foo\n");
+    let package_fields = SrcInfo("pkgname = foo\n\tpkgver = 1\n\tpkgrel = 2\n");
+    let version = |srcinfo: SrcInfo<&str>| srcinfo.version().unwrap().try_to_string().unwrap();
+    assert_eq!(version(with_pkgbase), "1-2");
+    assert_eq!(version(leading_fields), "3:1-2");
+    assert_eq!(version(package_fields), "1-2");
+    assert_eq!(leading_fields.pkgbase(), None);
+    assert_eq!(package_fields.pkgbase(), None);
+    assert_eq!(
+        SrcInfo("pkgname = foo\n").version().unwrap_err(),
+        "missing pkgver"
+    );
+}
@@ -1,3 +1,7 @@
+mod vercmp;
+
+pub use vercmp::vercmp;
+
 use num_bigint::BigUint;
 use num_traits::Zero;
 use std::{cmp::Ordering, fmt::Write};
@@ -85,7 +89,7 @@ where
 {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         match (self.try_to_string(), other.try_to_string()) {
-            (Ok(left), Ok(right)) => Some(alpm::vercmp(left, right)),
+            (Ok(left), Ok(right)) => Some(vercmp(left, right)),
             _ => None,
         }
     }
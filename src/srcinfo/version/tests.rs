@@ -1,4 +1,5 @@
-use super::Version;
+use super::{vercmp, Version};
+use std::cmp::Ordering;
 
 #[test]
 fn try_to_string_success() {
@@ -46,3 +47,95 @@ fn partial_cmp() {
 
     assert_eq!(actual, expected);
 }
+
+/// Cases and expected results of `vercmptest.sh` from the test suite of pacman.
+const VERCMP_CASES: &[(&str, &str, Ordering)] = &[
+    // all similar length, no pkgrel
+    ("1.5.0", "1.5.0", Ordering::Equal),
+    ("1.5.1", "1.5.0", Ordering::Greater),
+    // mixed length
+    ("1.5.1", "1.5", Ordering::Greater),
+    // with pkgrel, simple
+    ("1.5.0-1", "1.5.0-1", Ordering::Equal),
+    ("1.5.0-1", "1.5.0-2", Ordering::Less),
+    ("1.5.0-1", "1.5.1-1", Ordering::Less),
+    ("1.5.0-2", "1.5.1-1", Ordering::Less),
+    // with pkgrel, mixed lengths
+    ("1.5-1", "1.5.1-1", Ordering::Less),
+    ("1.5-2", "1.5.1-1", Ordering::Less),
+    ("1.5-2", "1.5.1-2", Ordering::Less),
+    // mixed pkgrel inclusion
+    ("1.5", "1.5-1", Ordering::Equal),
+    ("1.5-1", "1.5", Ordering::Equal),
+    ("1.1-1", "1.1", Ordering::Equal),
+    ("1.0-1", "1.1", Ordering::Less),
+    ("1.1-1", "1.0", Ordering::Greater),
+    // alphanumeric versions
+    ("1.5b-1", "1.5-1", Ordering::Less),
+    ("1.5b", "1.5", Ordering::Less),
+    ("1.5b-1", "1.5", Ordering::Less),
+    ("1.5b", "1.5.1", Ordering::Less),
+    // from the manpage
+    ("1.0a", "1.0alpha", Ordering::Less),
+    ("1.0alpha", "1.0b", Ordering::Less),
+    ("1.0b", "1.0beta", Ordering::Less),
+    ("1.0beta", "1.0rc", Ordering::Less),
+    ("1.0rc", "1.0", Ordering::Less),
+    // alpha-dotted versions
+    ("1.5.a", "1.5", Ordering::Greater),
+    ("1.5.b", "1.5.a", Ordering::Greater),
+    ("1.5.1", "1.5.b", Ordering::Greater),
+    // alpha dots and dashes
+    ("1.5.b-1", "1.5.b", Ordering::Equal),
+    ("1.5-1", "1.5.b", Ordering::Less),
+    // same/similar content, differing separators
+    ("2.0", "2_0", Ordering::Equal),
+    ("2.0_a", "2_0.a", Ordering::Equal),
+    ("2.0a", "2.0.a", Ordering::Less),
+    ("2___a", "2_a", Ordering::Greater),
+    // epoch included version comparisons
+    ("0:1.0", "0:1.0", Ordering::Equal),
+    ("0:1.0", "0:1.1", Ordering::Less),
+    ("1:1.0", "0:1.0", Ordering::Greater),
+    ("1:1.0", "0:1.1", Ordering::Greater),
+    ("1:1.0", "2:1.1", Ordering::Less),
+    // epoch + sometimes present pkgrel
+    ("1:1.0", "0:1.0-1", Ordering::Greater),
+    ("1:1.0-1", "0:1.1-1", Ordering::Greater),
+    // epoch included on one version
+    ("0:1.0", "1.0", Ordering::Equal),
+    ("0:1.0", "1.1", Ordering::Less),
+    ("0:1.1", "1.0", Ordering::Greater),
+    ("1:1.0", "1.0", Ordering::Greater),
+    ("1:1.0", "1.1", Ordering::Greater),
+    ("1:1.1", "1.1", Ordering::Greater),
+];
+
+#[test]
+fn vercmp_table() {
+    for (left, right, expected) in VERCMP_CASES.iter().copied() {
+        assert_eq!(vercmp(left, right), expected, "vercmp({left:?}, {right:?})");
+        assert_eq!(
+            vercmp(right, left),
+            expected.reverse(),
+            "vercmp({right:?}, {left:?})",
+        );
+    }
+}
+
+#[cfg(feature = "alpm")]
+#[test]
+fn vercmp_matches_alpm() {
+    for (left, right, _) in VERCMP_CASES.iter().copied() {
+        assert_eq!(
+            vercmp(left, right),
+            alpm::vercmp(left, right),
+            "vercmp({left:?}, {right:?})",
+        );
+        assert_eq!(
+            vercmp(right, left),
+            alpm::vercmp(right, left),
+            "vercmp({right:?}, {left:?})",
+        );
+    }
+}
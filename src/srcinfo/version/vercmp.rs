@@ -0,0 +1,119 @@
+use std::cmp::Ordering;
+
+/// Compare two version strings of the form `[epoch:]pkgver[-pkgrel]`.
+///
+/// This is a port of `alpm_pkg_vercmp` from pacman, so it does not require libalpm.
+/// The pkgrel is only compared when both sides have one, and a missing epoch is treated as `0`.
+pub fn vercmp(left: impl AsRef<str>, right: impl AsRef<str>) -> Ordering {
+    let (left, right) = (left.as_ref(), right.as_ref());
+    if left == right {
+        return Ordering::Equal;
+    }
+
+    let (left_epoch, left_pkgver, left_pkgrel) = parse_evr(left.as_bytes());
+    let (right_epoch, right_pkgver, right_pkgrel) = parse_evr(right.as_bytes());
+
+    rpmvercmp(left_epoch, right_epoch)
+        .then_with(|| rpmvercmp(left_pkgver, right_pkgver))
+        .then_with(|| match (left_pkgrel, right_pkgrel) {
+            (Some(left), Some(right)) => rpmvercmp(left, right),
+            _ => Ordering::Equal,
+        })
+}
+
+/// Split a version string into epoch, pkgver, and pkgrel.
+fn parse_evr(evr: &[u8]) -> (&[u8], &[u8], Option<&[u8]>) {
+    let digits = evr.iter().take_while(|x| x.is_ascii_digit()).count();
+
+    let (epoch, rest) = match evr[digits..].first() {
+        Some(b':') if digits == 0 => (&b"0"[..], &evr[1..]),
+        Some(b':') => (&evr[..digits], &evr[digits + 1..]),
+        _ => (&b"0"[..], evr),
+    };
+
+    match rest.iter().rposition(|x| *x == b'-') {
+        Some(index) => (epoch, &rest[..index], Some(&rest[index + 1..])),
+        None => (epoch, rest, None),
+    }
+}
+
+/// Compare alternating alphabetic and numeric segments just like rpm does.
+fn rpmvercmp(left: &[u8], right: &[u8]) -> Ordering {
+    if left == right {
+        return Ordering::Equal;
+    }
+
+    let (mut one, mut two) = (left, right);
+
+    while !one.is_empty() && !two.is_empty() {
+        let one_separator = one
+            .iter()
+            .take_while(|x| !x.is_ascii_alphanumeric())
+            .count();
+        let two_separator = two
+            .iter()
+            .take_while(|x| !x.is_ascii_alphanumeric())
+            .count();
+        one = &one[one_separator..];
+        two = &two[two_separator..];
+
+        if one.is_empty() || two.is_empty() {
+            break;
+        }
+
+        if one_separator != two_separator {
+            return one_separator.cmp(&two_separator);
+        }
+
+        let is_num = one[0].is_ascii_digit();
+        let segment_len = |text: &[u8]| {
+            text.iter()
+                .take_while(|x| match is_num {
+                    true => x.is_ascii_digit(),
+                    false => x.is_ascii_alphabetic(),
+                })
+                .count()
+        };
+        let one_len = segment_len(one);
+        let two_len = segment_len(two);
+
+        // NOTE: segments of different types, numeric ones are considered newer
+        if two_len == 0 {
+            return if is_num {
+                Ordering::Greater
+            } else {
+                Ordering::Less
+            };
+        }
+
+        let (one_segment, two_segment) = (&one[..one_len], &two[..two_len]);
+        let ordering = if is_num {
+            let one_segment = trim_leading_zeros(one_segment);
+            let two_segment = trim_leading_zeros(two_segment);
+            one_segment
+                .len()
+                .cmp(&two_segment.len())
+                .then_with(|| one_segment.cmp(two_segment))
+        } else {
+            one_segment.cmp(two_segment)
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+
+        one = &one[one_len..];
+        two = &two[two_len..];
+    }
+
+    match (one.first(), two.first()) {
+        (None, None) => Ordering::Equal,
+        (None, Some(x)) if !x.is_ascii_alphabetic() => Ordering::Less,
+        (Some(x), _) if x.is_ascii_alphabetic() => Ordering::Less,
+        _ => Ordering::Greater,
+    }
+}
+
+fn trim_leading_zeros(text: &[u8]) -> &[u8] {
+    let zeros = text.iter().take_while(|x| **x == b'0').count();
+    &text[zeros..]
+}
@@ -11,6 +11,7 @@ pub enum Code {
     UnrecognizedMakepkg = 5,
     FailedBuildRecordLoadingFailure = 6,
     FailedBuildRecordWritingFailure = 7,
+    RepositoryInconsistency = 8,
 }
 
 impl From<Code> for Status {
@@ -1,4 +1,7 @@
+#[cfg(feature = "alpm")]
 mod alpm_wrapper;
+mod build_log;
+#[cfg(feature = "alpm")]
 mod clone_aur;
 mod command_utils;
 mod copyright_terms;
@@ -6,23 +9,34 @@ mod create_makepkg_command;
 mod custom_makepkg;
 mod db_init;
 mod deserialize_multi_docs_yaml;
+#[cfg(feature = "alpm")]
 mod external_package_list;
 mod extract_pkgname_prefix;
 mod extract_value_from_line;
+mod glob_directories;
 mod load_failed_build_record;
+#[cfg(feature = "alpm")]
 mod orphan_packages;
 mod outdated_packages;
 mod package_file_name;
 mod pair;
+mod prune_repository;
+mod read_dir_or_current;
 mod read_srcinfo_file;
 mod read_srcinfo_from_directory;
 mod read_srcinfo_from_pkgbuild;
 mod read_srcinfo_texts;
 mod run_deref_db;
+mod run_in_dependency_order;
 mod serialize_iter_yaml;
+mod signing_key_args;
 mod split_str_once;
+mod superseded_packages;
 
+#[cfg(feature = "alpm")]
 pub use alpm_wrapper::AlpmWrapper;
+pub use build_log::{log_file_path, tail_lines, BuildLog};
+#[cfg(feature = "alpm")]
 pub use clone_aur::{CloneAur, CloneAurEffect};
 pub use command_utils::CommandUtils;
 pub use copyright_terms::COPYING;
@@ -30,18 +44,26 @@ pub use create_makepkg_command::create_makepkg_command;
 pub use custom_makepkg::{MakepkgPatch, MAKEPKG_PATCHES};
 pub use db_init::{DbInit, DbInitValue};
 pub use deserialize_multi_docs_yaml::deserialize_multi_docs_yaml;
+#[cfg(feature = "alpm")]
 pub use external_package_list::{ExternalPackageList, InstallationPlan, InstallationTarget};
 pub use extract_pkgname_prefix::extract_pkgname_prefix;
 pub use extract_value_from_line::extract_value_from_line;
-pub use load_failed_build_record::load_failed_build_record;
+pub use glob_directories::{glob_directories, wildcard_match};
+pub use load_failed_build_record::{load_failed_build_record, FailedBuildRecordEntry};
+#[cfg(feature = "alpm")]
 pub use orphan_packages::orphan_packages;
-pub use outdated_packages::outdated_packages;
-pub use package_file_name::PackageFileName;
+pub use outdated_packages::{outdated_packages, OutdatedPackage, OutdatedStatus};
+pub use package_file_name::{is_package_extension, PackageFileName, DEFAULT_PACKAGE_EXTENSION};
 pub use pair::Pair;
+pub use prune_repository::prune_repository;
+pub use read_dir_or_current::read_dir_or_current;
 pub use read_srcinfo_file::read_srcinfo_file;
 pub use read_srcinfo_from_directory::read_srcinfo_from_directory;
 pub use read_srcinfo_from_pkgbuild::read_srcinfo_from_pkgbuild;
 pub use read_srcinfo_texts::read_srcinfo_texts;
 pub use run_deref_db::run_deref_db;
+pub use run_in_dependency_order::run_in_dependency_order;
 pub use serialize_iter_yaml::serialize_iter_yaml;
+pub use signing_key_args::signing_key_args;
 pub use split_str_once::split_str_once;
+pub use superseded_packages::superseded_packages;
@@ -1,4 +1,5 @@
-use alpm::{Alpm, Db, Package, SigLevel};
+use super::super::srcinfo::dependency::UnreasonedDependency;
+use alpm::{Alpm, Db, Dep, DepModVer, Package, Pkg, SigLevel};
 use pipe_trait::Pipe;
 use std::iter::once;
 
@@ -33,6 +34,29 @@ impl AlpmWrapper {
         does_db_list_provide(self.alpm.syncdbs(), pkgname)
     }
 
+    pub fn is_provided_satisfying(
+        &self,
+        dependency: &UnreasonedDependency<impl AsRef<str>, impl AsRef<str>>,
+    ) -> bool {
+        self.is_installed_satisfying(dependency) || self.is_available_satisfying(dependency)
+    }
+
+    pub fn is_installed_satisfying(
+        &self,
+        dependency: &UnreasonedDependency<impl AsRef<str>, impl AsRef<str>>,
+    ) -> bool {
+        self.installed_packages()
+            .any(|pkg| does_package_satisfy(pkg, dependency))
+    }
+
+    pub fn is_available_satisfying(
+        &self,
+        dependency: &UnreasonedDependency<impl AsRef<str>, impl AsRef<str>>,
+    ) -> bool {
+        self.available_packages()
+            .any(|pkg| does_package_satisfy(pkg, dependency))
+    }
+
     pub fn installed_packages(&self) -> impl Iterator<Item = &'_ Package> {
         self.alpm.localdb().pkgs().into_iter()
     }
@@ -55,3 +79,31 @@ fn does_db_list_provide<'a>(db_list: impl IntoIterator<Item = &'a Db>, pkgname:
         .flat_map(|(names, provides)| names.chain(provides))
         .any(|name| name == pkgname)
 }
+
+/// Check whether `pkg` satisfies `dependency` either by its own name and version or by one of its `provides`.
+pub fn does_package_satisfy(
+    pkg: &Pkg,
+    dependency: &UnreasonedDependency<impl AsRef<str>, impl AsRef<str>>,
+) -> bool {
+    dependency.is_satisfied_by(pkg.name(), pkg.version().as_str())
+        || pkg
+            .provides()
+            .into_iter()
+            .any(|provision| dependency.is_satisfied_by_provision(&dependency_of(provision)))
+}
+
+/// Convert a dependency of alpm into a dependency of this program.
+pub fn dependency_of(dep: &Dep) -> UnreasonedDependency<&str, String> {
+    let range = match dep.depmodver() {
+        DepModVer::Any => String::new(),
+        DepModVer::Eq(version) => format!("={}", version.as_str()),
+        DepModVer::Ge(version) => format!(">={}", version.as_str()),
+        DepModVer::Le(version) => format!("<={}", version.as_str()),
+        DepModVer::Gt(version) => format!(">{}", version.as_str()),
+        DepModVer::Lt(version) => format!("<{}", version.as_str()),
+    };
+    UnreasonedDependency {
+        name: dep.name(),
+        range,
+    }
+}
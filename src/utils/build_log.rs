@@ -0,0 +1,227 @@
+use std::{
+    fmt::Arguments,
+    fs::{read, File},
+    io::{self, stderr, stdout, Read, Write},
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus, Stdio},
+    sync::mpsc,
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Destination of the messages and the command output of a single package build.
+#[derive(Debug)]
+pub struct BuildLog {
+    destination: Destination,
+    /// File that receives a copy of everything while an architecture is being built.
+    file: Option<(PathBuf, File)>,
+    /// Files that have been written so far.
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug)]
+enum Destination {
+    /// Write straight to the terminal, used when packages are built one at a time.
+    Inherit,
+    /// Collect everything so that builds running in parallel do not interleave.
+    Buffer(Vec<u8>),
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Stream {
+    Stdout,
+    Stderr,
+}
+
+impl BuildLog {
+    pub fn new(buffered: bool) -> Self {
+        let destination = if buffered {
+            Destination::Buffer(Vec::new())
+        } else {
+            Destination::Inherit
+        };
+        BuildLog {
+            destination,
+            file: None,
+            files: Vec::new(),
+        }
+    }
+
+    /// Files that have been written so far, in the order they were opened.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Start copying everything into a new file at `path`.
+    ///
+    /// The previous file, if any, is closed.
+    pub fn open_file(&mut self, path: PathBuf) -> io::Result<()> {
+        self.close_file();
+        let file = File::create(&path)?;
+        self.files.push(path.clone());
+        self.file = Some((path, file));
+        Ok(())
+    }
+
+    /// Stop copying into the current file and return its path.
+    pub fn close_file(&mut self) -> Option<PathBuf> {
+        self.file.take().map(|(path, _)| path)
+    }
+
+    pub fn message(&mut self, arguments: Arguments<'_>) {
+        let line = format!("{}\n", arguments);
+        self.write(Stream::Stderr, line.as_bytes());
+    }
+
+    fn write(&mut self, stream: Stream, chunk: &[u8]) {
+        match &mut self.destination {
+            Destination::Inherit => match stream {
+                Stream::Stdout => stdout().write_all(chunk).ok(),
+                Stream::Stderr => stderr().write_all(chunk).ok(),
+            },
+            Destination::Buffer(buffer) => {
+                buffer.extend_from_slice(chunk);
+                Some(())
+            }
+        };
+
+        if let Some((path, file)) = &mut self.file {
+            if let Err(error) = file.write_all(chunk) {
+                eprintln!("⚠ Cannot write to {:?}: {}", path, error);
+                self.file = None;
+            }
+        }
+    }
+
+    /// Spawn `command` and wait for it, its stdout and stderr go to the log.
+    pub fn run(&mut self, command: &mut Command) -> io::Result<ExitStatus> {
+        if let (Destination::Inherit, None) = (&self.destination, &self.file) {
+            return command
+                .stdout(Stdio::inherit())
+                .stderr(Stdio::inherit())
+                .spawn()?
+                .wait();
+        }
+
+        let mut child = command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // NOTE: both streams are read at the same time so that neither pipe fills up
+        let (sender, receiver) = mpsc::channel();
+        let readers: Vec<_> = [
+            child
+                .stdout
+                .take()
+                .map(|x| (Stream::Stdout, Box::new(x) as Box<dyn Read + Send>)),
+            child
+                .stderr
+                .take()
+                .map(|x| (Stream::Stderr, Box::new(x) as Box<dyn Read + Send>)),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|(kind, mut stream)| {
+            let sender = sender.clone();
+            thread::spawn(move || -> io::Result<()> {
+                let mut chunk = [0; 4096];
+                loop {
+                    let size = stream.read(&mut chunk)?;
+                    if size == 0 {
+                        return Ok(());
+                    }
+                    if sender.send((kind, chunk[..size].to_vec())).is_err() {
+                        return Ok(());
+                    }
+                }
+            })
+        })
+        .collect();
+        drop(sender);
+
+        for (kind, chunk) in receiver {
+            self.write(kind, &chunk);
+        }
+
+        let status = child.wait();
+        for reader in readers {
+            reader.join().expect("join output reader")?;
+        }
+        status
+    }
+
+    /// Print the collected output, if any, to the terminal at once.
+    pub fn flush(&mut self) {
+        if let Destination::Buffer(buffer) = &mut self.destination {
+            let mut stderr = stderr().lock();
+            stderr.write_all(buffer).ok();
+            stderr.flush().ok();
+            buffer.clear();
+        }
+    }
+}
+
+/// Path of a new log file for `name` in `directory`, e.g. `foo-x86_64-20200101T000000Z.log`.
+pub fn log_file_path(directory: &Path, name: &str, time: SystemTime) -> PathBuf {
+    directory.join(format!("{}-{}.log", name, utc_timestamp(time)))
+}
+
+/// Format `time` as a compact ISO 8601 UTC timestamp.
+fn utc_timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let (days, seconds) = (seconds / 86400, seconds % 86400);
+    let (hour, minute, second) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    // NOTE: convert days since epoch to a civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let days = days + 719468;
+    let era = days / 146097;
+    let day_of_era = days % 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year, month, day, hour, minute, second,
+    )
+}
+
+/// Read the last `count` lines of `path`.
+pub fn tail_lines(path: &Path, count: usize) -> io::Result<Vec<String>> {
+    let content = read(path)?;
+    let content = String::from_utf8_lossy(&content);
+    let lines: Vec<_> = content.lines().collect();
+    let start = lines.len().saturating_sub(count);
+    Ok(lines[start..].iter().map(ToString::to_string).collect())
+}
+
+#[test]
+fn test_utc_timestamp() {
+    use std::time::Duration;
+    let at = |seconds| utc_timestamp(UNIX_EPOCH + Duration::from_secs(seconds));
+    assert_eq!(at(0), "19700101T000000Z");
+    assert_eq!(at(951782400), "20000229T000000Z");
+    assert_eq!(at(1609459199), "20201231T235959Z");
+    assert_eq!(at(1792326896), "20261018T123456Z");
+}
+
+#[test]
+fn test_tail_lines() {
+    let directory = tempfile::tempdir().expect("create temporary directory");
+    let path = directory.path().join("build.log");
+    std::fs::write(&path, "a\nb\nc\nd\n").expect("write log file");
+    assert_eq!(tail_lines(&path, 2).unwrap(), ["c", "d"]);
+    assert_eq!(tail_lines(&path, 10).unwrap(), ["a", "b", "c", "d"]);
+    assert_eq!(tail_lines(&path, 0).unwrap(), Vec::<String>::new());
+}
@@ -69,7 +69,20 @@ impl<'a> CloneAur<'a> {
                     .all_required_dependencies()
                     .filter(|x| !contains_str(package_names.iter(), x.name))
                     .filter(|x| !contains_str(installed_dependencies.iter(), x.name))
-                    .filter(|x| !alpm.is_provided(x.name))
+                    .filter(|x| {
+                        if alpm.is_provided_satisfying(x) {
+                            return false;
+                        }
+                        // NOTE: a version that is too old does not satisfy the dependency,
+                        //       so it is cloned like any other missing dependency
+                        if alpm.is_provided(x.name) {
+                            eprintln!(
+                                "⚠ {:?} requires {}{} but the installed or available version is too old, cloning it from the AUR",
+                                package_name, x.name, x.range,
+                            );
+                        }
+                        true
+                    })
                     .map(|x| x.name.to_string())
                     .collect();
 
@@ -1,7 +1,7 @@
 use command_extra::CommandExtra;
 use std::process::Command;
 
-pub fn create_makepkg_command() -> Command {
+pub fn create_makepkg_command(package_extension: &str) -> Command {
     Command::new("makepkg")
         .without_env("PACMAN")
         .without_env("MAKEPKG_CONF")
@@ -11,9 +11,9 @@ pub fn create_makepkg_command() -> Command {
         .without_env("PACKAGER")
         .without_env("SRCPKGDEST")
         .without_env("BUILDDIR")
-        .without_env("GNUPGHOME")
         .without_env("GPGKEY")
+        .without_env("GNUPGHOME")
         .without_env("SOURCE_DATE_EPOCH")
-        .with_env("PKGEXT", ".pkg.tar.zst")
+        .with_env("PKGEXT", package_extension)
         .with_env("SRCEXT", ".src.tar.gz")
 }
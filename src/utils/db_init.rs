@@ -1,6 +1,6 @@
 use super::super::{
-    manifest::{BuildPacmanRepo, OwnedBuildPacmanRepo, OwnedMember},
-    srcinfo::{database::SimpleDatabase, SrcInfo},
+    manifest::{BuildPacmanRepo, ManifestOptions, OwnedBuildPacmanRepo, OwnedMember},
+    srcinfo::{database::SimpleDatabase, parsed::ParsedSrcInfo},
     status::{Code, Failure},
 };
 use super::{read_srcinfo_texts, Pair};
@@ -10,20 +10,46 @@ use std::path::PathBuf;
 
 #[derive(Debug, Default)]
 pub struct DbInit<'a> {
+    manifest: ManifestOptions<'a>,
+    profile: Option<&'a str>,
+    target_arch: Option<String>,
     srcinfo_texts: Vec<Pair<String, OwnedMember>>,
-    srcinfo_collection: Vec<Pair<SrcInfo<&'a str>, &'a OwnedMember>>,
+    srcinfo_collection: Vec<Pair<ParsedSrcInfo<'a>, &'a OwnedMember>>,
 }
 
 impl<'a> DbInit<'a> {
+    /// Only consider architecture-specific dependencies of `target_arch`.
+    ///
+    /// If `target_arch` is `None`, dependencies of every architecture are considered.
+    pub fn with_target_arch(mut self, target_arch: Option<String>) -> Self {
+        self.target_arch = target_arch;
+        self
+    }
+
+    /// Load the manifest as `manifest` says.
+    pub fn with_manifest(mut self, manifest: ManifestOptions<'a>) -> Self {
+        self.manifest = manifest;
+        self
+    }
+
+    /// Apply the profile named `profile` of the manifest.
+    pub fn with_profile(mut self, profile: Option<&'a str>) -> Self {
+        self.profile = profile;
+        self
+    }
+
     pub fn init(&'a mut self) -> Result<DbInitValue<'a>, Failure> {
         let DbInit {
+            manifest,
+            profile,
+            target_arch,
             srcinfo_texts,
             srcinfo_collection,
         } = self;
 
         let mut error_count = 0;
 
-        let manifest = match BuildPacmanRepo::from_env() {
+        let manifest = match BuildPacmanRepo::from_env_with_profile(*manifest, *profile) {
             Ok(manifest) => manifest,
             Err(error) => {
                 eprintln!("{}", error);
@@ -38,13 +64,17 @@ impl<'a> DbInit<'a> {
 
         *srcinfo_collection = srcinfo_texts
             .iter()
-            .map(|x| x.to_ref().map(String::as_str).map(SrcInfo))
+            .map(|x| x.to_ref().map(String::as_str).map(ParsedSrcInfo::parse))
             .collect();
         let mut database = SimpleDatabase::default();
         let mut duplications: IndexMap<String, IndexSet<PathBuf>> = Default::default();
         for pair in srcinfo_collection {
             let (srcinfo, member) = pair.to_ref().into_tuple();
-            match database.insert_srcinfo(srcinfo, member.directory.as_ref()) {
+            match database.insert_srcinfo(
+                srcinfo,
+                member.directory.as_ref(),
+                target_arch.as_deref(),
+            ) {
                 Err(error) => {
                     eprintln!("⮾ Error in directory {:?}: {}", member.directory, error);
                     error_count += 1;
@@ -63,6 +93,10 @@ impl<'a> DbInit<'a> {
             }
         }
 
+        for unsatisfied in database.resolve_dependencies(target_arch.as_deref()) {
+            eprintln!("⚠ {}", unsatisfied);
+        }
+
         if !duplications.is_empty() {
             eprintln!("⮾ Duplication detected");
             for (pkgbase, directories) in duplications.iter() {
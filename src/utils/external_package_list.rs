@@ -1,5 +1,9 @@
-use super::{orphan_packages, AlpmWrapper};
-use alpm::SigLevel;
+use super::super::srcinfo::dependency::UnreasonedDependency;
+use super::{
+    alpm_wrapper::{dependency_of, does_package_satisfy},
+    orphan_packages, AlpmWrapper,
+};
+use alpm::{Pkg, SigLevel};
 use indexmap::IndexSet;
 use std::{ffi::OsStr, ops::Deref, os::unix::prelude::OsStrExt};
 
@@ -23,64 +27,82 @@ impl ExternalPackageList {
 
     pub fn needed<'a>(
         &self,
-        srcinfo_all_depends: impl Iterator<Item = &'a str>,
-        srcinfo_conflicts: impl Iterator<Item = &'a str>,
+        srcinfo_all_depends: impl Iterator<Item = UnreasonedDependency<&'a str, &'a str>>,
+        srcinfo_conflicts: impl Iterator<Item = UnreasonedDependency<&'a str, &'a str>>,
     ) -> InstallationPlan {
-        // TODO: consider version ranges (how to check version satisfaction?)
+        let external_packages: Vec<_> = self
+            .pkg_load_params
+            .iter()
+            .filter_map(|LoadedPackageParam { filename }| {
+                match self.alpm.pkg_load(filename.clone(), true, SigLevel::NONE) {
+                    Err(error) => {
+                        eprintln!(
+                            "⚠ Failed to load {:?} as an alpm package: {}",
+                            OsStr::from_bytes(filename),
+                            error,
+                        );
+                        None
+                    }
+                    Ok(pkg) => Some((filename, pkg)),
+                }
+            })
+            .collect();
 
-        let make_installation_target = |name: String| {
-            if self.is_available(&name) {
-                return InstallationTarget {
+        let mut unsatisfiable = IndexSet::new();
+        let mut make_installation_target = |dependency: UnreasonedDependency<String, String>| {
+            let name = dependency.name().to_string();
+
+            if self.is_available_satisfying(&dependency) {
+                return Some(InstallationTarget {
                     name,
                     external: None,
-                };
+                });
             }
 
-            let external = self
-                .pkg_load_params
+            let external = external_packages
                 .iter()
-                .find(|param| {
-                    match self
-                        .alpm
-                        .pkg_load(param.filename.clone(), true, SigLevel::NONE)
-                    {
-                        Err(error) => {
-                            eprintln!(
-                                "⚠ Failed to load {:?} as an alpm package: {}",
-                                OsStr::from_bytes(&param.filename),
-                                error,
-                            );
-                            false
-                        }
-                        Ok(pkg) => pkg.name() == name,
-                    }
-                })
-                .map(|param| param.filename.clone());
+                .find(|(_, pkg)| does_package_satisfy(pkg, &dependency))
+                .map(|(filename, _)| filename.to_vec());
+
+            if external.is_none() {
+                unsatisfiable.insert(format!("{}{}", dependency.name(), dependency.range()));
+                return None;
+            }
 
-            InstallationTarget { name, external }
+            Some(InstallationTarget { name, external })
         };
 
-        let mut wanted: IndexSet<InstallationTarget> = srcinfo_all_depends
-            .filter(|pkgname| !self.is_installed(pkgname))
-            .map(ToString::to_string)
-            .map(make_installation_target)
+        let mut outdated = IndexSet::new();
+        let direct_dependencies: Vec<_> = srcinfo_all_depends
+            .filter(|dependency| !self.is_installed_satisfying(dependency))
+            .inspect(|dependency| {
+                if let Some(pkg) = self
+                    .installed_packages()
+                    .find(|pkg| pkg.name() == dependency.name)
+                {
+                    outdated.insert(format!(
+                        "{}{} (installed: {})",
+                        dependency.name,
+                        dependency.range,
+                        pkg.version(),
+                    ));
+                }
+            })
+            .map(|dependency| UnreasonedDependency {
+                name: dependency.name.to_string(),
+                range: dependency.range.to_string(),
+            })
             .collect();
 
         // Q: Why also add indirect dependencies?
         // A: To enable finding all possible conflicts later.
-        let addend: Vec<InstallationTarget> = wanted
+        let indirect_dependencies: Vec<_> = direct_dependencies
             .iter()
-            .flat_map(|InstallationTarget { name, .. }| -> Vec<String> {
+            .flat_map(|dependency| -> Vec<UnreasonedDependency<String, String>> {
                 macro_rules! find_pkg {
-                    ($list:expr) => {{
-                        let find_by_name = || $list.find(|pkg| pkg.name() == name);
-                        let find_by_provider = || {
-                            $list.find(|pkg| {
-                                pkg.provides().into_iter().any(|dep| dep.name() == name)
-                            })
-                        };
-                        find_by_name().or_else(find_by_provider)
-                    }};
+                    ($list:expr) => {
+                        $list.find(|pkg| does_package_satisfy(pkg, dependency))
+                    };
                 }
 
                 macro_rules! get_result {
@@ -89,9 +111,12 @@ impl ExternalPackageList {
                             .into_iter()
                             .chain($pkg.makedepends())
                             .chain($pkg.checkdepends())
-                            .map(|pkg| pkg.name())
-                            .filter(|pkgname| !self.is_installed(pkgname))
-                            .map(ToString::to_string)
+                            .map(dependency_of)
+                            .filter(|dependency| !self.is_installed_satisfying(dependency))
+                            .map(|dependency| UnreasonedDependency {
+                                name: dependency.name.to_string(),
+                                range: dependency.range,
+                            })
                             .collect()
                     };
                 }
@@ -100,34 +125,21 @@ impl ExternalPackageList {
                     return get_result!(pkg);
                 }
 
-                let external_packages: Vec<_> = self
-                    .pkg_load_params
-                    .iter()
-                    .filter_map(|LoadedPackageParam { filename }| {
-                        match self.alpm.pkg_load(filename.clone(), true, SigLevel::NONE) {
-                            Err(error) => {
-                                eprintln!(
-                                    "⚠ Failed to load {:?} as an alpm package: {}",
-                                    OsStr::from_bytes(filename),
-                                    error,
-                                );
-                                None
-                            }
-                            Ok(pkg) => Some(pkg),
-                        }
-                    })
-                    .collect();
-
-                if let Some(pkg) = find_pkg!(external_packages.iter()) {
+                if let Some(pkg) =
+                    find_pkg!(external_packages.iter().map(|(_, pkg)| -> &Pkg { pkg }))
+                {
                     return get_result!(pkg);
                 }
 
                 Vec::new()
             })
-            .map(make_installation_target)
             .collect();
 
-        wanted.extend(addend);
+        let wanted: IndexSet<InstallationTarget> = direct_dependencies
+            .into_iter()
+            .chain(indirect_dependencies)
+            .filter_map(&mut make_installation_target)
+            .collect();
 
         let left_unwanted = self
             .installed_packages()
@@ -140,20 +152,25 @@ impl ExternalPackageList {
 
         let orphan_packages = orphan_packages(self);
         let right_unwanted = srcinfo_conflicts
-            .filter(|pkgname| {
+            .filter(|conflict| {
                 // NOTE: do not use self.is_installed since it also includes providers
                 // NOTE: do not add explicitly installed packages to unwanted
 
                 self.installed_packages()
-                    .find(|pkg| pkg.name() == *pkgname)
+                    .find(|pkg| conflict.is_satisfied_by(pkg.name(), pkg.version().as_str()))
                     .map(|pkg| orphan_packages.contains(pkg.name()))
                     .unwrap_or(false)
             })
-            .map(ToString::to_string);
+            .map(|conflict| conflict.name.to_string());
 
         let unwanted: IndexSet<String> = left_unwanted.chain(right_unwanted).collect();
 
-        InstallationPlan { wanted, unwanted }
+        InstallationPlan {
+            wanted,
+            unwanted,
+            outdated,
+            unsatisfiable,
+        }
     }
 }
 
@@ -174,6 +191,10 @@ pub struct LoadedPackageParam {
 pub struct InstallationPlan {
     pub wanted: IndexSet<InstallationTarget>,
     pub unwanted: IndexSet<String>,
+    /// Required versions that the installed packages do not satisfy.
+    pub outdated: IndexSet<String>,
+    /// Dependencies that neither the sync databases nor the created package files could satisfy.
+    pub unsatisfiable: IndexSet<String>,
 }
 
 #[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
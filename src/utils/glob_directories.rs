@@ -0,0 +1,120 @@
+use super::read_dir_or_current;
+use std::{
+    io::ErrorKind,
+    path::{Component, Path, PathBuf},
+};
+
+/// Check whether `text` matches `pattern`.
+///
+/// `*` matches any sequence of characters other than `/`, `?` matches any single character other than `/`.
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut pattern_index, mut text_index) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while text_index < text.len() {
+        match pattern.get(pattern_index) {
+            Some('*') => {
+                backtrack = Some((pattern_index, text_index));
+                pattern_index += 1;
+                continue;
+            }
+            Some('?') if text[text_index] != '/' => {
+                pattern_index += 1;
+                text_index += 1;
+                continue;
+            }
+            Some(&current_char) if current_char == text[text_index] => {
+                pattern_index += 1;
+                text_index += 1;
+                continue;
+            }
+            _ => {}
+        }
+        match backtrack {
+            Some((star_index, star_text_index)) if text[star_text_index] != '/' => {
+                backtrack = Some((star_index, star_text_index + 1));
+                pattern_index = star_index + 1;
+                text_index = star_text_index + 1;
+            }
+            _ => return false,
+        }
+    }
+    pattern[pattern_index..].iter().all(|&char| char == '*')
+}
+
+fn has_wildcard(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// List directories under `base` whose paths relative to `base` match `pattern`.
+///
+/// Entries whose names start with `.` are only matched by pattern components that also start with `.`.
+/// The returned paths are relative to `base` and sorted.
+pub fn glob_directories(base: &Path, pattern: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut candidates = vec![PathBuf::new()];
+    for component in pattern.components() {
+        let component_pattern = match component {
+            Component::Normal(name) => match name.to_str() {
+                Some(name) if has_wildcard(name) => name,
+                _ => {
+                    for candidate in &mut candidates {
+                        candidate.push(name);
+                    }
+                    continue;
+                }
+            },
+            _ => {
+                for candidate in &mut candidates {
+                    candidate.push(component);
+                }
+                continue;
+            }
+        };
+        let mut matches = Vec::new();
+        for candidate in &candidates {
+            let directory = base.join(candidate);
+            let entries = match read_dir_or_current(&directory) {
+                Ok(entries) => entries,
+                Err(error) if error.kind() == ErrorKind::NotFound => continue,
+                Err(error) => {
+                    return Err(format!("cannot read directory {:?}: {}", directory, error))
+                }
+            };
+            for entry in entries {
+                let entry = entry.map_err(|error| {
+                    format!("cannot read an entry of {:?}: {}", directory, error)
+                })?;
+                let Ok(name) = entry.file_name().into_string() else {
+                    continue;
+                };
+                if name.starts_with('.') && !component_pattern.starts_with('.') {
+                    continue;
+                }
+                if wildcard_match(component_pattern, &name) {
+                    matches.push(candidate.join(name));
+                }
+            }
+        }
+        candidates = matches;
+    }
+    candidates.retain(|candidate| base.join(candidate).is_dir());
+    candidates.sort();
+    Ok(candidates)
+}
+
+#[test]
+fn test_wildcard_match() {
+    assert!(wildcard_match("foo", "foo"));
+    assert!(!wildcard_match("foo", "foobar"));
+    assert!(wildcard_match("foo*", "foobar"));
+    assert!(wildcard_match("*-git", "foo-git"));
+    assert!(!wildcard_match("*-git", "foo-git-bin"));
+    assert!(wildcard_match("*-*-*", "a-b-c-d"));
+    assert!(wildcard_match("f?o", "foo"));
+    assert!(!wildcard_match("f?o", "fo"));
+    assert!(wildcard_match("*", ""));
+    assert!(wildcard_match("pkgs/*", "pkgs/foo"));
+    assert!(!wildcard_match("*", "pkgs/foo"));
+    assert!(!wildcard_match("pkgs?foo", "pkgs/foo"));
+}
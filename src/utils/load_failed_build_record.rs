@@ -1,11 +1,26 @@
 use super::super::manifest::OwnedFailedBuildRecord;
 use super::PackageFileName;
 use pipe_trait::*;
-use std::{fs::File, io::ErrorKind};
+use serde::{Deserialize, Serialize};
+use std::{fs::File, io::ErrorKind, path::PathBuf};
+
+/// Package file that failed to build, as stored in `record-failed-builds`.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FailedBuildRecordEntry {
+    #[serde(flatten)]
+    pub package: PackageFileName<String, String, String>,
+    /// Log file of the failed build, if `log-directory` was set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log: Option<PathBuf>,
+    /// Failed package that this package depends on, if it was skipped because of it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked_by: Option<String>,
+}
 
 pub fn load_failed_build_record(
     failed_build_record: &Option<OwnedFailedBuildRecord>,
-) -> Result<Vec<PackageFileName<String, String, String>>, String> {
+) -> Result<Vec<FailedBuildRecordEntry>, String> {
     let Some(failed_build_record) = failed_build_record else {
         return Ok(Default::default());
     };
@@ -1,53 +1,144 @@
+use super::super::srcinfo::version::vercmp;
 use super::PackageFileName;
-use std::fmt::Display;
+use indexmap::IndexMap;
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+};
 
-pub fn outdated_packages<'a, Latest: ToString>(
-    latest_packages: impl IntoIterator<Item = Latest> + 'a,
-    current_packages: &'a [String],
+/// How a package that would be built relates to the one in the repository database.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum OutdatedStatus {
+    /// The database does not have the package.
+    New,
+    /// The database has an older version.
+    Upgrade,
+    /// The database has a newer version.
+    Downgrade,
+    /// The database has the same version but its file for the architecture is missing.
+    RebuildNeeded,
+}
+
+impl OutdatedStatus {
+    pub fn name(self) -> &'static str {
+        match self {
+            OutdatedStatus::New => "new",
+            OutdatedStatus::Upgrade => "upgrade",
+            OutdatedStatus::Downgrade => "downgrade",
+            OutdatedStatus::RebuildNeeded => "rebuild-needed",
+        }
+    }
+}
+
+impl Display for OutdatedStatus {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OutdatedPackage<'a, PkgName, Version, Arch> {
+    pub file_name: String,
+    pub latest: PackageFileName<PkgName, Version, Arch>,
+    /// Version in the repository database.
+    pub current_version: Option<&'a str>,
+    pub status: OutdatedStatus,
+}
+
+/// Compare `latest_packages` with the versions in the repository database.
+///
+/// `current_versions` maps each pkgname in the database to its version,
+/// `current_files` lists the package files in the repository directory.
+/// File names of the outdated packages end with `package_extension`.
+pub fn outdated_packages<'a, PkgName, Version, Arch>(
+    latest_packages: impl IntoIterator<Item = PackageFileName<PkgName, Version, Arch>> + 'a,
+    current_versions: &'a IndexMap<String, String>,
+    current_files: &'a [String],
     failed_builds: &'a [PackageFileName<impl Display, impl Display, impl Display>],
-) -> impl Iterator<Item = (String, Latest)> + 'a {
-    let not_failed =
-        move |filename: &String| failed_builds.iter().all(|x| &x.to_string() != filename);
+    package_extension: &'a str,
+) -> impl Iterator<Item = OutdatedPackage<'a, PkgName, Version, Arch>> + 'a
+where
+    PkgName: AsRef<str> + Display + 'a,
+    Version: AsRef<str> + Display + 'a,
+    Arch: AsRef<str> + Display + 'a,
+{
+    let not_failed = move |filename: &String| {
+        failed_builds
+            .iter()
+            .all(|x| &x.file_name(package_extension) != filename)
+    };
+
+    let has_file = move |latest: &PackageFileName<PkgName, Version, Arch>| {
+        current_files
+            .iter()
+            .any(|file_name| latest.matches(file_name))
+    };
 
     latest_packages
         .into_iter()
-        .map(|latest| (latest.to_string(), latest))
-        .filter(move |(filename, _)| !current_packages.contains(filename))
+        .map(move |latest| (latest.file_name(package_extension), latest))
         .filter(move |(filename, _)| not_failed(filename))
+        .filter_map(move |(file_name, latest)| {
+            let current_version = current_versions
+                .get(latest.pkgname.as_ref())
+                .map(String::as_str);
+            let status = match current_version {
+                None => OutdatedStatus::New,
+                Some(current_version) => match vercmp(latest.version.as_ref(), current_version) {
+                    Ordering::Greater => OutdatedStatus::Upgrade,
+                    Ordering::Less => OutdatedStatus::Downgrade,
+                    Ordering::Equal if has_file(&latest) => return None,
+                    Ordering::Equal => OutdatedStatus::RebuildNeeded,
+                },
+            };
+            Some(OutdatedPackage {
+                file_name,
+                latest,
+                current_version,
+                status,
+            })
+        })
 }
 
 #[test]
 fn test() {
-    use super::PackageFileName;
-
     let latest_packages = [
-        PackageFileName {
-            pkgname: "abc",
-            version: "1.2.3-4",
-            arch: "x86_64",
-        },
-        PackageFileName {
-            pkgname: "def",
-            version: "4.3.2-1",
-            arch: "any",
-        },
-        PackageFileName {
-            pkgname: "ghi",
-            version: "0.0.0-1",
-            arch: "i686",
-        },
-        PackageFileName {
-            pkgname: "jkl",
-            version: "3.3.3-3",
-            arch: "any",
-        },
-    ];
+        ("abc", "1.2.3-4", "x86_64"),
+        ("def", "4.3.2-1", "any"),
+        ("ghi", "0.0.0-1", "i686"),
+        ("jkl", "3.3.3-3", "any"),
+        ("mno", "1:1.0.0-1", "any"),
+        ("pqr", "2.0.0-1", "any"),
+        ("stu", "1.0.0-1", "any"),
+        ("vwx", "1.0.0-2", "any"),
+    ]
+    .map(|(pkgname, version, arch)| PackageFileName {
+        pkgname,
+        version,
+        arch,
+    });
+
+    let current_versions: IndexMap<String, String> = [
+        ("abc", "1.2.3-4"),
+        ("def", "1.2.3-4"),
+        ("ghi", "0.0.0-1"),
+        ("jkl", "0.0.0-1"),
+        ("mno", "2.0.0-1"),
+        ("pqr", "2.0.0-1"),
+        ("vwx", "1.0.0-10"),
+    ]
+    .iter()
+    .map(|(pkgname, version)| (pkgname.to_string(), version.to_string()))
+    .collect();
 
-    let current_packages = [
+    let current_files = [
         "abc-1.2.3-4-x86_64.pkg.tar.zst".to_string(),
         "def-1.2.3-4-any.pkg.tar.zst".to_string(),
         "ghi-0.0.0-1-x86_64.pkg.tar.zst".to_string(),
         "jkl-0.0.0-1-any.pkg.tar.zst".to_string(),
+        "mno-2.0.0-1-any.pkg.tar.zst".to_string(),
+        "pqr-2.0.0-1-any.pkg.tar.xz".to_string(),
+        "vwx-1.0.0-10-any.pkg.tar.zst".to_string(),
     ];
 
     let failed_builds = [PackageFileName {
@@ -56,27 +147,43 @@ fn test() {
         arch: "any",
     }];
 
-    let actual: Vec<_> =
-        outdated_packages(&latest_packages, &current_packages, &failed_builds).collect();
+    let actual: Vec<_> = outdated_packages(
+        latest_packages,
+        &current_versions,
+        &current_files,
+        &failed_builds,
+        ".pkg.tar.zst",
+    )
+    .map(|package| (package.file_name, package.current_version, package.status))
+    .collect();
 
     let expected = [
         (
             "def-4.3.2-1-any.pkg.tar.zst".to_string(),
-            &PackageFileName {
-                pkgname: "def",
-                version: "4.3.2-1",
-                arch: "any",
-            },
+            Some("1.2.3-4"),
+            OutdatedStatus::Upgrade,
         ),
         (
             "ghi-0.0.0-1-i686.pkg.tar.zst".to_string(),
-            &PackageFileName {
-                pkgname: "ghi",
-                version: "0.0.0-1",
-                arch: "i686",
-            },
+            Some("0.0.0-1"),
+            OutdatedStatus::RebuildNeeded,
+        ),
+        (
+            "mno-1:1.0.0-1-any.pkg.tar.zst".to_string(),
+            Some("2.0.0-1"),
+            OutdatedStatus::Upgrade,
+        ),
+        (
+            "stu-1.0.0-1-any.pkg.tar.zst".to_string(),
+            None,
+            OutdatedStatus::New,
+        ),
+        (
+            "vwx-1.0.0-2-any.pkg.tar.zst".to_string(),
+            Some("1.0.0-10"),
+            OutdatedStatus::Downgrade,
         ),
     ];
 
-    assert_eq!(&actual, &expected);
+    assert_eq!(actual, expected);
 }
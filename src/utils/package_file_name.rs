@@ -1,5 +1,26 @@
+use super::read_dir_or_current;
 use serde::{Deserialize, Serialize};
-use std::fmt::{self, Display, Formatter};
+use std::{
+    fmt::{self, Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+/// Extension of package files unless the manifest says otherwise.
+pub const DEFAULT_PACKAGE_EXTENSION: &str = ".pkg.tar.zst";
+
+/// Whether `extension` is `.pkg.tar` followed by at most one compression suffix, such as `.pkg.tar.zst`.
+pub fn is_package_extension(extension: &str) -> bool {
+    let Some(compression) = extension.strip_prefix(".pkg.tar") else {
+        return false;
+    };
+    if compression.is_empty() {
+        return true;
+    }
+    match compression.strip_prefix('.') {
+        Some(suffix) => !suffix.is_empty() && !suffix.contains(['.', '/']),
+        None => false,
+    }
+}
 
 #[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Copy, Clone)]
 #[serde(rename_all = "kebab-case")]
@@ -19,31 +40,136 @@ where
         write!(formatter, "{}-{}-{}", self.pkgname, self.version, self.arch)
     }
 
-    pub fn fmt_with_ext(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
-        self.fmt_without_ext(formatter)?;
-        write!(formatter, ".pkg.tar.zst")
+    /// Name of the package file that makepkg creates with `PKGEXT` set to `extension`.
+    pub fn file_name(&self, extension: &str) -> String {
+        format!(
+            "{}-{}-{}{}",
+            self.pkgname, self.version, self.arch, extension,
+        )
     }
 }
 
-impl<PkgName, Version, Arch> Display for PackageFileName<PkgName, Version, Arch>
+impl<PkgName, Version, Arch> PackageFileName<PkgName, Version, Arch>
 where
-    PkgName: Display,
-    Version: Display,
-    Arch: Display,
+    PkgName: AsRef<str>,
+    Version: AsRef<str>,
+    Arch: AsRef<str>,
 {
-    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
-        self.fmt_with_ext(formatter)
+    /// Whether `file_name` is a package file of this package, whatever its compression.
+    pub fn matches(&self, file_name: &str) -> bool {
+        match PackageFileName::parse(file_name) {
+            Some(name) => {
+                name.pkgname == self.pkgname.as_ref()
+                    && name.version == self.version.as_ref()
+                    && name.arch == self.arch.as_ref()
+            }
+            None => false,
+        }
+    }
+
+    /// Find the package file of this package in `directory`.
+    ///
+    /// The file with `extension` is preferred over ones with other compressions.
+    pub fn find_in(&self, directory: &Path, extension: &str) -> Option<PathBuf>
+    where
+        PkgName: Display,
+        Version: Display,
+        Arch: Display,
+    {
+        let preferred = directory.join(self.file_name(extension));
+        if preferred.exists() {
+            return Some(preferred);
+        }
+        read_dir_or_current(directory)
+            .ok()?
+            .filter_map(Result::ok)
+            .find(|entry| self.matches(&entry.file_name().to_string_lossy()))
+            .map(|entry| directory.join(entry.file_name()))
+    }
+}
+
+impl<'a> PackageFileName<&'a str, &'a str, &'a str> {
+    /// Parse a file name such as `foo-1:0.1.2-3-x86_64.pkg.tar.zst`, whatever the compression.
+    pub fn parse(file_name: &'a str) -> Option<Self> {
+        let (base, _) = file_name.rsplit_once(".pkg.tar")?;
+        if !is_package_extension(&file_name[base.len()..]) {
+            return None;
+        }
+        let mut segments = base.rsplitn(4, '-');
+        let arch = segments.next()?;
+        let pkgrel = segments.next()?;
+        let pkgver = segments.next()?;
+        let pkgname = segments.next()?;
+        if [pkgname, pkgver, pkgrel, arch].iter().any(|x| x.is_empty()) {
+            return None;
+        }
+        let version = &base[pkgname.len() + 1..base.len() - arch.len() - 1];
+        Some(PackageFileName {
+            pkgname,
+            version,
+            arch,
+        })
     }
 }
 
 #[test]
-fn test_to_string() {
-    let actual = PackageFileName {
+fn test_file_name() {
+    let name = PackageFileName {
         pkgname: "foo",
-        version: "0.1.2-3",
-        arch: "x86_64",
-    }
-    .to_string();
-    let expected = "foo-0.1.2-3-x86_64.pkg.tar.zst";
-    assert_eq!(actual, expected);
+        version: "1:0.1.2-3",
+        arch: "any",
+    };
+    assert_eq!(
+        name.file_name(".pkg.tar.xz"),
+        "foo-1:0.1.2-3-any.pkg.tar.xz"
+    );
+    assert!(name.matches("foo-1:0.1.2-3-any.pkg.tar.xz"));
+    assert!(name.matches("foo-1:0.1.2-3-any.pkg.tar.zst"));
+    assert!(!name.matches("foo-1:0.1.2-3-any.pkg.tar.zst.sig"));
+    assert!(!name.matches("foo-1:0.1.2-4-any.pkg.tar.zst"));
+    assert!(!name.matches("foo-1:0.1.2-3-x86_64.pkg.tar.zst"));
+}
+
+#[test]
+fn test_parse() {
+    let parse = PackageFileName::parse;
+    assert_eq!(
+        parse("foo-bar-1:0.1.2-3-x86_64.pkg.tar.zst"),
+        Some(PackageFileName {
+            pkgname: "foo-bar",
+            version: "1:0.1.2-3",
+            arch: "x86_64",
+        }),
+    );
+    assert_eq!(
+        parse("foo-0.1.2-3.1-any.pkg.tar.xz"),
+        Some(PackageFileName {
+            pkgname: "foo",
+            version: "0.1.2-3.1",
+            arch: "any",
+        }),
+    );
+    assert_eq!(
+        parse("foo-0.1.2-3-any.pkg.tar"),
+        Some(PackageFileName {
+            pkgname: "foo",
+            version: "0.1.2-3",
+            arch: "any",
+        }),
+    );
+    assert_eq!(parse("foo-0.1.2-3-any.pkg.tar.zst.sig"), None);
+    assert_eq!(parse("0.1.2-3-any.pkg.tar.zst"), None);
+    assert_eq!(parse("repo.db.tar.gz"), None);
+}
+
+#[test]
+fn test_is_package_extension() {
+    assert!(is_package_extension(".pkg.tar.zst"));
+    assert!(is_package_extension(".pkg.tar.xz"));
+    assert!(is_package_extension(".pkg.tar"));
+    assert!(!is_package_extension(".tar.zst"));
+    assert!(!is_package_extension(".pkg.tar.zst.sig"));
+    assert!(!is_package_extension(".pkg.tar."));
+    assert!(!is_package_extension(".pkg.tarzst"));
+    assert!(!is_package_extension(".pkg.tar./zst"));
 }
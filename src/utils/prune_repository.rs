@@ -0,0 +1,107 @@
+use super::super::repository_database::{Error, RepositoryDatabase};
+use super::{read_dir_or_current, superseded_packages};
+use std::{
+    fs::remove_file,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
+
+/// Delete package files that are older than the version in the repository database, along with their signatures.
+///
+/// Files are grouped by package name and architecture, and the newest `keep_versions` of each group are kept,
+/// counting the version in the database.
+///
+/// The files to delete are listed before anything is deleted. Nothing is deleted if `dry_run` is `true`.
+pub fn prune_repository(
+    repository: &Path,
+    keep_versions: NonZeroUsize,
+    dry_run: bool,
+) -> Result<(), Error> {
+    let repository_directory = repository.parent().expect("get repository directory");
+    if !repository.exists() {
+        eprintln!(
+            "🛈 Repository database {:?} does not exist. Nothing to prune.",
+            repository
+        );
+        return Ok(());
+    }
+    let current_files: Vec<_> = RepositoryDatabase::load(repository)?
+        .records()
+        .values()
+        .filter_map(|record| record.file_name().map(ToString::to_string))
+        .collect();
+
+    let mut files = Vec::new();
+    for entry in read_dir_or_current(repository_directory)? {
+        if let Ok(file_name) = entry?.file_name().into_string() {
+            files.push(file_name);
+        }
+    }
+
+    let mut targets: Vec<(PathBuf, u64)> = Vec::new();
+    for file_name in superseded_packages(
+        files.iter().map(String::as_str),
+        &current_files,
+        keep_versions,
+    ) {
+        for file_name in [file_name.to_string(), format!("{}.sig", file_name)] {
+            let path = repository_directory.join(file_name);
+            if let Ok(metadata) = path.symlink_metadata() {
+                targets.push((path, metadata.len()));
+            }
+        }
+    }
+
+    if targets.is_empty() {
+        eprintln!("🛈 No superseded package files.");
+        return Ok(());
+    }
+
+    eprintln!("🛈 Superseded package files:");
+    for (path, size) in &targets {
+        eprintln!("  ● {} ({})", path.to_string_lossy(), format_size(*size));
+    }
+    let total: u64 = targets.iter().map(|(_, size)| size).sum();
+
+    if dry_run {
+        eprintln!("🛈 Pruning would reclaim {}", format_size(total));
+        return Ok(());
+    }
+
+    for (path, _) in &targets {
+        eprintln!("  → Delete {:?}", path);
+        remove_file(path)?;
+    }
+    eprintln!("🛈 Reclaimed {}", format_size(total));
+
+    Ok(())
+}
+
+fn format_size(size: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB"];
+    if size < 1024 {
+        return format!("{} B", size);
+    }
+    let mut value = size as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next_unit in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next_unit;
+    }
+    format!("{:.1} {}", value, unit)
+}
+
+#[test]
+fn test_format_size() {
+    assert_eq!(format_size(0), "0 B");
+    assert_eq!(format_size(1023), "1023 B");
+    assert_eq!(format_size(1536), "1.5 KiB");
+    assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+    assert_eq!(
+        format_size(3 * 1024 * 1024 * 1024 * 1024 * 1024),
+        "3072.0 TiB"
+    );
+}
@@ -0,0 +1,29 @@
+use std::{
+    fs::{read_dir, ReadDir},
+    io,
+    path::Path,
+};
+
+/// Same as [`read_dir`], but an empty `directory` means the current directory.
+///
+/// An empty path is what [`Path::parent`] returns for a relative path of a single component,
+/// such as a repository at `repo.db.tar.gz`.
+pub fn read_dir_or_current(directory: &Path) -> io::Result<ReadDir> {
+    // PROBLEM: read_dir cannot read "" as a directory
+    // WORKAROUND: replace it with "."
+    if directory.as_os_str().is_empty() {
+        read_dir(".")
+    } else {
+        read_dir(directory)
+    }
+}
+
+#[test]
+fn test_read_dir_or_current() {
+    let count = |path: &str| {
+        read_dir_or_current(Path::new(path))
+            .expect("read directory")
+            .count()
+    };
+    assert_eq!(count(""), count("."));
+}
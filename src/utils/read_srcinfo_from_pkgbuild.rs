@@ -1,10 +1,10 @@
-use super::create_makepkg_command;
+use super::{create_makepkg_command, DEFAULT_PACKAGE_EXTENSION};
 use command_extra::CommandExtra;
 use pipe_trait::*;
 use std::path::Path;
 
 pub fn read_srcinfo_from_pkgbuild(directory: &Path) -> Result<String, String> {
-    let output = create_makepkg_command()
+    let output = create_makepkg_command(DEFAULT_PACKAGE_EXTENSION)
         .with_current_dir(directory)
         .with_arg("--printsrcinfo")
         .output()
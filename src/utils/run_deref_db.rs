@@ -19,7 +19,10 @@ pub fn run_deref_db(repository_directory: &Path) -> Result<(), io::Error> {
         }
         let file_name = entry.file_name();
         let lossy_file_name = file_name.to_string_lossy();
-        if !lossy_file_name.ends_with(".db") && !lossy_file_name.ends_with(".files") {
+        let is_database = [".db", ".files", ".db.sig", ".files.sig"]
+            .iter()
+            .any(|suffix| lossy_file_name.ends_with(suffix));
+        if !is_database {
             continue;
         }
         let link_path = canon_repository_directory.join(file_name);
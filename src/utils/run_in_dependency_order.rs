@@ -0,0 +1,196 @@
+use indexmap::IndexMap;
+use std::{
+    hash::Hash,
+    num::NonZeroUsize,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    sync::mpsc,
+    thread,
+};
+
+/// Run `task` on every item of `order` with at most `jobs` items at a time.
+///
+/// An item starts as soon as every item it depends on has finished. Among the ready
+/// items, the one that comes first in `order` starts first, so `jobs = 1` follows `order` exactly.
+///
+/// `finish` is called on the calling thread with the output of each task as soon as it finishes.
+/// If it returns `false`, no more items are started and the running ones are waited for.
+///
+/// If a task panics, no more items are started either, and the panic is resumed once the running ones have finished.
+pub fn run_in_dependency_order<Item, Output>(
+    order: &[Item],
+    dependencies: impl Fn(Item) -> Vec<Item>,
+    jobs: NonZeroUsize,
+    task: impl Fn(Item) -> Output + Sync,
+    mut finish: impl FnMut(Item, Output) -> bool,
+) where
+    Item: Hash + Eq + Copy + Send,
+    Output: Send,
+{
+    let mut dependants: IndexMap<Item, Vec<Item>> = IndexMap::new();
+    let mut remaining: IndexMap<Item, usize> = order.iter().map(|item| (*item, 0)).collect();
+    for item in order.iter().copied() {
+        for dependency in dependencies(item) {
+            if !remaining.contains_key(&dependency) {
+                continue;
+            }
+            dependants.entry(dependency).or_default().push(item);
+            *remaining.get_mut(&item).expect("item is registered") += 1;
+        }
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    let task = &task;
+
+    let mut panicked = None;
+
+    thread::scope(|scope| {
+        let mut running = 0;
+        let mut proceed = true;
+
+        loop {
+            while proceed && running < jobs.get() {
+                let Some(index) = remaining.values().position(|count| *count == 0) else {
+                    break;
+                };
+                let (item, _) = remaining.shift_remove_index(index).expect("ready item");
+                let sender = sender.clone();
+                scope.spawn(move || {
+                    // NOTE: the output must be sent even if the task panics, or the receiver would wait forever
+                    let output = catch_unwind(AssertUnwindSafe(|| task(item)));
+                    sender
+                        .send((item, output))
+                        .expect("send the output of a finished task");
+                });
+                running += 1;
+            }
+
+            if running == 0 {
+                break;
+            }
+
+            let (item, output) = receiver.recv().expect("receive the output of a task");
+            running -= 1;
+
+            let output = match output {
+                Ok(output) => output,
+                Err(payload) => {
+                    panicked.get_or_insert(payload);
+                    proceed = false;
+                    continue;
+                }
+            };
+
+            for dependant in dependants.get(&item).into_iter().flatten() {
+                if let Some(count) = remaining.get_mut(dependant) {
+                    *count -= 1;
+                }
+            }
+
+            if !finish(item, output) {
+                proceed = false;
+            }
+        }
+    });
+
+    if let Some(payload) = panicked {
+        resume_unwind(payload);
+    }
+}
+
+#[test]
+fn test_run_in_dependency_order() {
+    use std::sync::Mutex;
+
+    let order = ["a", "b", "c", "d", "e"];
+    let dependencies = |item| match item {
+        "c" => vec!["a", "b"],
+        "d" => vec!["c"],
+        "e" => vec!["a"],
+        _ => vec![],
+    };
+
+    for jobs in 1..=4 {
+        let started = Mutex::new(Vec::new());
+        let mut finished = Vec::new();
+        run_in_dependency_order(
+            &order,
+            dependencies,
+            NonZeroUsize::new(jobs).unwrap(),
+            |item| started.lock().unwrap().push(item),
+            |item, ()| {
+                finished.push(item);
+                true
+            },
+        );
+
+        let started = started.into_inner().unwrap();
+        if jobs == 1 {
+            assert_eq!(started, order);
+        }
+
+        let position = |item| finished.iter().position(|x| *x == item).unwrap();
+        for item in order {
+            for dependency in dependencies(item) {
+                assert!(position(dependency) < position(item));
+            }
+        }
+        assert_eq!(finished.len(), order.len());
+    }
+}
+
+#[test]
+fn test_run_in_dependency_order_stop() {
+    let order = ["a", "b", "c"];
+    let mut finished = Vec::new();
+    run_in_dependency_order(
+        &order,
+        |_| Vec::new(),
+        NonZeroUsize::new(1).unwrap(),
+        |item| item,
+        |item, _| {
+            finished.push(item);
+            item != "b"
+        },
+    );
+    assert_eq!(finished, ["a", "b"]);
+}
+
+#[test]
+fn test_run_in_dependency_order_panic() {
+    use std::sync::Mutex;
+
+    let order = ["a", "b", "c", "d"];
+    for jobs in 1..=4 {
+        let finished = Mutex::new(Vec::new());
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            run_in_dependency_order(
+                &order,
+                |item| match item {
+                    "d" => vec!["b"],
+                    _ => vec![],
+                },
+                NonZeroUsize::new(jobs).unwrap(),
+                |item| {
+                    if item == "b" {
+                        panic!("task {} panics", item);
+                    }
+                },
+                |item, ()| {
+                    finished.lock().unwrap().push(item);
+                    true
+                },
+            )
+        }));
+        let message = *result
+            .expect_err("the panic is resumed")
+            .downcast::<String>()
+            .unwrap();
+        assert_eq!(message, "task b panics");
+        let finished = finished.into_inner().unwrap();
+        assert!(!finished.contains(&"b"));
+        assert!(!finished.contains(&"d"));
+        if jobs == 1 {
+            assert_eq!(finished, ["a"]);
+        }
+    }
+}
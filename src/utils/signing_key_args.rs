@@ -0,0 +1,7 @@
+/// Arguments that make makepkg or repo-add sign with `signing_key` rather than the default key.
+pub fn signing_key_args(sign: bool, signing_key: Option<&str>) -> Vec<&str> {
+    match (sign, signing_key) {
+        (true, Some(signing_key)) => vec!["--key", signing_key],
+        _ => Vec::new(),
+    }
+}
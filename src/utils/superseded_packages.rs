@@ -0,0 +1,95 @@
+use super::super::srcinfo::version::vercmp;
+use super::PackageFileName;
+use indexmap::IndexMap;
+use std::{cmp::Ordering, num::NonZeroUsize};
+
+/// Find the package files in `files` that are older than the ones in `current_files`.
+///
+/// For each package, `keep_versions` counts the current version, so `1` keeps none of the older ones.
+/// Packages without a current file are left alone.
+pub fn superseded_packages<'a>(
+    files: impl IntoIterator<Item = &'a str>,
+    current_files: &[impl AsRef<str>],
+    keep_versions: NonZeroUsize,
+) -> Vec<&'a str> {
+    let current: IndexMap<(&str, &str), &str> = current_files
+        .iter()
+        .filter_map(|file_name| PackageFileName::parse(file_name.as_ref()))
+        .map(|name| ((name.pkgname, name.arch), name.version))
+        .collect();
+
+    let mut older: IndexMap<(&str, &str), Vec<(&str, &str)>> = IndexMap::new();
+    for file_name in files {
+        let Some(name) = PackageFileName::parse(file_name) else {
+            continue;
+        };
+        let Some(current_version) = current.get(&(name.pkgname, name.arch)) else {
+            continue;
+        };
+        if vercmp(name.version, current_version) == Ordering::Less {
+            older
+                .entry((name.pkgname, name.arch))
+                .or_default()
+                .push((name.version, file_name));
+        }
+    }
+
+    older
+        .into_values()
+        .flat_map(|mut versions| {
+            versions.sort_by(|(a, _), (b, _)| vercmp(b, a));
+            versions
+                .into_iter()
+                .skip(keep_versions.get() - 1)
+                .map(|(_, file_name)| file_name)
+        })
+        .collect()
+}
+
+#[test]
+fn test_superseded_packages() {
+    let files = [
+        "foo-1.0.0-1-x86_64.pkg.tar.zst",
+        "foo-1.0.0-1-x86_64.pkg.tar.zst.sig",
+        "foo-1.1.0-1-x86_64.pkg.tar.zst",
+        "foo-1.10.0-1-x86_64.pkg.tar.zst",
+        "foo-1.2.0-1-x86_64.pkg.tar.zst",
+        "foo-1.2.0-1-i686.pkg.tar.zst",
+        "bar-2-1-any.pkg.tar.xz",
+        "bar-3-1-any.pkg.tar.zst",
+        "baz-1-1-any.pkg.tar.zst",
+        "orphan-1-1-any.pkg.tar.zst",
+        "repo.db.tar.gz",
+    ];
+    let current_files = [
+        "foo-1.10.0-1-x86_64.pkg.tar.zst",
+        "foo-1.2.0-1-i686.pkg.tar.zst",
+        "bar-3-1-any.pkg.tar.zst",
+        "baz-1-1-any.pkg.tar.zst",
+    ];
+    let superseded = |keep_versions| {
+        superseded_packages(
+            files,
+            &current_files,
+            NonZeroUsize::new(keep_versions).unwrap(),
+        )
+    };
+
+    assert_eq!(
+        superseded(1),
+        [
+            "foo-1.2.0-1-x86_64.pkg.tar.zst",
+            "foo-1.1.0-1-x86_64.pkg.tar.zst",
+            "foo-1.0.0-1-x86_64.pkg.tar.zst",
+            "bar-2-1-any.pkg.tar.xz",
+        ],
+    );
+    assert_eq!(
+        superseded(2),
+        [
+            "foo-1.1.0-1-x86_64.pkg.tar.zst",
+            "foo-1.0.0-1-x86_64.pkg.tar.zst",
+        ],
+    );
+    assert_eq!(superseded(4), [] as [&str; 0]);
+}
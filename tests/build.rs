@@ -0,0 +1,351 @@
+#![cfg(feature = "alpm")]
+
+use command_extra::CommandExtra;
+use pacman_repo_builder::repository_database::RepositoryDatabase;
+use std::{
+    env,
+    fs::{create_dir, read_dir, read_to_string, remove_file, set_permissions, write, Permissions},
+    os::unix::fs::PermissionsExt,
+    path::Path,
+    process::{Command, Output},
+};
+use tempfile::tempdir;
+
+const EXE: &str = env!("CARGO_BIN_EXE_build-pacman-repo");
+
+/// Stand-in of makepkg that packs a `.PKGINFO` made from `.SRCINFO` and records its arguments.
+///
+/// It signs the package file when given `--sign`. A `fail-<pkgname>` file in the log directory
+/// makes it fail, an `abort-<pkgname>` file makes it kill `build-pacman-repo` instead.
+const FAKE_MAKEPKG: &str = r#"#!/bin/sh
+printf '%s\n' "$*" >> "$FAKE_LOG_DIRECTORY/makepkg.log"
+field() {
+    sed -n "s/^[[:space:]]*$1 = //p" .SRCINFO | head -n 1
+}
+pkgname=$(field pkgname)
+if [ -e "$FAKE_LOG_DIRECTORY/abort-$pkgname" ]; then
+    kill -9 "$PPID"
+    exit 1
+fi
+if [ -e "$FAKE_LOG_DIRECTORY/fail-$pkgname" ]; then
+    for line in 1 2 3 4 5; do
+        echo "$pkgname error $line" >&2
+    done
+    exit 1
+fi
+version="$(field pkgver)-$(field pkgrel)"
+printf 'pkgname = %s\npkgbase = %s\npkgver = %s\narch = any\n' "$pkgname" "$pkgname" "$version" > .PKGINFO
+echo "packing $pkgname"
+file="$pkgname-$version-any$PKGEXT"
+bsdtar --create --zstd --file "$file" .PKGINFO || exit
+case " $* " in *" --sign "*)
+    key=$(printf '%s\n' "$@" | sed -n '/^--key$/{n;p;}')
+    exec gpg --batch --detach-sign --no-armor --yes ${key:+--local-user "$key"} "$file"
+esac
+"#;
+
+/// Stand-in of pacman that fails like pacman does when another transaction holds the lock.
+const FAKE_PACMAN: &str = r#"#!/bin/sh
+if ! mkdir "$FAKE_LOG_DIRECTORY/db.lck" 2>/dev/null; then
+    echo "error: failed to init transaction (unable to lock database)" >&2
+    printf '%s\n' "$*" >> "$FAKE_LOG_DIRECTORY/conflicts.log"
+    exit 1
+fi
+printf '%s\n' "$*" >> "$FAKE_LOG_DIRECTORY/pacman.log"
+sleep 0.2
+rmdir "$FAKE_LOG_DIRECTORY/db.lck"
+"#;
+
+const PACKAGES: &[&str] = &["foo", "bar", "baz", "qux"];
+
+fn write_executable(file: &Path, content: &str) {
+    write(file, content).expect("write executable");
+    set_permissions(file, Permissions::from_mode(0o755)).expect("make file executable");
+}
+
+fn setup(work_dir: &Path, settings: &str) {
+    let bin = work_dir.join("bin");
+    create_dir(&bin).expect("create bin directory");
+    write_executable(&bin.join("makepkg"), FAKE_MAKEPKG);
+    write_executable(&bin.join("pacman"), FAKE_PACMAN);
+    create_dir(work_dir.join("logs")).expect("create log directory");
+    create_dir(work_dir.join("repo")).expect("create repository directory");
+
+    let mut manifest = format!(
+        "global-settings:\n  repository: repo/repo.db.tar.gz\n  read-build-metadata: srcinfo\n{}members:\n",
+        settings,
+    );
+    for pkgname in PACKAGES {
+        let directory = work_dir.join(pkgname);
+        create_dir(&directory).expect("create build directory");
+        write(
+            directory.join(".SRCINFO"),
+            format!(
+                "pkgbase = {0}\n\tpkgver = 1.0.0\n\tpkgrel = 1\n\tarch = any\n\npkgname = {0}\n",
+                pkgname,
+            ),
+        )
+        .expect("write .SRCINFO");
+        manifest += &format!("  - directory: {}\n", pkgname);
+    }
+    write(work_dir.join("build-pacman-repo.yaml"), manifest).expect("write manifest");
+}
+
+fn build(work_dir: &Path, args: &[&str]) -> Output {
+    let mut paths = vec![work_dir.join("bin")];
+    paths.extend(env::split_paths(&env::var_os("PATH").unwrap_or_default()));
+    let output = Command::new(EXE)
+        .with_current_dir(work_dir)
+        .with_env("PATH", env::join_paths(paths).expect("join PATH"))
+        .with_env("FAKE_LOG_DIRECTORY", work_dir.join("logs"))
+        .with_env("GNUPGHOME", work_dir.join("gnupg"))
+        .with_arg("build")
+        .with_args(args)
+        .output()
+        .expect("run build");
+    eprintln!("{}", String::from_utf8_lossy(&output.stderr));
+    output
+}
+
+fn read_log(work_dir: &Path, name: &str) -> Vec<String> {
+    read_to_string(work_dir.join("logs").join(name))
+        .unwrap_or_default()
+        .lines()
+        .map(ToString::to_string)
+        .collect()
+}
+
+fn database_packages(work_dir: &Path) -> Vec<String> {
+    let mut packages: Vec<_> = RepositoryDatabase::load(&work_dir.join("repo/repo.db.tar.gz"))
+        .expect("load repository database")
+        .records()
+        .values()
+        .filter_map(|record| record.file_name())
+        .map(ToString::to_string)
+        .collect();
+    packages.sort();
+    packages
+}
+
+#[test]
+fn parallel_jobs() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    setup(work_dir, "  install-missing-dependencies: true\n");
+
+    let output = build(work_dir, &["--jobs", "2"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let makepkg_calls = read_log(work_dir, "makepkg.log");
+    assert_eq!(makepkg_calls.len(), PACKAGES.len());
+    for arguments in &makepkg_calls {
+        assert!(!arguments.contains("--install"), "makepkg {}", arguments);
+        assert!(!arguments.contains("--syncdeps"), "makepkg {}", arguments);
+    }
+
+    let pacman_calls = read_log(work_dir, "pacman.log");
+    assert_eq!(pacman_calls.len(), PACKAGES.len());
+    assert!(pacman_calls
+        .iter()
+        .all(|call| call.starts_with("--upgrade")));
+    assert_eq!(read_log(work_dir, "conflicts.log"), Vec::<String>::new());
+
+    let mut expected: Vec<_> = PACKAGES
+        .iter()
+        .map(|pkgname| format!("{}-1.0.0-1-any.pkg.tar.zst", pkgname))
+        .collect();
+    expected.sort();
+    assert_eq!(database_packages(work_dir), expected);
+}
+
+#[test]
+fn interrupted_build() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    setup(work_dir, "");
+    write(work_dir.join("logs").join("abort-foo"), "").expect("mark foo as aborting");
+
+    let output = build(work_dir, &[]);
+    assert_eq!(output.status.code(), None);
+
+    let mut copied: Vec<_> = read_dir(work_dir.join("repo"))
+        .expect("read repository directory")
+        .map(|entry| entry.expect("read repository entry").file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .filter(|name| name.ends_with(".pkg.tar.zst"))
+        .collect();
+    copied.sort();
+    assert!(
+        !copied.is_empty(),
+        "some packages should be built before foo"
+    );
+    assert!(!copied.iter().any(|name| name.starts_with("foo-")));
+    let mut journal: Vec<_> = read_to_string(work_dir.join("repo/repo.db.tar.gz.journal"))
+        .expect("read journal")
+        .lines()
+        .map(ToString::to_string)
+        .collect();
+    journal.sort();
+    assert_eq!(journal, copied);
+
+    // The next build adds the package files that it skips as already built.
+    remove_file(work_dir.join("logs").join("abort-foo")).expect("unmark foo as aborting");
+    let output = build(work_dir, &[]);
+    assert!(output.status.success());
+    assert!(!work_dir.join("repo/repo.db.tar.gz.journal").exists());
+    let mut expected: Vec<_> = PACKAGES
+        .iter()
+        .map(|pkgname| format!("{}-1.0.0-1-any.pkg.tar.zst", pkgname))
+        .collect();
+    expected.sort();
+    assert_eq!(database_packages(work_dir), expected);
+}
+
+#[test]
+fn failed_build() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    setup(work_dir, "");
+    write(work_dir.join("logs").join("fail-bar"), "").expect("mark bar as failing");
+
+    let output = build(work_dir, &[]);
+    assert!(!output.status.success());
+
+    let mut copied: Vec<_> = read_dir(work_dir.join("repo"))
+        .expect("read repository directory")
+        .map(|entry| entry.expect("read repository entry").file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .filter(|name| name.ends_with(".pkg.tar.zst"))
+        .collect();
+    copied.sort();
+    assert!(
+        !copied.is_empty(),
+        "some packages should be built before bar"
+    );
+    assert!(!copied.iter().any(|name| name.starts_with("bar-")));
+    assert_eq!(database_packages(work_dir), copied);
+    assert!(!work_dir.join("repo/repo.db.tar.gz.journal").exists());
+}
+
+#[test]
+fn log_directory() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    setup(
+        work_dir,
+        "  log-directory: build-logs\n  allow-failure: true\n",
+    );
+    write(work_dir.join("logs").join("fail-bar"), "").expect("mark bar as failing");
+
+    let output = build(work_dir, &["--log-tail", "2"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let log_files: Vec<_> = read_dir(work_dir.join("build-logs"))
+        .expect("read log directory")
+        .map(|entry| entry.expect("read log directory entry").path())
+        .collect();
+    assert_eq!(log_files.len(), PACKAGES.len());
+    let log_of = |pkgname: &str| -> String {
+        let prefix = format!("{}-any-", pkgname);
+        let file = log_files
+            .iter()
+            .find(|file| {
+                let name = file.file_name().unwrap().to_string_lossy();
+                name.starts_with(&prefix) && name.ends_with(".log")
+            })
+            .unwrap_or_else(|| panic!("find log file of {}", pkgname));
+        read_to_string(file).expect("read log file")
+    };
+    assert!(log_of("foo").contains("packing foo"));
+    assert!(!log_of("foo").contains("packing bar"));
+    assert!(log_of("bar").contains("bar error 5"));
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let summary = stderr
+        .split("Some builds failed:")
+        .nth(1)
+        .expect("find failure summary");
+    assert!(summary.contains("● bar"), "{}", summary);
+    assert!(summary.contains("│ bar error 4"), "{}", summary);
+    assert!(summary.contains("│ bar error 5"), "{}", summary);
+    assert!(!summary.contains("bar error 3"), "{}", summary);
+
+    let mut expected: Vec<_> = PACKAGES
+        .iter()
+        .filter(|pkgname| **pkgname != "bar")
+        .map(|pkgname| format!("{}-1.0.0-1-any.pkg.tar.zst", pkgname))
+        .collect();
+    expected.sort();
+    assert_eq!(database_packages(work_dir), expected);
+}
+
+#[test]
+fn sign_packages_and_database() {
+    const KEY: &str = "build-pacman-repo-test@example.invalid";
+
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    setup(
+        work_dir,
+        &format!(
+            "  sign-packages: true\n  sign-database: true\n  signing-key: {}\n",
+            KEY,
+        ),
+    );
+
+    let gnupg_home = work_dir.join("gnupg");
+    create_dir(&gnupg_home).expect("create GNUPGHOME");
+    set_permissions(&gnupg_home, Permissions::from_mode(0o700)).expect("make GNUPGHOME private");
+    let gpg = |args: &[&str]| {
+        Command::new("gpg")
+            .with_env("GNUPGHOME", &gnupg_home)
+            .with_arg("--batch")
+            .with_args(args)
+            .output()
+            .expect("run gpg")
+    };
+    let status = gpg(&[
+        "--passphrase",
+        "",
+        "--quick-gen-key",
+        KEY,
+        "default",
+        "default",
+        "never",
+    ])
+    .status;
+    assert!(status.success(), "generate signing key");
+
+    let output = build(work_dir, &[]);
+
+    let mut files: Vec<_> = PACKAGES
+        .iter()
+        .map(|pkgname| format!("{}-1.0.0-1-any.pkg.tar.zst", pkgname))
+        .collect();
+    files.push("repo.db.tar.gz".to_string());
+    let verifications: Vec<_> = files
+        .iter()
+        .map(|file| {
+            let file = work_dir.join("repo").join(file);
+            let signature = format!("{}.sig", file.to_string_lossy());
+            let output = gpg(&["--verify", &signature, &file.to_string_lossy()]);
+            (file, output)
+        })
+        .collect();
+
+    Command::new("gpgconf")
+        .with_env("GNUPGHOME", &gnupg_home)
+        .with_args(&["--kill", "gpg-agent"])
+        .status()
+        .ok();
+
+    assert_eq!(output.status.code(), Some(0));
+    for (file, output) in verifications {
+        assert!(
+            output.status.success(),
+            "verify {:?}: {}",
+            file,
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "alpm")]
+
+use command_extra::CommandExtra;
+use pipe_trait::*;
+use std::{path::PathBuf, process::Command};
+
+const EXE: &str = env!("CARGO_BIN_EXE_build-pacman-repo");
+const ROOT: &str = env!("CARGO_MANIFEST_DIR");
+
+fn work_dir() -> PathBuf {
+    ROOT.pipe(PathBuf::from)
+        .join("tests")
+        .join("fixtures")
+        .join("build-plan")
+}
+
+fn init() -> Command {
+    Command::new(EXE)
+        .with_current_dir(work_dir())
+        .with_arg("build")
+        .with_arg("--dry-run")
+}
+
+fn output(mut command: Command) -> (String, String, bool) {
+    let output = command.output().expect("get output from a command");
+    let stdout = output
+        .stdout
+        .pipe(String::from_utf8)
+        .expect("convert stdout to UTF-8");
+    let stderr = output
+        .stderr
+        .pipe(String::from_utf8)
+        .expect("convert stderr to UTF-8");
+    let success = output.status.success();
+    (stdout, stderr, success)
+}
+
+fn inspect((stdout, stderr, success): (&str, &str, bool)) {
+    eprintln!();
+    eprintln!();
+    eprintln!("STDOUT:\n\n{}\n\n", stdout);
+    eprintln!("STDERR:\n\n{}\n\n", stderr);
+    eprintln!("SUCCESS: {}\n\n", success);
+}
+
+#[test]
+fn dry_run_text() {
+    let (stdout, stderr, success) = output(init());
+    let actual = (stdout.trim(), stderr.trim(), success);
+    inspect(actual);
+    let expected = (
+        include_str!("./expected-output/build-plan/dry-run.stdout.txt").trim(),
+        "",
+        true,
+    );
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn dry_run_yaml() {
+    let (stdout, stderr, success) = init().with_args(&["--plan-format", "yaml"]).pipe(output);
+    let actual = (stdout.trim(), stderr.trim(), success);
+    inspect(actual);
+    let expected = (
+        include_str!("./expected-output/build-plan/dry-run.stdout.yaml").trim(),
+        "",
+        true,
+    );
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn dry_run_leaves_repository_untouched() {
+    let (_, stderr, success) = output(init());
+    inspect(("", stderr.trim(), success));
+    assert!(success);
+    let repository = work_dir().join("repo");
+    let mut files: Vec<_> = repository
+        .read_dir()
+        .expect("read repository directory")
+        .map(|entry| entry.expect("read entry").file_name())
+        .collect();
+    files.sort();
+    assert_eq!(files, ["already-built-1.0.0-1-any.pkg.tar.zst"]);
+}
@@ -0,0 +1,31 @@
+use pacman_repo_builder::repository_database::{
+    package_entry::PackageEntry, package_info::PackageInfo, RepositoryDatabase,
+};
+use std::path::Path;
+
+/// Write a repository database at `repository` that registers each `(pkgname, version, arch)`
+/// as `<pkgname>-<version>-<arch>.pkg.tar.zst`.
+///
+/// The package files are neither read nor required to exist.
+pub fn write_database(repository: &Path, packages: &[(&str, &str, &str)]) {
+    let mut database = RepositoryDatabase::new();
+    for (pkgname, version, arch) in packages {
+        database.add(&PackageEntry {
+            file_name: format!("{}-{}-{}.pkg.tar.zst", pkgname, version, arch),
+            info: PackageInfo {
+                pkgname: pkgname.to_string(),
+                pkgbase: pkgname.to_string(),
+                pkgver: version.to_string(),
+                arch: arch.to_string(),
+                ..PackageInfo::default()
+            },
+            compressed_size: 0,
+            sha256sum: String::new(),
+            pgpsig: None,
+            files: Vec::new(),
+        });
+    }
+    database
+        .write(repository, false, None)
+        .expect("write repository database");
+}
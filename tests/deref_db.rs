@@ -0,0 +1,93 @@
+#![cfg(feature = "alpm")]
+
+use command_extra::CommandExtra;
+use pipe_trait::*;
+use std::{
+    fs::{create_dir, read_to_string, symlink_metadata, write, DirBuilder},
+    os::unix::fs::{symlink, DirBuilderExt},
+    path::Path,
+    process::{Command, Stdio},
+};
+use tempfile::tempdir;
+
+const EXE: &str = env!("CARGO_BIN_EXE_build-pacman-repo");
+
+fn gpg(gnupg_home: &Path) -> Command {
+    Command::new("gpg")
+        .with_env("GNUPGHOME", gnupg_home)
+        .with_arg("--batch")
+        .with_arg("--quiet")
+        .with_stdin(Stdio::null())
+}
+
+fn run(mut command: Command) {
+    let status = command.status().expect("spawn command");
+    assert!(status.success(), "{:?} exits with {:?}", command, status);
+}
+
+#[test]
+fn dereference_signatures() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    let gnupg_home = work_dir.join("gnupg");
+    let repo = work_dir.join("repo");
+    DirBuilder::new()
+        .mode(0o700)
+        .create(&gnupg_home)
+        .expect("create GNUPGHOME");
+    create_dir(&repo).expect("create repository directory");
+
+    write(
+        work_dir.join("build-pacman-repo.yaml"),
+        "global-settings:\n  repository: repo/repo.db.tar.gz\nmembers: []\n",
+    )
+    .expect("write manifest");
+
+    gpg(&gnupg_home)
+        .with_args(["--passphrase", "", "--quick-gen-key"])
+        .with_args(["test@example.com", "ed25519", "sign", "never"])
+        .pipe(run);
+
+    for name in ["repo.db", "repo.files"] {
+        let target = format!("{}.tar.gz", name);
+        write(repo.join(&target), format!("content of {}", target)).expect("write database");
+        gpg(&gnupg_home)
+            .with_arg("--detach-sign")
+            .with_arg(repo.join(&target))
+            .pipe(run);
+        symlink(&target, repo.join(name)).expect("link database");
+        symlink(
+            format!("{}.sig", target),
+            repo.join(format!("{}.sig", name)),
+        )
+        .expect("link signature");
+    }
+
+    Command::new(EXE)
+        .with_current_dir(work_dir)
+        .with_arg("deref-db")
+        .pipe(run);
+
+    for name in ["repo.db", "repo.files"] {
+        let target = format!("{}.tar.gz", name);
+        for file in [name.to_string(), format!("{}.sig", name)] {
+            let metadata = symlink_metadata(repo.join(&file)).expect("stat dereferenced file");
+            assert!(metadata.is_file(), "{} should be a regular file", file);
+        }
+        assert_eq!(
+            read_to_string(repo.join(name)).expect("read dereferenced database"),
+            format!("content of {}", target),
+        );
+        gpg(&gnupg_home)
+            .with_arg("--verify")
+            .with_arg(repo.join(format!("{}.sig", name)))
+            .with_arg(repo.join(name))
+            .pipe(run);
+    }
+
+    Command::new("gpgconf")
+        .with_env("GNUPGHOME", &gnupg_home)
+        .with_args(["--kill", "gpg-agent"])
+        .status()
+        .ok();
+}
@@ -1,14 +1,20 @@
 use pacman_repo_builder::{
     manifest::{
-        ArchFilter, BorrowedInner, BuildMetadata, BuildPacmanRepo, OwnedBuildPacmanRepo,
-        OwnedContainer, OwnedFailedBuildRecord, OwnedGlobalSettings, OwnedInitAurBuilder,
-        OwnedMember, TriState, Wrapper,
+        ArchFilter, BorrowedInner, BuildMetadata, BuildPacmanRepo, ManifestOptions,
+        OwnedBuildPacmanRepo, OwnedContainer, OwnedFailedBuildRecord, OwnedGlobalSettings,
+        OwnedInitAurBuilder, OwnedLogDirectory, OwnedMember, SettingOverride, TriState, Wrapper,
+        GLOBAL_SETTINGS_KEYS,
     },
     utils::{deserialize_multi_docs_yaml, serialize_iter_yaml},
 };
 use pipe_trait::*;
 use pretty_assertions::assert_eq;
-use std::path::PathBuf;
+use std::{
+    fs::{create_dir_all, write},
+    num::NonZeroUsize,
+    path::PathBuf,
+};
+use tempfile::tempdir;
 
 fn manifest_list_yaml() -> &'static str {
     include_str!("./assets/manifest-list.yaml").trim()
@@ -19,6 +25,11 @@ fn manifest_list() -> impl Iterator<Item = OwnedBuildPacmanRepo> {
         vec![
             OwnedMember {
                 directory: "foo".pipe(PathBuf::from).pipe(Wrapper::from_inner),
+                glob: None,
+                scan: None,
+                exclude: Vec::new(),
+                require_pkgbuild: None,
+                require_srcinfo: None,
                 read_build_metadata: None,
                 install_missing_dependencies: None,
                 clean_before_build: None,
@@ -30,6 +41,11 @@ fn manifest_list() -> impl Iterator<Item = OwnedBuildPacmanRepo> {
             },
             OwnedMember {
                 directory: "bar".pipe(PathBuf::from).pipe(Wrapper::from_inner),
+                glob: None,
+                scan: None,
+                exclude: Vec::new(),
+                require_pkgbuild: None,
+                require_srcinfo: None,
                 read_build_metadata: Some(BuildMetadata::PkgBuild),
                 install_missing_dependencies: None,
                 clean_before_build: Some(false),
@@ -41,6 +57,11 @@ fn manifest_list() -> impl Iterator<Item = OwnedBuildPacmanRepo> {
             },
             OwnedMember {
                 directory: "bar".pipe(PathBuf::from).pipe(Wrapper::from_inner),
+                glob: None,
+                scan: None,
+                exclude: Vec::new(),
+                require_pkgbuild: None,
+                require_srcinfo: None,
                 read_build_metadata: None,
                 install_missing_dependencies: Some(true),
                 clean_before_build: None,
@@ -52,6 +73,11 @@ fn manifest_list() -> impl Iterator<Item = OwnedBuildPacmanRepo> {
             },
             OwnedMember {
                 directory: "baz".pipe(PathBuf::from).pipe(Wrapper::from_inner),
+                glob: None,
+                scan: None,
+                exclude: Vec::new(),
+                require_pkgbuild: None,
+                require_srcinfo: None,
                 read_build_metadata: Some(BuildMetadata::SrcInfo),
                 install_missing_dependencies: Some(false),
                 clean_before_build: Some(true),
@@ -82,6 +108,13 @@ fn manifest_list() -> impl Iterator<Item = OwnedBuildPacmanRepo> {
             packager: None,
             allow_failure: None,
             dereference_database_symlinks: None,
+            log_directory: None,
+            sign_packages: None,
+            sign_database: None,
+            signing_key: None,
+            keep_versions: None,
+            protected_packages: None,
+            package_extension: None,
         },
         || OwnedGlobalSettings {
             container: "container"
@@ -103,6 +136,13 @@ fn manifest_list() -> impl Iterator<Item = OwnedBuildPacmanRepo> {
             packager: None,
             allow_failure: Some(true),
             dereference_database_symlinks: None,
+            log_directory: None,
+            sign_packages: None,
+            sign_database: None,
+            signing_key: None,
+            keep_versions: None,
+            protected_packages: None,
+            package_extension: None,
         },
         || OwnedGlobalSettings {
             container: None,
@@ -124,6 +164,13 @@ fn manifest_list() -> impl Iterator<Item = OwnedBuildPacmanRepo> {
             packager: Some("Bob <bob@example.com>".to_owned_wrapper()),
             allow_failure: None,
             dereference_database_symlinks: Some(false),
+            log_directory: None,
+            sign_packages: None,
+            sign_database: None,
+            signing_key: None,
+            keep_versions: None,
+            protected_packages: None,
+            package_extension: None,
         },
         || OwnedGlobalSettings {
             container: "container"
@@ -148,10 +195,21 @@ fn manifest_list() -> impl Iterator<Item = OwnedBuildPacmanRepo> {
             packager: Some("Bob <bob@example.com>".to_owned_wrapper()),
             allow_failure: Some(true),
             dereference_database_symlinks: Some(true),
+            log_directory: "logs"
+                .pipe(PathBuf::from)
+                .pipe(OwnedLogDirectory::from_inner)
+                .pipe(Some),
+            sign_packages: Some(true),
+            sign_database: Some(true),
+            signing_key: Some("0123456789ABCDEF".to_owned_wrapper()),
+            keep_versions: NonZeroUsize::new(3),
+            protected_packages: Some(["my-manual-package"].into_iter().collect()),
+            package_extension: Some(".pkg.tar.xz".to_owned_wrapper()),
         },
     ]
     .iter()
     .map(move |make_global_settings| BuildPacmanRepo {
+        include: Vec::new(),
         global_settings: make_global_settings(),
         members: make_members(),
     })
@@ -200,6 +258,506 @@ fn resolve_members() {
     assert_eq!(actual, expected);
 }
 
+#[test]
+fn expand_members() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    for directory in [
+        "explicit",
+        "pkgs/foo",
+        "pkgs/bar-git",
+        "pkgs/baz",
+        "pkgs/.hidden",
+        "aur/qux",
+        "aur/quux",
+    ] {
+        create_dir_all(work_dir.join(directory)).unwrap();
+    }
+    for directory in ["pkgs/foo", "pkgs/bar-git", "pkgs/.hidden"] {
+        write(work_dir.join(directory).join("PKGBUILD"), "").unwrap();
+    }
+    write(work_dir.join("pkgs/not-a-directory"), "").unwrap();
+    let manifest_file = work_dir.join("build-pacman-repo.yaml");
+    write(
+        &manifest_file,
+        [
+            "global-settings:",
+            "  repository: repo/repo.db.tar.gz",
+            "members:",
+            "  - directory: explicit",
+            "  - glob: pkgs/*",
+            "    exclude: ['*-git']",
+            "    require-pkgbuild: true",
+            "    force-rebuild: true",
+            "  - scan: aur",
+            "    allow-failure: true",
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+
+    let manifest =
+        OwnedBuildPacmanRepo::from_env(ManifestOptions::with_file(&manifest_file)).unwrap();
+    let actual: Vec<_> = manifest
+        .resolve_members()
+        .map(|member| {
+            (
+                member.directory.into_inner(),
+                member.force_rebuild,
+                member.allow_failure,
+            )
+        })
+        .collect();
+    let expected = vec![
+        (work_dir.join("explicit"), None, None),
+        (work_dir.join("pkgs/foo"), Some(true), None),
+        (work_dir.join("aur/quux"), None, Some(true)),
+        (work_dir.join("aur/qux"), None, Some(true)),
+    ];
+    assert_eq!(actual, expected);
+
+    write(
+        &manifest_file,
+        "global-settings:\n  repository: repo.db.tar.gz\nmembers:\n  - directory: foo\n    glob: '*'\n",
+    )
+    .unwrap();
+    let error =
+        OwnedBuildPacmanRepo::from_env(ManifestOptions::with_file(&manifest_file)).unwrap_err();
+    assert!(
+        error.ends_with(
+            "members: member cannot have more than one of directory, glob, and scan at line 4 column 3"
+        ),
+        "error: {}",
+        error,
+    );
+
+    write(
+        &manifest_file,
+        "global-settings:\n  repository: repo.db.tar.gz\nmembers:\n  - force-rebuild: true\n",
+    )
+    .unwrap();
+    let error = OwnedBuildPacmanRepo::from_file(&manifest_file).unwrap_err();
+    assert!(
+        error.ends_with("members: member needs one of directory, glob, or scan at line 4 column 3"),
+        "error: {}",
+        error,
+    );
+}
+
+#[test]
+fn include_fragments() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    for directory in [
+        "main",
+        "teams/a/pkgs/foo",
+        "teams/a/pkgs/bar-git",
+        "teams/b/baz",
+    ] {
+        create_dir_all(work_dir.join(directory)).unwrap();
+    }
+    let manifest_file = work_dir.join("build-pacman-repo.yaml");
+    let write_manifest = |members: &str| {
+        write(
+            &manifest_file,
+            format!(
+                "include:\n  - teams/a/fragment.yaml\n  - teams/b/fragment.yaml\nglobal-settings:\n  repository: repo/repo.db.tar.gz\n  allow-failure: false\nmembers:\n{}",
+                members,
+            ),
+        )
+        .unwrap();
+    };
+    write_manifest("  - directory: main\n");
+    write(
+        work_dir.join("teams/a/fragment.yaml"),
+        [
+            "global-settings:",
+            "  container: pkgs",
+            "  allow-failure: true",
+            "members:",
+            "  - directory: foo",
+            "  - glob: '*-git'",
+            "    allow-failure: false",
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+    write(
+        work_dir.join("teams/b/fragment.yaml"),
+        "members:\n  - directory: baz\n",
+    )
+    .unwrap();
+
+    let manifest =
+        OwnedBuildPacmanRepo::from_env(ManifestOptions::with_file(&manifest_file)).unwrap();
+    let actual: Vec<_> = manifest
+        .resolve_members()
+        .map(|member| (member.directory.into_inner(), member.allow_failure))
+        .collect();
+    let expected = vec![
+        (work_dir.join("main"), Some(false)),
+        (work_dir.join("teams/a/pkgs/foo"), Some(true)),
+        (work_dir.join("teams/a/pkgs/bar-git"), Some(false)),
+        (work_dir.join("teams/b/baz"), Some(false)),
+    ];
+    assert_eq!(actual, expected);
+
+    write_manifest("  - directory: main\n  - directory: teams/b/baz\n");
+    let error =
+        OwnedBuildPacmanRepo::from_env(ManifestOptions::with_file(&manifest_file)).unwrap_err();
+    let expected = format!(
+        "duplicate members detected\n  * directory: {}\n    - manifest: {}\n    - manifest: {}",
+        work_dir
+            .join("teams/b/baz")
+            .canonicalize()
+            .unwrap()
+            .to_string_lossy(),
+        manifest_file.to_string_lossy(),
+        work_dir.join("teams/b/fragment.yaml").to_string_lossy(),
+    );
+    assert_eq!(error, expected);
+
+    write(
+        work_dir.join("teams/b/fragment.yaml"),
+        "global-settings:\n  repository: other.db.tar.gz\nmembers: []\n",
+    )
+    .unwrap();
+    let error =
+        OwnedBuildPacmanRepo::from_env(ManifestOptions::with_file(&manifest_file)).unwrap_err();
+    assert!(
+        error.contains(
+            "teams/b/fragment.yaml\" as Fragment: global-settings: unknown field `repository`"
+        ),
+        "error: {}",
+        error,
+    );
+}
+
+#[test]
+fn fragment_precedence() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    for directory in ["main", "team/foo", "team/bar"] {
+        create_dir_all(work_dir.join(directory)).unwrap();
+    }
+    let manifest_file = work_dir.join("build-pacman-repo.yaml");
+    write(
+        &manifest_file,
+        [
+            "include:",
+            "  - team/fragment.yaml",
+            "global-settings:",
+            "  repository: repo/repo.db.tar.gz",
+            "  check: inherit",
+            "  force-rebuild: true",
+            "members:",
+            "  - directory: main",
+            "profiles:",
+            "  ci:",
+            "    global-settings:",
+            "      check: disabled",
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+    write(
+        work_dir.join("team/fragment.yaml"),
+        [
+            "global-settings:",
+            "  check: enabled",
+            "  force-rebuild: false",
+            "members:",
+            "  - directory: foo",
+            "  - directory: bar",
+            "    check: inherit",
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+
+    let load = |profile, overrides: &[&str]| {
+        let overrides: Vec<SettingOverride> =
+            overrides.iter().map(|text| text.parse().unwrap()).collect();
+        let options = ManifestOptions {
+            file: Some(&manifest_file),
+            overrides: &overrides,
+        };
+        OwnedBuildPacmanRepo::from_env_with_profile(options, profile)
+            .unwrap()
+            .resolve_members()
+            .map(|member| (member.check, member.force_rebuild))
+            .collect::<Vec<_>>()
+    };
+
+    // Settings of the fragment take precedence over the ones of the including manifest.
+    assert_eq!(
+        load(None, &[]),
+        [
+            (Some(TriState::Inherit), Some(true)),
+            (Some(TriState::Enabled), Some(false)),
+            (Some(TriState::Inherit), Some(false)),
+        ],
+    );
+
+    // Profiles and overrides take precedence over the settings of the fragment, but not over the fields of members.
+    assert_eq!(
+        load(Some("ci"), &["force-rebuild=true"]),
+        [
+            (Some(TriState::Disabled), Some(true)),
+            (Some(TriState::Disabled), Some(true)),
+            (Some(TriState::Inherit), Some(true)),
+        ],
+    );
+}
+
+#[test]
+fn apply_profile() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    let manifest_file = work_dir.join("build-pacman-repo.yaml");
+    write(
+        &manifest_file,
+        [
+            "global-settings:",
+            "  repository: repo/repo.db.tar.gz",
+            "  check: enabled",
+            "  force-rebuild: false",
+            "members:",
+            "  - directory: foo",
+            "  - directory: foo-git",
+            "  - directory: bar",
+            "    check: inherit",
+            "profiles:",
+            "  ci:",
+            "    global-settings:",
+            "      check: disabled",
+            "      allow-failure: true",
+            "    members:",
+            "      exclude: ['*-git']",
+            "  release:",
+            "    global-settings:",
+            "      force-rebuild: true",
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+
+    let load = |profile| {
+        OwnedBuildPacmanRepo::from_env_with_profile(
+            ManifestOptions::with_file(&manifest_file),
+            profile,
+        )
+        .unwrap()
+        .resolve_members()
+        .map(|member| {
+            (
+                member.directory.into_inner(),
+                member.check,
+                member.force_rebuild,
+                member.allow_failure,
+            )
+        })
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        load(Some("ci")),
+        vec![
+            (
+                work_dir.join("foo"),
+                Some(TriState::Disabled),
+                Some(false),
+                Some(true)
+            ),
+            (
+                work_dir.join("bar"),
+                Some(TriState::Inherit),
+                Some(false),
+                Some(true)
+            ),
+        ],
+    );
+    assert_eq!(
+        load(Some("release")),
+        vec![
+            (
+                work_dir.join("foo"),
+                Some(TriState::Enabled),
+                Some(true),
+                None
+            ),
+            (
+                work_dir.join("foo-git"),
+                Some(TriState::Enabled),
+                Some(true),
+                None
+            ),
+            (
+                work_dir.join("bar"),
+                Some(TriState::Inherit),
+                Some(true),
+                None
+            ),
+        ],
+    );
+    assert_eq!(load(None), {
+        vec![
+            (
+                work_dir.join("foo"),
+                Some(TriState::Enabled),
+                Some(false),
+                None,
+            ),
+            (
+                work_dir.join("foo-git"),
+                Some(TriState::Enabled),
+                Some(false),
+                None,
+            ),
+            (
+                work_dir.join("bar"),
+                Some(TriState::Inherit),
+                Some(false),
+                None,
+            ),
+        ]
+    });
+
+    let error = OwnedBuildPacmanRepo::from_env_with_profile(
+        ManifestOptions::with_file(&manifest_file),
+        Some("nightly"),
+    )
+    .unwrap_err();
+    assert_eq!(
+        error,
+        format!("{:?}: profile \"nightly\" is not defined", manifest_file),
+    );
+}
+
+#[test]
+fn invalid_profile_settings() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    let manifest_file = work_dir.join("build-pacman-repo.yaml");
+    write(
+        &manifest_file,
+        [
+            "global-settings:",
+            "  repository: repo/repo.db.tar.gz",
+            "members:",
+            "  - directory: foo",
+            "profiles:",
+            "  unknown-key:",
+            "    global-settings:",
+            "      not-a-setting: true",
+            "  wrong-type:",
+            "    global-settings:",
+            "      allow-failure: sometimes",
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+
+    let load = |profile| {
+        OwnedBuildPacmanRepo::from_env_with_profile(
+            ManifestOptions::with_file(&manifest_file),
+            Some(profile),
+        )
+        .unwrap_err()
+    };
+
+    let error = load("unknown-key");
+    assert!(error.starts_with(&format!(
+        "{:?}: profile: global-settings.not-a-setting: unknown global setting \"not-a-setting\", expected one of ",
+        manifest_file,
+    )));
+
+    let error = load("wrong-type");
+    assert!(error.starts_with(&format!(
+        "{:?}: profile: global-settings.allow-failure: ",
+        manifest_file,
+    )));
+}
+
+#[test]
+fn override_settings() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    let manifest_file = work_dir.join("build-pacman-repo.yaml");
+    write(
+        &manifest_file,
+        [
+            "global-settings:",
+            "  repository: repo/repo.db.tar.gz",
+            "  check: enabled",
+            "members:",
+            "  - directory: foo",
+            "profiles:",
+            "  ci:",
+            "    global-settings:",
+            "      check: disabled",
+            "      force-rebuild: false",
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+
+    let load = |overrides: &[&str]| {
+        let overrides: Vec<SettingOverride> =
+            overrides.iter().map(|text| text.parse().unwrap()).collect();
+        let options = ManifestOptions {
+            file: Some(&manifest_file),
+            overrides: &overrides,
+        };
+        OwnedBuildPacmanRepo::from_env_with_profile(options, Some("ci"))
+    };
+
+    let manifest = load(&[
+        "check=inherit",
+        "arch-filter=[x86_64]",
+        "repository=other.db.tar.gz",
+    ])
+    .unwrap();
+    let member = manifest.resolve_members().next().unwrap();
+    assert_eq!(member.check, Some(TriState::Inherit));
+    assert_eq!(member.force_rebuild, Some(false));
+    assert_eq!(
+        manifest.global_settings.repository.as_ref(),
+        work_dir.join("other.db.tar.gz"),
+    );
+    assert_eq!(
+        manifest.global_settings.arch_filter,
+        Some(ArchFilter::Selective(
+            vec!["x86_64".to_string()].pipe(Wrapper::from_inner)
+        )),
+    );
+
+    assert_eq!(
+        load(&["keep-versions=0"]).unwrap_err(),
+        "--set keep-versions=0: invalid value: integer `0`, expected a nonzero usize",
+    );
+    assert!(load(&["keep-version=1"])
+        .unwrap_err()
+        .starts_with("--set keep-version=1: unknown global setting \"keep-version\", expected one of repository, container,"));
+}
+
+#[test]
+fn global_settings_keys() {
+    let manifest_list: Vec<serde_yaml::Value> = manifest_list_yaml()
+        .pipe(deserialize_multi_docs_yaml)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let actual: Vec<_> = manifest_list
+        .last()
+        .and_then(|manifest| manifest.get("global-settings"))
+        .and_then(serde_yaml::Value::as_mapping)
+        .unwrap()
+        .keys()
+        .map(|key| key.as_str().unwrap())
+        .collect();
+    assert_eq!(actual, GLOBAL_SETTINGS_KEYS);
+}
+
 fn init_aur_builder() -> OwnedInitAurBuilder {
     OwnedInitAurBuilder::default()
         .with_global_settings(OwnedGlobalSettings {
@@ -0,0 +1,175 @@
+#![cfg(feature = "alpm")]
+
+use command_extra::CommandExtra;
+use pacman_repo_builder::repository_database::RepositoryDatabase;
+use std::{
+    ffi::OsStr,
+    fs::{create_dir, create_dir_all, write},
+    path::Path,
+    process::{Command, Output},
+};
+use tempfile::tempdir;
+
+const EXE: &str = env!("CARGO_BIN_EXE_build-pacman-repo");
+
+const MANIFEST: &str = "global-settings:\n  repository: repo/repo.db.tar.gz\nmembers: []\n";
+
+fn setup(directory: &Path, manifest_name: &str) {
+    let repo = directory.join("repo");
+    create_dir_all(&repo).expect("create repository directory");
+    write(directory.join(manifest_name), MANIFEST).expect("write manifest");
+    RepositoryDatabase::new()
+        .write(&repo.join("repo.db.tar.gz"), false, None)
+        .expect("write repository database");
+}
+
+fn verify_repo(work_dir: &Path, global_args: &[&OsStr], envs: &[(&str, &str)]) -> Output {
+    let mut command = Command::new(EXE)
+        .with_current_dir(work_dir)
+        .with_args(global_args)
+        .with_arg("verify-repo");
+    command.envs(envs.iter().copied());
+    let output = command.output().expect("run verify-repo");
+    eprintln!("{}", String::from_utf8_lossy(&output.stderr));
+    output
+}
+
+#[test]
+fn search_parent_directories() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    setup(work_dir, "build-pacman-repo.yaml");
+    let subdirectory = work_dir.join("a").join("b");
+    create_dir_all(&subdirectory).expect("create subdirectory");
+    write(work_dir.join("repo/foo-1-1-any.pkg.tar.zst"), "").expect("write package file");
+
+    let output = verify_repo(&subdirectory, &[], &[]);
+    assert_eq!(output.status.code(), Some(8));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("\"../../repo/foo-1-1-any.pkg.tar.zst\" is not in the database"),
+        "stderr: {}",
+        stderr,
+    );
+}
+
+#[test]
+fn manifest_option() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    let elsewhere = work_dir.join("elsewhere");
+    setup(&elsewhere, "custom.yaml");
+    let cwd = work_dir.join("cwd");
+    create_dir(&cwd).expect("create current directory");
+
+    let manifest = elsewhere.join("custom.yaml");
+    let output = verify_repo(&cwd, &["--manifest".as_ref(), manifest.as_ref()], &[]);
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn missing_manifest() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let output = verify_repo(work_dir.path(), &[], &[]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("cannot find build-pacman-repo.yaml in"),
+        "stderr: {}",
+        stderr,
+    );
+}
+
+#[test]
+fn override_settings() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    setup(work_dir, "build-pacman-repo.yaml");
+    let elsewhere = work_dir.join("elsewhere");
+    create_dir(&elsewhere).expect("create directory");
+
+    // The database is empty, so verify-repo complains about the package file next to it.
+    write(elsewhere.join("foo-1-1-any.pkg.tar.zst"), "").expect("write package file");
+    let set_elsewhere: &[&OsStr] = &[
+        "--set".as_ref(),
+        "repository=elsewhere/repo.db.tar.gz".as_ref(),
+    ];
+    let env_elsewhere = ("PACMAN_REPO_BUILDER_REPOSITORY", "elsewhere/repo.db.tar.gz");
+    let env_repo = ("PACMAN_REPO_BUILDER_REPOSITORY", "repo/repo.db.tar.gz");
+    RepositoryDatabase::new()
+        .write(&elsewhere.join("repo.db.tar.gz"), false, None)
+        .expect("write repository database");
+
+    assert_eq!(verify_repo(work_dir, &[], &[]).status.code(), Some(0));
+    assert_eq!(
+        verify_repo(work_dir, set_elsewhere, &[]).status.code(),
+        Some(8)
+    );
+    assert_eq!(
+        verify_repo(work_dir, &[], &[env_elsewhere]).status.code(),
+        Some(8)
+    );
+    assert_eq!(
+        verify_repo(work_dir, &[], &[env_repo]).status.code(),
+        Some(0)
+    );
+    assert_eq!(
+        verify_repo(work_dir, set_elsewhere, &[env_repo])
+            .status
+            .code(),
+        Some(8)
+    );
+
+    let output = verify_repo(
+        work_dir,
+        &[],
+        &[("PACMAN_REPO_BUILDER_FORCE_REBUILD", "yes")],
+    );
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "PACMAN_REPO_BUILDER_FORCE_REBUILD: invalid type: string \"yes\", expected a boolean"
+        ),
+        "stderr: {}",
+        stderr,
+    );
+
+    let output = verify_repo(
+        work_dir,
+        &["--set".as_ref(), "no-such-setting=1".as_ref()],
+        &[],
+    );
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--set no-such-setting=1: unknown global setting \"no-such-setting\""),
+        "stderr: {}",
+        stderr,
+    );
+}
+
+#[test]
+fn invalid_package_extension() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    setup(work_dir, "build-pacman-repo.yaml");
+
+    for command in ["outdated", "prune", "verify-repo"] {
+        let output = Command::new(EXE)
+            .with_current_dir(work_dir)
+            .with_args(&["--set", "package-extension=.tar.gz"])
+            .with_arg(command)
+            .output()
+            .expect("run build-pacman-repo");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        eprintln!("{}", stderr);
+        assert_eq!(output.status.code(), Some(2), "{}", command);
+        assert!(
+            stderr.contains("package-extension must be .pkg.tar followed by"),
+            "{}: {}",
+            command,
+            stderr,
+        );
+    }
+}
@@ -1,6 +1,10 @@
+#![cfg(feature = "alpm")]
+
+mod common;
+
 use command_extra::CommandExtra;
 use pipe_trait::*;
-use std::{fs, path::PathBuf, process::Command};
+use std::{fs, path::PathBuf, process::Command, sync::Once};
 
 const EXE: &str = env!("CARGO_BIN_EXE_build-pacman-repo");
 const ROOT: &str = env!("CARGO_MANIFEST_DIR");
@@ -13,41 +17,57 @@ fn work_dir(branch: &'static str) -> PathBuf {
         .join(branch)
 }
 
+/// Packages in the repository database and their versions.
+const DATABASE: &[(&str, &str, &str)] = &[
+    ("downgraded", "2.0.0-1", "any"),
+    ("out-of-date-by-arch", "1.2.3-1", "x86_64"),
+    ("out-of-date-by-epoch", "1:1.2.3-1", "any"),
+    ("out-of-date-by-pkgrel", "1.2.3-2", "any"),
+    ("up-to-date-pkgbuild", "1.2.3-1", "any"),
+    ("up-to-date-srcinfo", "1.2.3-1", "any"),
+];
+
 fn setup_test_files() {
-    let base_dir = ROOT
-        .pipe(PathBuf::from)
-        .join("tests")
-        .join("fixtures")
-        .join("outdated")
-        .join("__template__")
-        .join("repo");
-    let files = vec![
-        "out-of-date-by-arch-1.2.3-1-i686.pkg.tar.zst",
-        "out-of-date-by-arch-1.2.3-1-x86_64.pkg.tar.zst",
-        "out-of-date-by-epoch-1.2.3-1-any.pkg.tar.zst",
-        "out-of-date-by-epoch-1:-1.2.3-1-any.pkg.tar.zst",
-        "out-of-date-by-epoch-2:-1.2.3-1-any.pkg.tar.zst",
-        "out-of-date-by-pkgrel-0.0.0-1.pkg.tar.zst",
-        "out-of-date-by-pkgrel-0.0.1-1.pkg.tar.zst",
-        "out-of-date-by-pkgrel-0.1.0-1.pkg.tar.zst",
-        "out-of-date-by-pkgrel-1.0.0-1.pkg.tar.zst",
-        "out-of-date-by-pkgrel-1.2.3-1.pkg.tar.zst",
-        "out-of-date-by-pkgrel-1.2.3-2.pkg.tar.zst",
-        "repo.db",
-        "up-to-date-pkgbuild-1.2.3-1-any.pkg.tar.zst",
-        "up-to-date-srcinfo-1.2.3-1-any.pkg.tar.zst",
-    ];
-
-    // Create the base directory if it doesn't exist
-    if !base_dir.exists() {
-        fs::create_dir_all(&base_dir).expect("Failed to create base directory");
-    }
+    // Tests share the same repository directory, so it is only set up once.
+    static SETUP: Once = Once::new();
+    SETUP.call_once(|| {
+        let base_dir = ROOT
+            .pipe(PathBuf::from)
+            .join("tests")
+            .join("fixtures")
+            .join("outdated")
+            .join("__template__")
+            .join("repo");
+        let files = vec![
+            "downgraded-2.0.0-1-any.pkg.tar.zst",
+            "out-of-date-by-arch-1.2.3-1-i686.pkg.tar.zst",
+            "out-of-date-by-arch-1.2.3-1-x86_64.pkg.tar.zst",
+            "out-of-date-by-epoch-1.2.3-1-any.pkg.tar.zst",
+            "out-of-date-by-epoch-1:-1.2.3-1-any.pkg.tar.zst",
+            "out-of-date-by-epoch-2:-1.2.3-1-any.pkg.tar.zst",
+            "out-of-date-by-pkgrel-0.0.0-1.pkg.tar.zst",
+            "out-of-date-by-pkgrel-0.0.1-1.pkg.tar.zst",
+            "out-of-date-by-pkgrel-0.1.0-1.pkg.tar.zst",
+            "out-of-date-by-pkgrel-1.0.0-1.pkg.tar.zst",
+            "out-of-date-by-pkgrel-1.2.3-1.pkg.tar.zst",
+            "out-of-date-by-pkgrel-1.2.3-2.pkg.tar.zst",
+            "up-to-date-pkgbuild-1.2.3-1-any.pkg.tar.zst",
+            "up-to-date-srcinfo-1.2.3-1-any.pkg.tar.zst",
+        ];
+
+        // Create the base directory if it doesn't exist
+        if !base_dir.exists() {
+            fs::create_dir_all(&base_dir).expect("Failed to create base directory");
+        }
 
-    // Create the files
-    for file in files {
-        let file_path = base_dir.join(file);
-        fs::File::create(file_path).expect("Failed to create file");
-    }
+        // Create the files
+        for file in files {
+            let file_path = base_dir.join(file);
+            fs::File::create(file_path).expect("Failed to create file");
+        }
+
+        common::write_database(&base_dir.join("repo.db.tar.gz"), DATABASE);
+    });
 }
 
 fn init(branch: &'static str) -> Command {
@@ -1,3 +1,5 @@
+#![cfg(feature = "alpm")]
+
 use command_extra::CommandExtra;
 use pacman_repo_builder::utils::MAKEPKG_PATCHES;
 use pipe_trait::*;
@@ -1,3 +1,5 @@
+#![cfg(feature = "alpm")]
+
 use command_extra::CommandExtra;
 use pipe_trait::*;
 use std::{path::PathBuf, process::Command};
@@ -0,0 +1,119 @@
+#![cfg(feature = "alpm")]
+
+mod common;
+
+use command_extra::CommandExtra;
+use pipe_trait::*;
+use std::{
+    fs::{create_dir, read_dir, write},
+    path::Path,
+    process::Command,
+};
+use tempfile::tempdir;
+
+const EXE: &str = env!("CARGO_BIN_EXE_build-pacman-repo");
+
+const CURRENT: &[(&str, &str, &str)] = &[("foo", "1.2.0-1", "x86_64"), ("bar", "2-1", "any")];
+
+const PACKAGE_FILES: &[&str] = &[
+    "foo-1.0.0-1-x86_64.pkg.tar.zst",
+    "foo-1.0.0-1-x86_64.pkg.tar.zst.sig",
+    "foo-1.1.0-1-x86_64.pkg.tar.zst",
+    "foo-1.1.0-1-x86_64.pkg.tar.zst.sig",
+    "foo-1.2.0-1-x86_64.pkg.tar.zst",
+    "foo-1.2.0-1-x86_64.pkg.tar.zst.sig",
+    "bar-1-1-any.pkg.tar.zst",
+    "bar-2-1-any.pkg.tar.zst",
+    "unregistered-1-1-any.pkg.tar.zst",
+];
+
+fn setup(work_dir: &Path, keep_versions: Option<usize>) {
+    let repo = work_dir.join("repo");
+    create_dir(&repo).expect("create repository directory");
+
+    let keep_versions = keep_versions
+        .map(|count| format!("  keep-versions: {}\n", count))
+        .unwrap_or_default();
+    write(
+        work_dir.join("build-pacman-repo.yaml"),
+        format!(
+            "global-settings:\n  repository: repo/repo.db.tar.gz\n{}members: []\n",
+            keep_versions,
+        ),
+    )
+    .expect("write manifest");
+
+    for file_name in PACKAGE_FILES {
+        write(repo.join(file_name), file_name).expect("write package file");
+    }
+
+    common::write_database(&repo.join("repo.db.tar.gz"), CURRENT);
+}
+
+fn prune(work_dir: &Path, args: &[&str]) {
+    let output = Command::new(EXE)
+        .with_current_dir(work_dir)
+        .with_arg("prune")
+        .with_args(args)
+        .output()
+        .expect("run prune");
+    eprintln!("{}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success(), "prune should succeed");
+}
+
+fn remaining(work_dir: &Path) -> Vec<String> {
+    let mut files: Vec<_> = work_dir
+        .join("repo")
+        .pipe(read_dir)
+        .expect("read repository directory")
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|file_name| !file_name.starts_with("repo."))
+        .collect();
+    files.sort();
+    files
+}
+
+fn sorted(files: &[&str]) -> Vec<String> {
+    let mut files: Vec<_> = files.iter().map(ToString::to_string).collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn dry_run() {
+    let work_dir = tempdir().expect("create temporary directory");
+    setup(work_dir.path(), None);
+    prune(work_dir.path(), &["--dry-run"]);
+    assert_eq!(remaining(work_dir.path()), sorted(PACKAGE_FILES));
+}
+
+#[test]
+fn keep_current_versions() {
+    let work_dir = tempdir().expect("create temporary directory");
+    setup(work_dir.path(), None);
+    prune(work_dir.path(), &[]);
+    let expected = sorted(&[
+        "foo-1.2.0-1-x86_64.pkg.tar.zst",
+        "foo-1.2.0-1-x86_64.pkg.tar.zst.sig",
+        "bar-2-1-any.pkg.tar.zst",
+        "unregistered-1-1-any.pkg.tar.zst",
+    ]);
+    assert_eq!(remaining(work_dir.path()), expected);
+}
+
+#[test]
+fn keep_versions_setting() {
+    let work_dir = tempdir().expect("create temporary directory");
+    setup(work_dir.path(), Some(2));
+    prune(work_dir.path(), &[]);
+    let expected = sorted(&[
+        "foo-1.1.0-1-x86_64.pkg.tar.zst",
+        "foo-1.1.0-1-x86_64.pkg.tar.zst.sig",
+        "foo-1.2.0-1-x86_64.pkg.tar.zst",
+        "foo-1.2.0-1-x86_64.pkg.tar.zst.sig",
+        "bar-1-1-any.pkg.tar.zst",
+        "bar-2-1-any.pkg.tar.zst",
+        "unregistered-1-1-any.pkg.tar.zst",
+    ]);
+    assert_eq!(remaining(work_dir.path()), expected);
+}
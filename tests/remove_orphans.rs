@@ -0,0 +1,140 @@
+#![cfg(feature = "alpm")]
+
+mod common;
+
+use command_extra::CommandExtra;
+use pacman_repo_builder::repository_database::RepositoryDatabase;
+use pipe_trait::*;
+use std::{
+    fs::{create_dir, read_dir, write},
+    path::Path,
+    process::Command,
+};
+use tempfile::tempdir;
+
+const EXE: &str = env!("CARGO_BIN_EXE_build-pacman-repo");
+
+const REGISTERED: &[&str] = &["foo", "removed-member", "manually-added"];
+
+fn setup(work_dir: &Path) {
+    let repo = work_dir.join("repo");
+    create_dir(&repo).expect("create repository directory");
+
+    write(
+        work_dir.join("build-pacman-repo.yaml"),
+        concat!(
+            "global-settings:\n",
+            "  repository: repo/repo.db.tar.gz\n",
+            "  protected-packages:\n",
+            "  - manually-added\n",
+            "members:\n",
+            "- directory: foo\n",
+        ),
+    )
+    .expect("write manifest");
+
+    create_dir(work_dir.join("foo")).expect("create member directory");
+    write(
+        work_dir.join("foo").join(".SRCINFO"),
+        "pkgbase = foo\n\tpkgver = 1\n\tpkgrel = 1\n\tarch = any\n\npkgname = foo\n",
+    )
+    .expect("write srcinfo");
+
+    let mut packages = Vec::new();
+    for pkgname in REGISTERED {
+        let file_name = format!("{}-1-1-any.pkg.tar.zst", pkgname);
+        write(repo.join(&file_name), &file_name).expect("write package file");
+        write(repo.join(format!("{}.sig", file_name)), &file_name).expect("write signature file");
+        packages.push((*pkgname, "1-1", "any"));
+    }
+    common::write_database(&repo.join("repo.db.tar.gz"), &packages);
+}
+
+fn remove_orphans(work_dir: &Path, args: &[&str]) -> Vec<String> {
+    let output = Command::new(EXE)
+        .with_current_dir(work_dir)
+        .with_arg("remove-orphans")
+        .with_args(args)
+        .output()
+        .expect("run remove-orphans");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    eprintln!("{}", stderr);
+    assert!(output.status.success(), "remove-orphans should succeed");
+    stderr
+        .lines()
+        .filter_map(|line| line.strip_prefix("  ● "))
+        .map(ToString::to_string)
+        .collect()
+}
+
+fn package_files(work_dir: &Path) -> Vec<String> {
+    let mut files: Vec<_> = work_dir
+        .join("repo")
+        .pipe(read_dir)
+        .expect("read repository directory")
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|file_name| !file_name.starts_with("repo."))
+        .collect();
+    files.sort();
+    files
+}
+
+fn database_packages(work_dir: &Path) -> Vec<String> {
+    let mut packages: Vec<_> = RepositoryDatabase::load(&work_dir.join("repo/repo.db.tar.gz"))
+        .expect("load repository database")
+        .records()
+        .values()
+        .filter_map(|record| record.name())
+        .map(ToString::to_string)
+        .collect();
+    packages.sort();
+    packages
+}
+
+#[test]
+fn dry_run() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    setup(work_dir);
+    let files_before = package_files(work_dir);
+
+    let orphans = remove_orphans(work_dir, &["--dry-run"]);
+    assert_eq!(
+        orphans,
+        ["removed-member (removed-member-1-1-any.pkg.tar.zst)"]
+    );
+
+    assert_eq!(package_files(work_dir), files_before);
+    assert_eq!(files_before.len(), REGISTERED.len() * 2);
+    assert_eq!(
+        database_packages(work_dir),
+        ["foo", "manually-added", "removed-member"],
+    );
+}
+
+#[test]
+fn remove() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    setup(work_dir);
+
+    let orphans = remove_orphans(work_dir, &[]);
+    assert_eq!(
+        orphans,
+        ["removed-member (removed-member-1-1-any.pkg.tar.zst)"]
+    );
+
+    assert_eq!(
+        package_files(work_dir),
+        [
+            "foo-1-1-any.pkg.tar.zst",
+            "foo-1-1-any.pkg.tar.zst.sig",
+            "manually-added-1-1-any.pkg.tar.zst",
+            "manually-added-1-1-any.pkg.tar.zst.sig",
+        ],
+    );
+    assert_eq!(database_packages(work_dir), ["foo", "manually-added"]);
+
+    let orphans = remove_orphans(work_dir, &[]);
+    assert_eq!(orphans, Vec::<String>::new());
+}
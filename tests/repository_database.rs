@@ -0,0 +1,287 @@
+#![cfg(feature = "alpm")]
+
+use command_extra::CommandExtra;
+use pacman_repo_builder::repository_database::{package_entry::PackageEntry, RepositoryDatabase};
+use pipe_trait::*;
+use pretty_assertions::assert_eq;
+use std::{
+    fs::{create_dir, metadata, read_dir, read_link, read_to_string},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+use tempfile::tempdir;
+
+const ROOT: &str = env!("CARGO_MANIFEST_DIR");
+const EXE: &str = env!("CARGO_BIN_EXE_build-pacman-repo");
+
+/// Fixture directory and the name of the package file made from it.
+const PACKAGES: &[(&str, &str)] = &[
+    ("foo", "foo-1.0.0-1-x86_64.pkg.tar.zst"),
+    ("bar", "bar-1:2-1-any.pkg.tar.zst"),
+];
+
+fn fixtures() -> PathBuf {
+    ROOT.pipe(PathBuf::from)
+        .join("tests")
+        .join("fixtures")
+        .join("repository-database")
+}
+
+fn expected_output() -> PathBuf {
+    ROOT.pipe(PathBuf::from)
+        .join("tests")
+        .join("expected-output")
+        .join("repository-database")
+}
+
+fn run(mut command: Command) {
+    let status = command.status().expect("spawn command");
+    assert!(status.success(), "{:?} exits with {:?}", command, status);
+}
+
+fn make_packages(directory: &Path) -> Vec<PathBuf> {
+    PACKAGES
+        .iter()
+        .map(|(fixture, file_name)| {
+            let package_file = directory.join(file_name);
+            Command::new("bsdtar")
+                .with_arg("--create")
+                .with_arg("--zstd")
+                .with_arg("--file")
+                .with_arg(&package_file)
+                .with_arg("--directory")
+                .with_arg(fixtures().join(fixture))
+                .with_arg(".PKGINFO")
+                .with_arg("usr")
+                .pipe(run);
+            package_file
+        })
+        .collect()
+}
+
+/// Extract `archive` and read every file in it, sorted by path.
+fn extract(archive: &Path) -> Vec<(String, String)> {
+    let destination = tempdir().expect("create temporary directory");
+    Command::new("bsdtar")
+        .with_arg("--extract")
+        .with_arg("--file")
+        .with_arg(archive)
+        .with_arg("--directory")
+        .with_arg(destination.path())
+        .pipe(run);
+    let mut files = Vec::new();
+    for entry in read_dir(destination.path()).expect("read extracted archive") {
+        let entry = entry.expect("read entry");
+        let directory = entry.file_name().into_string().unwrap();
+        for file in read_dir(entry.path()).expect("read database entry") {
+            let file = file.expect("read entry");
+            let name = format!("{}/{}", directory, file.file_name().to_string_lossy());
+            let content = read_to_string(file.path()).expect("read file");
+            files.push((name, content));
+        }
+    }
+    files.sort();
+    files
+}
+
+fn sha256sum(file: &Path) -> String {
+    let output = Command::new("sha256sum")
+        .with_arg(file)
+        .with_stdin(Stdio::null())
+        .output()
+        .expect("run sha256sum");
+    assert!(output.status.success(), "sha256sum should succeed");
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .split_whitespace()
+        .next()
+        .unwrap()
+        .to_string()
+}
+
+/// Content that `repo-add` would write for the fixture packages in `directory`.
+fn expected_files(directory: &Path, with_files: bool) -> Vec<(String, String)> {
+    let mut files = Vec::new();
+    for (_, file_name) in PACKAGES {
+        let package_file = directory.join(file_name);
+        let csize = metadata(&package_file).unwrap().len().to_string();
+        let sha256sum = sha256sum(&package_file);
+        let entry = file_name
+            .trim_end_matches(".pkg.tar.zst")
+            .rsplit_once('-')
+            .unwrap()
+            .0;
+        let desc = expected_output()
+            .join(entry)
+            .join("desc")
+            .pipe(read_to_string)
+            .unwrap()
+            .replace("@CSIZE@", &csize)
+            .replace("@SHA256SUM@", &sha256sum);
+        files.push((format!("{}/desc", entry), desc));
+        if with_files {
+            let content = read_to_string(expected_output().join(entry).join("files")).unwrap();
+            files.push((format!("{}/files", entry), content));
+        }
+    }
+    files.sort();
+    files
+}
+
+fn write_database(packages: &[PathBuf], repository: &Path) -> RepositoryDatabase {
+    let mut database = RepositoryDatabase::new();
+    for package in packages {
+        database.add(&PackageEntry::from_package_file(package).expect("read package file"));
+    }
+    database
+        .write(repository, false, None)
+        .expect("write repository database");
+    database
+}
+
+#[test]
+fn write_like_repo_add() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    let packages = make_packages(work_dir);
+    let repository = work_dir.join("repo.db.tar.gz");
+    let database = write_database(&packages, &repository);
+
+    assert_eq!(
+        extract(&work_dir.join("repo.db.tar.gz")),
+        expected_files(work_dir, false),
+    );
+    assert_eq!(
+        extract(&work_dir.join("repo.files.tar.gz")),
+        expected_files(work_dir, true),
+    );
+    assert_eq!(
+        read_link(work_dir.join("repo.db")).expect("read link"),
+        PathBuf::from("repo.db.tar.gz"),
+    );
+    assert_eq!(
+        read_link(work_dir.join("repo.files")).expect("read link"),
+        PathBuf::from("repo.files.tar.gz"),
+    );
+
+    let loaded = RepositoryDatabase::load(&repository).expect("load repository database");
+    assert_eq!(loaded, database);
+}
+
+#[test]
+fn write_with_every_compression() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    let packages = make_packages(work_dir);
+    for extension in [".tar", ".tar.gz", ".tar.xz", ".tar.zst"] {
+        let repository = work_dir.join(format!("repo.db{}", extension));
+        let database = write_database(&packages, &repository);
+        assert_eq!(
+            extract(&work_dir.join(format!("repo.files{}", extension))),
+            expected_files(work_dir, true),
+        );
+        let loaded = RepositoryDatabase::load(&repository).expect("load repository database");
+        assert_eq!(loaded, database);
+    }
+
+    let error = RepositoryDatabase::new()
+        .write(&work_dir.join("repo.db.tar.bz2"), false, None)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.starts_with("Unsupported archive "),
+        "error: {}",
+        error
+    );
+}
+
+#[test]
+#[ignore = "requires repo-add, run with --ignored"]
+fn compare_with_repo_add() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    let native = work_dir.join("native");
+    let reference = work_dir.join("repo-add");
+    create_dir(&native).unwrap();
+    create_dir(&reference).unwrap();
+    let packages = make_packages(work_dir);
+
+    write_database(&packages, &native.join("repo.db.tar.gz"));
+    Command::new("repo-add")
+        .with_arg("--quiet")
+        .with_arg("--nocolor")
+        .with_arg(reference.join("repo.db.tar.gz"))
+        .with_args(&packages)
+        .pipe(run);
+
+    for archive in ["repo.db.tar.gz", "repo.files.tar.gz"] {
+        assert_eq!(
+            extract(&native.join(archive)),
+            extract(&reference.join(archive)),
+        );
+    }
+}
+
+fn read_dir_names(directory: &Path) -> Vec<String> {
+    let mut names: Vec<_> = read_dir(directory)
+        .expect("read directory")
+        .map(|entry| {
+            entry
+                .expect("read entry")
+                .file_name()
+                .into_string()
+                .unwrap()
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+fn list(repository: &Path, format: &str) -> String {
+    let output = Command::new(EXE)
+        .with_arg("list")
+        .with_arg("--repository")
+        .with_arg(repository)
+        .with_arg("--format")
+        .with_arg(format)
+        .output()
+        .expect("run list");
+    eprintln!("{}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success(), "list should succeed");
+    String::from_utf8(output.stdout).expect("read stdout")
+}
+
+#[test]
+fn list_packages() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    let packages = make_packages(work_dir);
+    let repository = work_dir.join("repo.db.tar.gz");
+    write_database(&packages, &repository);
+    let files_before = read_dir_names(work_dir);
+
+    assert_eq!(list(&repository, "text"), "bar 1:2-1\nfoo 1.0.0-1\n");
+    assert_eq!(read_dir_names(work_dir), files_before);
+
+    let yaml = list(&repository, "yaml");
+    assert!(yaml.starts_with("---\nname: bar\n"), "yaml: {}", yaml);
+    assert!(yaml.contains("\n---\nname: foo\n"), "yaml: {}", yaml);
+    assert!(
+        yaml.contains("\nprovides:\n- libbar.so=1-64\n"),
+        "yaml: {}",
+        yaml
+    );
+
+    let json = list(&repository, "json");
+    assert!(
+        json.starts_with("[\n  {\n    \"name\": \"bar\",\n"),
+        "json: {}",
+        json
+    );
+    assert!(
+        json.contains("\"file-name\": \"bar-1:2-1-any.pkg.tar.zst\""),
+        "json: {}",
+        json
+    );
+    assert!(json.ends_with("  }\n]\n"), "json: {}", json);
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "alpm")]
+
 use command_extra::CommandExtra;
 use pipe_trait::*;
 use std::{collections::BTreeSet, iter::FromIterator, path::PathBuf, process::Command};
@@ -109,6 +111,12 @@ test_order!(
     ]
 );
 
+test_order!(
+    provides_prefix,
+    |line| line.starts_with("provides-"),
+    vec!["provides-provider", "provides-consumer"]
+);
+
 #[test]
 fn stderr() {
     let (_, stderr, _) = output(init());
@@ -120,3 +128,37 @@ fn success() {
     let (_, _, success) = output(init());
     assert!(success, "process exit with success status");
 }
+
+#[test]
+fn profile() {
+    let (stdout, stderr, success) = init().with_arg("--profile").with_arg("levels").pipe(output);
+    eprintln!("    ==> command stderr\n{}", &stderr);
+    assert!(success, "process exit with success status");
+    let actual: BTreeSet<_> = collect(&stdout, |_| true);
+    let expected: BTreeSet<_> = [
+        "top-level-multi",
+        "middle-level-multi",
+        "bottom-level-multi",
+        "top-level-single",
+        "bottom-level-single",
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn undefined_profile() {
+    let output = init()
+        .with_arg("--profile")
+        .with_arg("undefined")
+        .output()
+        .expect("get output from a command");
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("profile \"undefined\" is not defined"),
+        "stderr: {}",
+        stderr,
+    );
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "alpm")]
+
 use command_extra::CommandExtra;
 use pacman_repo_builder::{manifest::OwnedBuildPacmanRepo, status::Code::SrcInfoOutOfSync};
 use pipe_trait::*;
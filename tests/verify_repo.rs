@@ -0,0 +1,171 @@
+#![cfg(feature = "alpm")]
+
+use command_extra::CommandExtra;
+use pacman_repo_builder::repository_database::{package_entry::PackageEntry, RepositoryDatabase};
+use pipe_trait::*;
+use std::{
+    fs::{copy, create_dir, remove_file, rename, write, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    process::Command,
+};
+use tempfile::tempdir;
+
+const EXE: &str = env!("CARGO_BIN_EXE_build-pacman-repo");
+const ROOT: &str = env!("CARGO_MANIFEST_DIR");
+
+/// Fixture directory and the name of the package file made from it.
+const PACKAGES: &[(&str, &str)] = &[
+    ("foo", "foo-1.0.0-1-x86_64.pkg.tar.zst"),
+    ("bar", "bar-1:2-1-any.pkg.tar.zst"),
+];
+
+fn setup(work_dir: &Path, sign_packages: bool) {
+    let repo = work_dir.join("repo");
+    create_dir(&repo).expect("create repository directory");
+    write(
+        work_dir.join("build-pacman-repo.yaml"),
+        format!(
+            "global-settings:\n  repository: repo/repo.db.tar.gz\n  sign-packages: {}\nmembers: []\n",
+            sign_packages,
+        ),
+    )
+    .expect("write manifest");
+
+    let fixtures = ROOT
+        .pipe(PathBuf::from)
+        .join("tests")
+        .join("fixtures")
+        .join("repository-database");
+    let mut database = RepositoryDatabase::new();
+    for (fixture, file_name) in PACKAGES {
+        let package_file = repo.join(file_name);
+        Command::new("bsdtar")
+            .with_arg("--create")
+            .with_arg("--zstd")
+            .with_arg("--file")
+            .with_arg(&package_file)
+            .with_arg("--directory")
+            .with_arg(fixtures.join(fixture))
+            .with_arg(".PKGINFO")
+            .with_arg("usr")
+            .status()
+            .expect("run bsdtar")
+            .success()
+            .pipe(|success| assert!(success, "bsdtar should succeed"));
+        database.add(&PackageEntry::from_package_file(&package_file).expect("read package file"));
+    }
+    database
+        .write(&repo.join("repo.db.tar.gz"), false, None)
+        .expect("write repository database");
+}
+
+fn verify_repo(work_dir: &Path) -> (Vec<String>, Option<i32>) {
+    let output = Command::new(EXE)
+        .with_current_dir(work_dir)
+        .with_arg("verify-repo")
+        .output()
+        .expect("run verify-repo");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    eprintln!("{}", stderr);
+    let inconsistencies = stderr
+        .lines()
+        .filter_map(|line| line.strip_prefix("⮾ "))
+        .map(ToString::to_string)
+        .collect();
+    (inconsistencies, output.status.code())
+}
+
+#[test]
+fn consistent() {
+    let work_dir = tempdir().expect("create temporary directory");
+    setup(work_dir.path(), false);
+    assert_eq!(verify_repo(work_dir.path()), (Vec::new(), Some(0)));
+}
+
+#[test]
+fn inconsistent() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    setup(work_dir, false);
+    let repo = work_dir.join("repo");
+
+    remove_file(repo.join("foo-1.0.0-1-x86_64.pkg.tar.zst")).expect("delete package file");
+    OpenOptions::new()
+        .append(true)
+        .open(repo.join("bar-1:2-1-any.pkg.tar.zst"))
+        .expect("open package file")
+        .write_all(b"garbage")
+        .expect("corrupt package file");
+    write(repo.join("bar-1:3-1-any.pkg.tar.zst"), "").expect("write package file");
+    write(repo.join("bar-1:1-1-any.pkg.tar.zst"), "").expect("write package file");
+    write(repo.join("bar-1:2-1-x86_64.pkg.tar.zst"), "").expect("write package file");
+    write(repo.join("bar-1:2-1-any.pkg.tar.xz"), "").expect("write package file");
+    remove_file(repo.join("repo.files.tar.gz")).expect("delete files database");
+
+    let (inconsistencies, code) = verify_repo(work_dir);
+    assert_eq!(code, Some(8));
+    let has = |expected: &str| {
+        inconsistencies
+            .iter()
+            .any(|inconsistency| inconsistency.contains(expected))
+    };
+    assert_eq!(inconsistencies.len(), 7, "{:#?}", inconsistencies);
+    assert!(has("repo.files.tar.gz\" does not exist"));
+    assert!(has(
+        "foo-1.0.0-1-x86_64.pkg.tar.zst\" of foo does not exist"
+    ));
+    assert!(has("bar-1:2-1-any.pkg.tar.zst\" has "));
+    assert!(has("SHA-256 of \"repo/bar-1:2-1-any.pkg.tar.zst\""));
+    assert!(has("bar-1:3-1-any.pkg.tar.zst\" is not in the database"));
+    assert!(has("bar-1:2-1-x86_64.pkg.tar.zst\" is not in the database"));
+    assert!(has("bar-1:2-1-any.pkg.tar.xz\" is not in the database"));
+}
+
+#[test]
+fn missing_signatures() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    setup(work_dir, true);
+    write(work_dir.join("repo/bar-1:2-1-any.pkg.tar.zst.sig"), "").expect("write signature");
+
+    let (inconsistencies, code) = verify_repo(work_dir);
+    assert_eq!(code, Some(8));
+    assert_eq!(
+        inconsistencies,
+        ["\"repo/foo-1.0.0-1-x86_64.pkg.tar.zst\" is not signed"],
+    );
+}
+
+#[test]
+fn out_of_sync() {
+    let work_dir = tempdir().expect("create temporary directory");
+    let work_dir = work_dir.path();
+    setup(work_dir, false);
+    let repo = work_dir.join("repo");
+
+    // Leave the files database with only foo, as if bar was added to the database alone.
+    copy(
+        repo.join("repo.db.tar.gz"),
+        work_dir.join("complete.db.tar.gz"),
+    )
+    .expect("back up");
+    let mut database =
+        RepositoryDatabase::load(&repo.join("repo.db.tar.gz")).expect("load repository database");
+    database.remove("bar");
+    database
+        .write(&repo.join("repo.db.tar.gz"), false, None)
+        .expect("write repository database");
+    rename(
+        work_dir.join("complete.db.tar.gz"),
+        repo.join("repo.db.tar.gz"),
+    )
+    .expect("restore");
+
+    let (inconsistencies, code) = verify_repo(work_dir);
+    assert_eq!(code, Some(8));
+    assert_eq!(
+        inconsistencies,
+        ["bar-1:2-1 is missing from the files database"],
+    );
+}